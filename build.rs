@@ -0,0 +1,8 @@
+fn main() {
+    #[cfg(feature = "protobuf")]
+    {
+        println!("cargo:rerun-if-changed=proto/feeds.proto");
+        let descriptors = protox::compile(["proto/feeds.proto"], ["proto"]).expect("Failed to parse feeds.proto");
+        prost_build::compile_fds(descriptors).expect("Failed to generate protobuf types");
+    }
+}
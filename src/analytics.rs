@@ -0,0 +1,371 @@
+//! Statistics over price series
+
+use serde::Serialize;
+
+/// Arithmetic mean, `None` for an empty series
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Population standard deviation, `None` for an empty series
+pub fn std_dev(values: &[f64]) -> Option<f64> {
+    let mean = mean(values)?;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    Some(variance.sqrt())
+}
+
+/// Standard score of `current` against `history`.
+///
+/// `None` when history is empty or has zero variance.
+pub fn z_score(current: f64, history: &[f64]) -> Option<f64> {
+    let mean = mean(history)?;
+    let std_dev = std_dev(history)?;
+    if std_dev == 0.0 {
+        return None;
+    }
+    Some((current - mean) / std_dev)
+}
+
+/// Simple moving average of the last `period` values.
+///
+/// `None` when `period` is zero or exceeds the series length.
+pub fn sma(prices: &[f64], period: usize) -> Option<f64> {
+    if period == 0 || prices.len() < period {
+        return None;
+    }
+    mean(&prices[prices.len() - period..])
+}
+
+/// Exponential moving average with `alpha = 2 / (period + 1)`, seeded with
+/// the first value and folded over the whole series.
+///
+/// `None` when `period` is zero or exceeds the series length.
+pub fn ema(prices: &[f64], period: usize) -> Option<f64> {
+    if period == 0 || prices.len() < period {
+        return None;
+    }
+    let alpha = 2.0 / (period as f64 + 1.0);
+    let (&first, rest) = prices.split_first()?;
+    Some(rest.iter().fold(first, |ema, &price| alpha * price + (1.0 - alpha) * ema))
+}
+
+/// Bollinger Bands `(upper, middle, lower)` over the last `period` values:
+/// the SMA plus and minus `std_devs` population standard deviations.
+///
+/// `None` when `period` is zero or exceeds the series length.
+pub fn bollinger_bands(prices: &[f64], period: usize, std_devs: f64) -> Option<(f64, f64, f64)> {
+    let middle = sma(prices, period)?;
+    let spread = std_devs * std_dev(&prices[prices.len() - period..])?;
+    Some((middle + spread, middle, middle - spread))
+}
+
+/// Fewest prices [`classify_regime`] will classify
+const MIN_REGIME_PRICES: usize = 10;
+
+/// Efficiency ratio above which a series counts as trending
+const TRENDING_EFFICIENCY: f64 = 0.5;
+
+/// Direction of a trend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendDirection {
+    Up,
+    Down,
+}
+
+/// Market regime of a price series
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "regime", rename_all = "lowercase")]
+pub enum Regime {
+    /// `strength` is the efficiency ratio in `(0.5, 1]`
+    Trending { direction: TrendDirection, strength: f64 },
+    /// `tightness` is in `(0, 1]`; 1 means the price did not move
+    Ranging { tightness: f64 },
+    Insufficient,
+}
+
+/// Classify a chronological price series as trending or ranging.
+///
+/// Uses the efficiency ratio `|sum of returns| / sum of |returns|`: near 1 when
+/// every move goes the same way, near 0 when moves cancel out. Ranging
+/// tightness is `1 / (1 + cv)`, with `cv` the price coefficient of variation
+/// in percent. Fewer than 10 prices is `Insufficient`.
+pub fn classify_regime(prices: &[f64]) -> Regime {
+    if prices.len() < MIN_REGIME_PRICES {
+        return Regime::Insufficient;
+    }
+    let returns: Vec<f64> = prices.windows(2).map(|w| w[1] / w[0] - 1.0).collect();
+    let net: f64 = returns.iter().sum();
+    let total: f64 = returns.iter().map(|r| r.abs()).sum();
+    let efficiency = if total > 0.0 { net.abs() / total } else { 0.0 };
+
+    if efficiency > TRENDING_EFFICIENCY {
+        let direction = if net > 0.0 { TrendDirection::Up } else { TrendDirection::Down };
+        return Regime::Trending { direction, strength: efficiency };
+    }
+    let (Some(mean), Some(std_dev)) = (mean(prices), std_dev(prices)) else {
+        return Regime::Insufficient;
+    };
+    let cv_pct = if mean != 0.0 { std_dev / mean.abs() * 100.0 } else { 0.0 };
+    Regime::Ranging { tightness: 1.0 / (1.0 + cv_pct) }
+}
+
+/// How unusual a Z-score is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZScoreLevel {
+    Normal,
+    Elevated,
+    Extreme,
+}
+
+impl ZScoreLevel {
+    /// `|z| < 2` normal, `< 3` elevated, otherwise extreme
+    pub fn from_z(z: f64) -> Self {
+        match z.abs() {
+            a if a < 2.0 => ZScoreLevel::Normal,
+            a if a < 3.0 => ZScoreLevel::Elevated,
+            _ => ZScoreLevel::Extreme,
+        }
+    }
+}
+
+/// Implied cross rates: `matrix[i][j] = prices[i] / prices[j]`
+pub fn cross_rate_matrix(prices: &[f64]) -> Vec<Vec<f64>> {
+    prices
+        .iter()
+        .map(|numerator| prices.iter().map(|denominator| numerator / denominator).collect())
+        .collect()
+}
+
+/// Open/high/low/close over a run of ticks
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct OhlcBar {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub tick_count: usize,
+}
+
+/// Folds chronologically ordered ticks into an [`OhlcBar`]
+#[derive(Debug, Default)]
+pub struct CandleAggregator {
+    bar: Option<OhlcBar>,
+}
+
+impl CandleAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the next tick
+    pub fn push(&mut self, price: f64) {
+        match self.bar.as_mut() {
+            Some(bar) => {
+                bar.high = bar.high.max(price);
+                bar.low = bar.low.min(price);
+                bar.close = price;
+                bar.tick_count += 1;
+            }
+            None => {
+                self.bar = Some(OhlcBar {
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    tick_count: 1,
+                })
+            }
+        }
+    }
+
+    /// The finished bar, `None` if no ticks were pushed
+    pub fn finish(self) -> Option<OhlcBar> {
+        self.bar
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mean_and_std_dev() {
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_eq!(mean(&values), Some(5.0));
+        assert_eq!(std_dev(&values), Some(2.0));
+    }
+
+    #[test]
+    fn test_empty_series() {
+        assert_eq!(mean(&[]), None);
+        assert_eq!(std_dev(&[]), None);
+        assert_eq!(z_score(1.0, &[]), None);
+    }
+
+    #[test]
+    fn test_z_score() {
+        let history = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_eq!(z_score(5.0, &history), Some(0.0));
+        assert_eq!(z_score(9.0, &history), Some(2.0));
+        assert_eq!(z_score(1.0, &history), Some(-2.0));
+    }
+
+    #[test]
+    fn test_z_score_zero_variance() {
+        assert_eq!(z_score(100.0, &[50.0; 40]), None);
+    }
+
+    #[test]
+    fn test_z_score_level_thresholds() {
+        assert_eq!(ZScoreLevel::from_z(0.0), ZScoreLevel::Normal);
+        assert_eq!(ZScoreLevel::from_z(-1.99), ZScoreLevel::Normal);
+        assert_eq!(ZScoreLevel::from_z(2.0), ZScoreLevel::Elevated);
+        assert_eq!(ZScoreLevel::from_z(-2.5), ZScoreLevel::Elevated);
+        assert_eq!(ZScoreLevel::from_z(3.0), ZScoreLevel::Extreme);
+        assert_eq!(ZScoreLevel::from_z(-10.0), ZScoreLevel::Extreme);
+    }
+
+    // === Moving average tests ===
+
+    #[test]
+    fn test_sma_uses_last_period_values() {
+        let prices = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(sma(&prices, 3), Some(4.0));
+        assert_eq!(sma(&prices, 5), Some(3.0));
+        assert_eq!(sma(&prices, 1), Some(5.0));
+    }
+
+    #[test]
+    fn test_ema_known_values() {
+        // alpha = 0.5: 1 -> 1.5 -> 2.25 -> 3.125 -> 4.0625
+        assert_eq!(ema(&[1.0, 2.0, 3.0, 4.0, 5.0], 3), Some(4.0625));
+        // alpha = 0.2 with period 9
+        let prices = [10.0, 20.0, 10.0, 20.0, 15.0, 9.8, 10.0, 10.0, 10.0];
+        assert!((ema(&prices, 9).unwrap() - 11.4639104).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_moving_averages_need_period_samples() {
+        assert_eq!(sma(&[1.0, 2.0], 3), None);
+        assert_eq!(ema(&[1.0, 2.0], 3), None);
+        assert_eq!(sma(&[1.0], 0), None);
+        assert_eq!(ema(&[1.0], 0), None);
+    }
+
+    // === Bollinger tests ===
+
+    #[test]
+    fn test_bollinger_bands_known_values() {
+        // last 8 values have mean 5 and std dev 2
+        let prices = [100.0, 2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_eq!(bollinger_bands(&prices, 8, 2.0), Some((9.0, 5.0, 1.0)));
+        assert_eq!(bollinger_bands(&prices, 8, 1.5), Some((8.0, 5.0, 2.0)));
+    }
+
+    #[test]
+    fn test_bollinger_bands_flat_series_collapse() {
+        assert_eq!(bollinger_bands(&[3.0; 5], 5, 2.0), Some((3.0, 3.0, 3.0)));
+    }
+
+    #[test]
+    fn test_bollinger_bands_need_period_samples() {
+        assert_eq!(bollinger_bands(&[1.0, 2.0], 3, 2.0), None);
+        assert_eq!(bollinger_bands(&[1.0, 2.0], 0, 2.0), None);
+    }
+
+    // === Regime tests ===
+
+    #[test]
+    fn test_monotonic_series_is_trending() {
+        let rising: Vec<f64> = (0..20).map(|i| 100.0 + i as f64).collect();
+        assert_eq!(classify_regime(&rising), Regime::Trending { direction: TrendDirection::Up, strength: 1.0 });
+
+        let falling: Vec<f64> = rising.iter().rev().copied().collect();
+        assert!(matches!(
+            classify_regime(&falling),
+            Regime::Trending { direction: TrendDirection::Down, strength } if strength == 1.0
+        ));
+    }
+
+    #[test]
+    fn test_stable_series_is_ranging() {
+        assert_eq!(classify_regime(&[100.0; 20]), Regime::Ranging { tightness: 1.0 });
+
+        let choppy: Vec<f64> = (0..20).map(|i| if i % 2 == 0 { 100.0 } else { 101.0 }).collect();
+        match classify_regime(&choppy) {
+            Regime::Ranging { tightness } => assert!(tightness > 0.5 && tightness < 1.0),
+            other => panic!("expected ranging, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_short_series_is_insufficient() {
+        assert_eq!(classify_regime(&[1.0, 2.0, 3.0]), Regime::Insufficient);
+    }
+
+    #[test]
+    fn test_regime_serialization() {
+        let json = serde_json::to_value(Regime::Trending { direction: TrendDirection::Up, strength: 0.8 }).unwrap();
+        assert_eq!(json, serde_json::json!({ "regime": "trending", "direction": "up", "strength": 0.8 }));
+        let json = serde_json::to_value(Regime::Insufficient).unwrap();
+        assert_eq!(json, serde_json::json!({ "regime": "insufficient" }));
+    }
+
+    // === Cross rate tests ===
+
+    #[test]
+    fn test_cross_rate_matrix_values() {
+        let matrix = cross_rate_matrix(&[100.0, 50.0, 25.0]);
+        assert_eq!(matrix[0], vec![1.0, 2.0, 4.0]);
+        assert_eq!(matrix[2], vec![0.25, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_cross_rate_matrix_reciprocal_and_transitive() {
+        let prices = [89846.94, 3245.5, 148.25, 0.37];
+        let matrix = cross_rate_matrix(&prices);
+        for i in 0..prices.len() {
+            assert_eq!(matrix[i][i], 1.0);
+            for j in 0..prices.len() {
+                assert!((matrix[i][j] * matrix[j][i] - 1.0).abs() < 1e-12);
+                for k in 0..prices.len() {
+                    assert!((matrix[i][j] * matrix[j][k] - matrix[i][k]).abs() / matrix[i][k] < 1e-12);
+                }
+            }
+        }
+    }
+
+    // === Candle tests ===
+
+    fn candle(prices: &[f64]) -> Option<OhlcBar> {
+        let mut aggregator = CandleAggregator::new();
+        prices.iter().for_each(|&p| aggregator.push(p));
+        aggregator.finish()
+    }
+
+    #[test]
+    fn test_candle_open_close_are_first_and_last() {
+        let bar = candle(&[100.0, 105.0, 95.0, 102.0]).unwrap();
+        assert_eq!(bar.open, 100.0);
+        assert_eq!(bar.close, 102.0);
+        assert_eq!(bar.high, 105.0);
+        assert_eq!(bar.low, 95.0);
+        assert_eq!(bar.tick_count, 4);
+    }
+
+    #[test]
+    fn test_candle_single_tick() {
+        let bar = candle(&[42.0]).unwrap();
+        assert_eq!((bar.open, bar.high, bar.low, bar.close), (42.0, 42.0, 42.0, 42.0));
+    }
+
+    #[test]
+    fn test_candle_empty() {
+        assert!(candle(&[]).is_none());
+    }
+}
@@ -4,9 +4,15 @@
 //! - SURGE_API_KEY: Required API key for authentication
 //! - SURGE_PORT: Server port (default: 9000)
 //! - SURGE_HOST: Server host (default: 0.0.0.0)
+//! - SURGE_PRELOAD_SYMBOLS: Comma-separated symbols fetched into price history at startup (default: BTC,ETH,SOL)
+//! - SURGE_RATE_LIMIT_RPM: Requests per minute per client on /v1/* (default: 60, 0 disables)
 //! - RUST_LOG: Log level filter (default: info)
+//!
+//! With the `otel` feature, spans are also exported over OTLP/gRPC:
+//! - OTEL_EXPORTER_OTLP_ENDPOINT: Collector endpoint (default: http://localhost:4317)
+//! - OTEL_SERVICE_NAME: Reported service name (default: i-am-surging)
 
-use i_am_surging::server::{app::ServerConfig, create_app, metrics::init_metrics};
+use i_am_surging::server::{app::ServerConfig, create_app, metrics::init_metrics, routes::AppState};
 use std::net::SocketAddr;
 use tokio::signal;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -14,13 +20,35 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 #[tokio::main]
 async fn main() {
     // Initialize tracing (JSON format for production)
-    tracing_subscriber::registry()
+    let registry = tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "info,tower_http=debug".into()),
         )
-        .with(tracing_subscriber::fmt::layer().json())
-        .init();
+        .with(tracing_subscriber::fmt::layer().json());
+
+    #[cfg(feature = "otel")]
+    let otel_provider = {
+        use i_am_surging::server::telemetry::{otel_layer, otlp_endpoint};
+
+        let service_name = std::env::var("OTEL_SERVICE_NAME")
+            .unwrap_or_else(|_| i_am_surging::SurgeConfig::default().service_name);
+        let endpoint = otlp_endpoint();
+        match otel_layer(&service_name, &endpoint) {
+            Ok((layer, provider)) => {
+                registry.with(layer).init();
+                tracing::info!("Exporting traces to {} as {}", endpoint, service_name);
+                Some(provider)
+            }
+            Err(e) => {
+                registry.init();
+                tracing::warn!("OpenTelemetry export disabled: {}", e);
+                None
+            }
+        }
+    };
+    #[cfg(not(feature = "otel"))]
+    registry.init();
 
     // Check for API key
     match std::env::var("SURGE_API_KEY") {
@@ -35,8 +63,22 @@ async fn main() {
     // Load configuration
     let config = ServerConfig::default();
 
+    // Build state and seed price history before accepting traffic
+    let state = match AppState::new() {
+        Ok(state) => state,
+        Err(e) => {
+            tracing::error!("Failed to create application state: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let preload = std::env::var("SURGE_PRELOAD_SYMBOLS").unwrap_or_else(|_| "BTC,ETH,SOL".to_string());
+    let preload: Vec<&str> = preload.split(',').map(str::trim).filter(|s| !s.is_empty()).collect();
+    if let Err(e) = state.preload_cache(&preload).await {
+        tracing::warn!("Price history preload failed: {}", e);
+    }
+
     // Build application
-    let app = match create_app() {
+    let app = match create_app(Some(state)) {
         Ok(app) => app,
         Err(e) => {
             tracing::error!("Failed to create application: {}", e);
@@ -59,11 +101,18 @@ async fn main() {
     tracing::info!("API docs: See API.md for endpoint documentation");
 
     // Run server with graceful shutdown
-    axum::serve(listener, app)
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .with_graceful_shutdown(shutdown_signal())
         .await
         .expect("Server error");
 
+    #[cfg(feature = "otel")]
+    if let Some(provider) = otel_provider {
+        if let Err(e) = provider.shutdown() {
+            tracing::warn!("Failed to flush traces: {}", e);
+        }
+    }
+
     tracing::info!("Server shutdown complete");
 }
 
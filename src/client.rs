@@ -1,75 +1,678 @@
+use futures_util::{future, stream, StreamExt};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::AbortHandle;
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+
 use crate::error::{Result, SurgeError};
 use crate::feed_loader::FeedLoader;
 use crate::normalize_symbol;
-use crate::types::FeedPrice;
+use crate::types::{CacheStats, FeedPrice, FeedPriceWithConfidence, HealthStatus, RetryPolicy, PRIORITY_BASES};
 
 const CROSSBAR_URL: &str = "https://crossbar.switchboard.xyz";
 
+/// How long a health check result is reused
+const HEALTH_CACHE_TTL: Duration = Duration::from_secs(10);
+const GATEWAY_PING_TIMEOUT: Duration = Duration::from_secs(2);
+const HEALTH_PRICE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Per-request limit on REST calls unless [`SurgeClient::with_request_timeout`] is used
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Results buffered by [`SurgeClient::watch_price`] before polling waits
+const WATCH_CHANNEL_CAPACITY: usize = 16;
+
+/// Check applied to every price before [`SurgeClient::get_price`] returns it
+type ResponseValidator = Arc<dyn Fn(&FeedPrice) -> Result<()> + Send + Sync>;
+
+/// Prices kept by [`SurgeClient::cached_get_price`], keyed by normalized symbol
+struct PriceCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (FeedPrice, Instant)>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl PriceCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    fn get(&self, symbol: &str) -> Option<FeedPrice> {
+        let entries = self.entries.lock().unwrap();
+        let fresh = entries
+            .get(symbol)
+            .filter(|(_, fetched_at)| fetched_at.elapsed() < self.ttl)
+            .map(|(price, _)| price.clone());
+        let counter = if fresh.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        fresh
+    }
+
+    fn insert(&self, price: FeedPrice) {
+        self.entries.lock().unwrap().insert(price.symbol.clone(), (price, Instant::now()));
+    }
+
+    fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.lock().unwrap().len(),
+        }
+    }
+}
+
+/// Where [`SurgeClient`] gets raw feed prices
+#[derive(Clone)]
+enum PriceSource {
+    /// The Crossbar simulate endpoint under this base URL
+    Real(Arc<str>),
+    /// Fixed prices keyed by feed ID, see [`SurgeClient::mock_with_prices`]
+    Mock(Arc<HashMap<String, f64>>),
+}
+
+impl PriceSource {
+    fn crossbar() -> Self {
+        PriceSource::Real(CROSSBAR_URL.into())
+    }
+
+    async fn fetch(&self, http: &reqwest::Client, feed_id: &str, request_timeout: Duration) -> Result<f64> {
+        self.fetch_samples(http, feed_id, request_timeout).await.map(|samples| samples.values[0])
+    }
+
+    /// Every sample reported for `feed_id`, never empty; mocks report one, without a timestamp
+    async fn fetch_samples(&self, http: &reqwest::Client, feed_id: &str, request_timeout: Duration) -> Result<FeedSamples> {
+        match self {
+            PriceSource::Real(base_url) => fetch_feed_samples(http, base_url, feed_id, request_timeout).await,
+            PriceSource::Mock(prices) => prices
+                .get(feed_id)
+                .map(|&price| FeedSamples { values: vec![price], timestamp: None })
+                .ok_or_else(|| SurgeError::ApiError(format!("No price data for feed {}", feed_id))),
+        }
+    }
+}
+
+/// Samples reported for a feed; the first is the price
+struct FeedSamples {
+    values: Vec<f64>,
+    /// Unix ms, when Crossbar includes one
+    timestamp: Option<i64>,
+}
+
+impl FeedSamples {
+    fn into_price(self, symbol: String, feed_id: String) -> FeedPrice {
+        FeedPrice { symbol, feed_id, value: self.values[0], timestamp: self.timestamp }
+    }
+}
+
 /// Switchboard Surge client for fetching cryptocurrency prices
 pub struct SurgeClient {
     http: reqwest::Client,
-    feeds: FeedLoader,
+    source: PriceSource,
+    /// Symbol registry, possibly shared with and reloaded by the caller
+    feeds: Arc<std::sync::RwLock<FeedLoader>>,
+    health: RwLock<Option<(Instant, HealthStatus)>>,
+    validator: Option<ResponseValidator>,
+    cache: Option<PriceCache>,
+    request_timeout: Duration,
+    #[cfg(feature = "streaming")]
+    api_key: Option<String>,
 }
 
 #[derive(serde::Deserialize)]
 struct SimulateResponse {
     results: Vec<String>,
+    #[serde(default)]
+    timestamp: Option<i64>,
 }
 
 impl SurgeClient {
-    /// Create a new Surge client
+    /// Create a new Surge client (honours `HTTPS_PROXY` / `HTTP_PROXY` if set)
     pub fn new() -> Result<Self> {
+        Ok(Self::from_parts(build_http_client(None, None)?, PriceSource::crossbar(), shared(default_feeds()?)))
+    }
+
+    /// Create a client that routes all requests through the given proxy
+    pub fn with_proxy(proxy_url: &str) -> Result<Self> {
+        Ok(Self::from_parts(build_http_client(Some(proxy_url), None)?, PriceSource::crossbar(), shared(default_feeds()?)))
+    }
+
+    /// Create a client that sends REST calls through `http`, e.g. one shared pool
+    /// with its own proxy, TLS roots or connection limits. Proxy environment
+    /// variables are not consulted; `http` is used as given.
+    pub fn with_http_client(http: reqwest::Client) -> Result<Self> {
+        Ok(Self::from_parts(http, PriceSource::crossbar(), shared(default_feeds()?)))
+    }
+
+    /// Create a client that looks symbols up in `feeds`, so a registry the caller
+    /// reloads (e.g. the server's) takes effect for price lookups too
+    pub fn with_feeds(feeds: Arc<std::sync::RwLock<FeedLoader>>) -> Result<Self> {
+        Ok(Self::from_parts(build_http_client(None, None)?, PriceSource::crossbar(), feeds))
+    }
+
+    /// Create a client whose [`Self::cached_get_price`] reuses prices younger than `ttl`
+    pub fn with_cache(ttl: Duration) -> Result<Self> {
         Ok(Self {
-            http: reqwest::Client::new(),
-            feeds: FeedLoader::load_default()?,
+            cache: Some(PriceCache::new(ttl)),
+            ..Self::new()?
         })
     }
 
+    /// Offline client for tests: knows only the symbols in `prices` ("btc" or
+    /// "BTC/USD") and always returns those values without any HTTP calls
+    pub fn mock_with_prices(prices: HashMap<&str, f64>) -> Self {
+        let mut symbols: Vec<(String, f64)> = prices.into_iter().map(|(s, p)| (normalize_symbol(s), p)).collect();
+        symbols.sort_by(|a, b| a.0.cmp(&b.0));
+        let feed_id = |i: usize| format!("{:064x}", i + 1);
+        let feeds = symbols.iter().enumerate().map(|(i, (symbol, _))| (symbol.clone(), feed_id(i))).collect();
+        let by_feed_id = symbols.into_iter().enumerate().map(|(i, (_, price))| (feed_id(i), price)).collect();
+        Self::from_parts(reqwest::Client::new(), PriceSource::Mock(Arc::new(by_feed_id)), shared(FeedLoader::from_feeds(feeds)))
+    }
+
+    fn from_parts(http: reqwest::Client, source: PriceSource, feeds: Arc<std::sync::RwLock<FeedLoader>>) -> Self {
+        Self {
+            http,
+            source,
+            feeds,
+            health: RwLock::new(None),
+            validator: None,
+            cache: None,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            #[cfg(feature = "streaming")]
+            api_key: None,
+        }
+    }
+
+    /// API key for [`Self::subscribe_websocket_events`] (defaults to `SURGE_API_KEY`)
+    #[cfg(feature = "streaming")]
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Send REST calls to the Crossbar at `url` (e.g. a self-hosted one) instead
+    /// of the public gateway. Mock clients ignore it.
+    pub fn with_crossbar_url(mut self, url: impl Into<String>) -> Self {
+        if let PriceSource::Real(_) = self.source {
+            self.source = PriceSource::Real(url.into().trim_end_matches('/').into());
+        }
+        self
+    }
+
+    /// Fail REST calls that take longer than `timeout` (default 10s)
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Reject prices that fail `validator`; replaces any previous validator
+    pub fn with_response_validator<F: Fn(&FeedPrice) -> Result<()> + Send + Sync + 'static>(mut self, validator: F) -> Self {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// Reject prices failing [`FeedPrice::default_sanity_validator`]
+    pub fn with_sanity_validation(self) -> Self {
+        self.with_response_validator(FeedPrice::default_sanity_validator())
+    }
+
+    fn feeds(&self) -> std::sync::RwLockReadGuard<'_, FeedLoader> {
+        self.feeds.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Feed ID for a normalized symbol, copied out so no lock is held across awaits
+    fn feed_id(&self, symbol: &str) -> Result<String> {
+        self.feeds().get_feed_id(symbol).map(str::to_owned)
+    }
+
     /// Get the latest price for a symbol (e.g., "BTC/USD" or "btc")
     pub async fn get_price(&self, symbol: &str) -> Result<FeedPrice> {
         let symbol = normalize_symbol(symbol);
-        let feed_id = self.feeds.get_feed_id(&symbol)?;
-        let price = self.fetch_price(feed_id).await?;
-        Ok(FeedPrice {
-            symbol,
-            feed_id: feed_id.to_string(),
-            value: price,
-        })
+        let feed_id = self.feed_id(&symbol)?;
+        let samples = self.source.fetch_samples(&self.http, &feed_id, self.request_timeout).await?;
+        let price = samples.into_price(symbol, feed_id);
+        self.validate(&price)?;
+        Ok(price)
     }
 
-    /// Get prices for multiple symbols
-    pub async fn get_multiple_prices(&self, symbols: &[&str]) -> Result<Vec<FeedPrice>> {
-        let mut prices = Vec::new();
-        for symbol in symbols {
+    /// [`Self::get_price`], failing with [`SurgeError::StalePrice`] when the price
+    /// is older than `max_age`. Prices without a timestamp count as fresh.
+    pub async fn get_fresh_price(&self, symbol: &str, max_age: Duration) -> Result<FeedPrice> {
+        let price = self.get_price(symbol).await?;
+        match price.age() {
+            Some(age) if age > max_age => Err(SurgeError::StalePrice {
+                symbol: price.symbol,
+                age_ms: age.as_millis() as u64,
+                max_age_ms: max_age.as_millis() as u64,
+            }),
+            _ => Ok(price),
+        }
+    }
+
+    /// [`Self::get_price`], retrying failures that `policy` treats as transient.
+    /// A rate limit's `Retry-After` is honoured when longer than the backoff delay.
+    pub async fn get_price_with_retries(&self, symbol: &str, policy: &RetryPolicy) -> Result<FeedPrice> {
+        let mut delay_ms = policy.initial_delay_ms;
+        let mut attempt = 1;
+        loop {
             match self.get_price(symbol).await {
+                Err(e) if attempt < policy.max_attempts && (policy.retryable)(&e) => {
+                    let wait_ms = match e {
+                        SurgeError::RateLimited { retry_after_secs: Some(secs) } => delay_ms.max(secs * 1000),
+                        _ => delay_ms,
+                    };
+                    tracing::debug!("Retrying {} in {}ms after attempt {}: {}", symbol, wait_ms, attempt, e);
+                    tokio::time::sleep(Duration::from_millis(wait_ms)).await;
+                    delay_ms = (delay_ms as f64 * policy.backoff_factor) as u64;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// [`Self::get_price_with_retries`] with [`RetryPolicy::default`]
+    pub async fn get_price_retry(&self, symbol: &str) -> Result<FeedPrice> {
+        self.get_price_with_retries(symbol, &RetryPolicy::default()).await
+    }
+
+    /// Like [`Self::get_price`], plus the standard deviation of the samples
+    /// Crossbar returned for the feed (0 when it returned one)
+    pub async fn get_price_with_confidence(&self, symbol: &str) -> Result<FeedPriceWithConfidence> {
+        let symbol = normalize_symbol(symbol);
+        let feed_id = self.feed_id(&symbol)?;
+        let samples = self.source.fetch_samples(&self.http, &feed_id, self.request_timeout).await?;
+        let values = samples.values.clone();
+        let price = samples.into_price(symbol, feed_id);
+        self.validate(&price)?;
+        Ok(FeedPriceWithConfidence::from_samples(price, &values))
+    }
+
+    /// Like [`Self::get_price`], but served from the cache while the entry is fresh.
+    ///
+    /// Without [`Self::with_cache`] this always fetches.
+    pub async fn cached_get_price(&self, symbol: &str) -> Result<FeedPrice> {
+        let Some(cache) = &self.cache else {
+            return self.get_price(symbol).await;
+        };
+        if let Some(price) = cache.get(&normalize_symbol(symbol)) {
+            return Ok(price);
+        }
+        let price = self.get_price(symbol).await?;
+        cache.insert(price.clone());
+        Ok(price)
+    }
+
+    /// Drop every cached price; hit/miss counters are kept
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.entries.lock().unwrap().clear();
+        }
+    }
+
+    /// Hit/miss counts for [`Self::cached_get_price`]; all zero without a cache
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.as_ref().map(PriceCache::stats).unwrap_or_default()
+    }
+
+    fn validate(&self, price: &FeedPrice) -> Result<()> {
+        run_validator(self.validator.as_ref(), price)
+    }
+
+    /// Fetch the raw price for a feed ID, bypassing the symbol registry
+    pub async fn get_price_by_feed_id(&self, feed_id: &str) -> Result<f64> {
+        self.fetch_price(feed_id).await
+    }
+
+    /// Get prices for multiple symbols, fetching them all concurrently
+    ///
+    /// Results keep the order of `symbols`; failed symbols are skipped,
+    /// except that being rate limited fails the whole call.
+    pub async fn get_multiple_prices(&self, symbols: &[&str]) -> Result<Vec<FeedPrice>> {
+        let results = future::join_all(symbols.iter().map(|symbol| self.get_price(symbol))).await;
+        let mut prices = Vec::with_capacity(results.len());
+        for result in results {
+            match result {
                 Ok(price) => prices.push(price),
+                Err(e @ SurgeError::RateLimited { .. }) => return Err(e),
                 Err(e) => eprintln!("Warning: {}", e),
             }
         }
         Ok(prices)
     }
 
+    /// Get prices for multiple symbols, running up to `concurrency` fetches at once.
+    ///
+    /// Results keep the order of `symbols`; failed symbols are skipped.
+    pub async fn get_multiple_prices_concurrent(&self, symbols: &[&str], concurrency: usize) -> Result<Vec<FeedPrice>> {
+        self.get_multiple_prices_concurrent_cancellable(symbols, concurrency, CancellationToken::new())
+            .await
+    }
+
+    /// Like [`Self::get_multiple_prices_concurrent`], but stops when `token` is cancelled.
+    ///
+    /// In-flight fetches are dropped and the prices fetched so far are returned
+    /// in [`SurgeError::Cancelled`].
+    pub async fn get_multiple_prices_concurrent_cancellable(
+        &self,
+        symbols: &[&str],
+        concurrency: usize,
+        token: CancellationToken,
+    ) -> Result<Vec<FeedPrice>> {
+        // Owned symbols keep the returned future `Send`, so axum handlers can await it
+        let owned: Vec<String> = symbols.iter().map(|symbol| symbol.to_string()).collect();
+        let mut fetches = stream::iter(owned)
+            .map(|symbol| async move { self.get_price(&symbol).await })
+            .buffered(concurrency.max(1));
+        let mut prices = Vec::with_capacity(symbols.len());
+
+        loop {
+            match token.run_until_cancelled(fetches.next()).await {
+                Some(Some(Ok(price))) => prices.push(price),
+                Some(Some(Err(e))) => tracing::warn!("Skipping price: {}", e),
+                Some(None) => return Ok(prices),
+                None => return Err(SurgeError::Cancelled(prices)),
+            }
+        }
+    }
+
+    /// [`Self::get_multiple_prices`] keyed by normalized symbol ("BTC/USD")
+    pub async fn get_prices_as_map(&self, symbols: &[&str]) -> Result<HashMap<String, FeedPrice>> {
+        Ok(index_by_symbol(self.get_multiple_prices(symbols).await?))
+    }
+
+    /// [`Self::get_multiple_prices_concurrent`] keyed by normalized symbol
+    pub async fn get_prices_as_map_concurrent(
+        &self,
+        symbols: &[&str],
+        concurrency: usize,
+    ) -> Result<HashMap<String, FeedPrice>> {
+        Ok(index_by_symbol(self.get_multiple_prices_concurrent(symbols, concurrency).await?))
+    }
+
+    /// Stream real-time updates over WebSocket using the same client API.
+    ///
+    /// Delegates to [`crate::Surge`] with the client's API key; the connection
+    /// lives as long as the stream.
+    #[cfg(feature = "streaming")]
+    pub async fn subscribe_websocket_events(
+        &self,
+        symbols: &[&str],
+    ) -> Result<impl futures_util::Stream<Item = Result<crate::SurgeUpdate>>> {
+        use crate::{Surge, SurgeEvent};
+        use tokio::sync::broadcast::error::RecvError;
+
+        let api_key = self.api_key.clone().or_else(|| std::env::var("SURGE_API_KEY").ok());
+        let mut surge = Surge::new(api_key.unwrap_or_default());
+        let events = surge.subscribe_events();
+        surge.connect_and_subscribe(symbols.to_vec()).await?;
+
+        let pending = std::collections::VecDeque::new();
+        Ok(futures_util::stream::unfold(
+            (surge, events, pending),
+            |(surge, mut events, mut pending)| async move {
+                loop {
+                    if let Some(update) = pending.pop_front() {
+                        return Some((Ok(update), (surge, events, pending)));
+                    }
+                    match events.recv().await {
+                        Ok(SurgeEvent::Error(e)) => {
+                            return Some((Err(SurgeError::ApiError(e)), (surge, events, pending)))
+                        }
+                        Ok(SurgeEvent::ReconnectFailed { reason, .. }) => {
+                            return Some((Err(SurgeError::ConnectionError(reason)), (surge, events, pending)))
+                        }
+                        Ok(event) => pending.extend(event.into_updates()),
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            },
+        ))
+    }
+
+    /// Poll prices as a `Stream`: one [`Self::get_price`] per `interval` tick,
+    /// cycling through `symbols` in order. Empty `symbols` gives an empty stream.
+    ///
+    /// This is REST polling; for real-time data prefer the [`crate::Surge`] streaming client.
+    pub fn stream<'a>(
+        &'a self,
+        symbols: Vec<&'a str>,
+        interval: Duration,
+    ) -> impl futures_util::Stream<Item = Result<FeedPrice>> + 'a {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let symbols = symbols.into_iter().cycle();
+        stream::unfold((ticker, symbols), move |(mut ticker, mut symbols)| async move {
+            let symbol = symbols.next()?;
+            ticker.tick().await;
+            Some((self.get_price(symbol).await, (ticker, symbols)))
+        })
+    }
+
+    /// Poll `symbol` every `interval` on a background task, sending each result.
+    ///
+    /// REST-only alternative to [`crate::Surge`]. Polling stops when the receiver
+    /// is dropped or the handle is aborted; an unknown symbol sends one
+    /// `FeedNotFound` and stops.
+    pub fn watch_price(&self, symbol: &str, interval: Duration) -> (mpsc::Receiver<Result<FeedPrice>>, AbortHandle) {
+        let (tx, rx) = mpsc::channel(WATCH_CHANNEL_CAPACITY);
+        let symbol = normalize_symbol(symbol);
+        let feed_id = self.feed_id(&symbol);
+        let http = self.http.clone();
+        let source = self.source.clone();
+        let validator = self.validator.clone();
+        let request_timeout = self.request_timeout;
+
+        let task = tokio::spawn(async move {
+            let feed_id = match feed_id {
+                Ok(feed_id) => feed_id,
+                Err(e) => {
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            };
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let result = source.fetch_samples(&http, &feed_id, request_timeout).await.and_then(|samples| {
+                    let price = samples.into_price(symbol.clone(), feed_id.clone());
+                    run_validator(validator.as_ref(), &price)?;
+                    Ok(price)
+                });
+                if tx.send(result).await.is_err() {
+                    return;
+                }
+            }
+        });
+        (rx, task.abort_handle())
+    }
+
     /// Check if a symbol is available
     pub fn has_symbol(&self, symbol: &str) -> bool {
         let symbol = normalize_symbol(symbol);
-        self.feeds.has_symbol(&symbol)
+        self.feeds().has_symbol(&symbol)
     }
 
     /// Get all available symbols
     pub fn get_all_symbols(&self) -> Vec<String> {
-        self.feeds.get_all_symbols()
+        self.feeds().get_all_symbols()
+    }
+
+    /// All symbols with major assets first (see [`PRIORITY_BASES`]), the rest alphabetically
+    pub fn list_symbols_prioritized(&self) -> Vec<String> {
+        prioritize_symbols(self.feeds().get_all_symbols())
+    }
+
+    /// Prioritized symbols quoted in `quote` (e.g. "USD" or "usdt")
+    pub fn list_symbols_by_quote(&self, quote: &str) -> Vec<String> {
+        let quote = quote.trim().to_uppercase();
+        let mut symbols = self.list_symbols_prioritized();
+        symbols.retain(|s| s.split_once('/').is_some_and(|(_, q)| q == quote));
+        symbols
+    }
+
+    /// Check the feed registry, gateway reachability and price latency.
+    ///
+    /// Results are cached for 10 seconds.
+    pub async fn health_check(&self) -> HealthStatus {
+        if let Some((checked_at, status)) = self.health.read().await.as_ref() {
+            if checked_at.elapsed() < HEALTH_CACHE_TTL {
+                return status.clone();
+            }
+        }
+
+        let status = self.run_health_check().await;
+        *self.health.write().await = Some((Instant::now(), status.clone()));
+        status
+    }
+
+    async fn run_health_check(&self) -> HealthStatus {
+        let symbol_count = self.feeds().len();
+        let mut error = (symbol_count == 0).then(|| "Feed registry is empty".to_string());
+
+        let gateway = match &self.source {
+            PriceSource::Real(base_url) => base_url,
+            PriceSource::Mock(_) => CROSSBAR_URL,
+        };
+        let gateway_reachable = match timeout(GATEWAY_PING_TIMEOUT, self.http.get(gateway).timeout(self.request_timeout).send()).await {
+            Ok(Ok(_)) => true,
+            Ok(Err(e)) => {
+                error.get_or_insert_with(|| format!("Gateway unreachable: {}", e));
+                false
+            }
+            Err(_) => {
+                error.get_or_insert_with(|| "Gateway ping timed out".to_string());
+                false
+            }
+        };
+
+        let started = Instant::now();
+        let latency_ms = match timeout(HEALTH_PRICE_TIMEOUT, self.get_price("BTC/USD")).await {
+            Ok(Ok(_)) => Some(started.elapsed().as_millis() as u64),
+            Ok(Err(e)) => {
+                error.get_or_insert_with(|| format!("Price check failed: {}", e));
+                None
+            }
+            Err(_) => {
+                error.get_or_insert_with(|| "Price check timed out".to_string());
+                None
+            }
+        };
+
+        HealthStatus {
+            feed_loader_ok: symbol_count > 0,
+            gateway_reachable,
+            latency_ms,
+            symbol_count,
+            error,
+        }
     }
 
     async fn fetch_price(&self, feed_id: &str) -> Result<f64> {
-        let url = format!("{}/simulate/{}", CROSSBAR_URL, feed_id);
-        let responses: Vec<SimulateResponse> = self.http.get(&url).send().await?.json().await?;
+        self.source.fetch(&self.http, feed_id, self.request_timeout).await
+    }
+}
 
-        responses
-            .first()
-            .and_then(|r| r.results.first())
-            .and_then(|price_str| price_str.parse().ok())
-            .ok_or_else(|| SurgeError::ApiError(format!("No price data for feed {}", feed_id)))
+fn index_by_symbol(prices: Vec<FeedPrice>) -> HashMap<String, FeedPrice> {
+    prices.into_iter().map(|price| (price.symbol.clone(), price)).collect()
+}
+
+/// Raw price samples for `feed_id` from the Crossbar simulate endpoint under
+/// `base_url`; the first is the reported price
+async fn fetch_feed_samples(http: &reqwest::Client, base_url: &str, feed_id: &str, request_timeout: Duration) -> Result<FeedSamples> {
+    let url = format!("{}/simulate/{}", base_url, feed_id);
+    let response = http.get(&url).timeout(request_timeout).send().await?;
+    if let Some(err) = SurgeError::from_rate_limit(&response) {
+        return Err(err);
+    }
+    let responses: Vec<SimulateResponse> = response.error_for_status()?.json().await?;
+
+    let Some(response) = responses.into_iter().next() else {
+        return Err(SurgeError::ApiError(format!("No price data for feed {}", feed_id)));
+    };
+    let values: Vec<f64> = response.results.iter().filter_map(|price_str| price_str.parse().ok()).collect();
+    if values.is_empty() {
+        return Err(SurgeError::ApiError(format!("No price data for feed {}", feed_id)));
     }
+    Ok(FeedSamples { values, timestamp: response.timestamp })
+}
+
+/// Apply `validator` if set, wrapping failures as `validation failed: ...`
+fn run_validator(validator: Option<&ResponseValidator>, price: &FeedPrice) -> Result<()> {
+    let Some(validator) = validator else {
+        return Ok(());
+    };
+    validator(price).map_err(|e| {
+        let message = match e {
+            SurgeError::ApiError(message) => message,
+            e => e.to_string(),
+        };
+        SurgeError::ApiError(format!("validation failed: {}", message))
+    })
+}
+
+fn shared(feeds: FeedLoader) -> Arc<std::sync::RwLock<FeedLoader>> {
+    Arc::new(std::sync::RwLock::new(feeds))
+}
+
+/// The default registry; with `strict-validation`, any malformed entry is an error
+fn default_feeds() -> Result<FeedLoader> {
+    let feeds = FeedLoader::load_default()?;
+    #[cfg(feature = "strict-validation")]
+    if !feeds.is_valid() {
+        return Err(SurgeError::InvalidFeedId(feeds.validate_all()));
+    }
+    Ok(feeds)
+}
+
+/// Build the HTTP client used for REST calls.
+///
+/// An explicit `proxy_url` wins; otherwise `HTTPS_PROXY` / `HTTP_PROXY` are read
+/// from the environment. Hosts in `no_proxy` bypass the proxy (falls back to `NO_PROXY`).
+pub(crate) fn build_http_client(proxy_url: Option<&str>, no_proxy: Option<&[String]>) -> Result<reqwest::Client> {
+    let no_proxy = match no_proxy {
+        Some(hosts) => reqwest::NoProxy::from_string(&hosts.join(",")),
+        None => reqwest::NoProxy::from_env(),
+    };
+
+    let mut builder = reqwest::Client::builder();
+    if let Some(url) = proxy_url {
+        builder = builder.proxy(reqwest::Proxy::all(url)?.no_proxy(no_proxy));
+    } else {
+        if let Some(url) = proxy_from_env(&["HTTPS_PROXY", "https_proxy"]) {
+            builder = builder.proxy(reqwest::Proxy::https(&url)?.no_proxy(no_proxy.clone()));
+        }
+        if let Some(url) = proxy_from_env(&["HTTP_PROXY", "http_proxy"]) {
+            builder = builder.proxy(reqwest::Proxy::http(&url)?.no_proxy(no_proxy));
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// Sort by base priority, then alphabetically
+fn prioritize_symbols(mut symbols: Vec<String>) -> Vec<String> {
+    symbols.sort_by_cached_key(|symbol| {
+        let base = symbol.split('/').next().unwrap_or(symbol);
+        let rank = PRIORITY_BASES.iter().position(|&b| b == base).unwrap_or(PRIORITY_BASES.len());
+        (rank, symbol.clone())
+    });
+    symbols
+}
+
+fn proxy_from_env(vars: &[&str]) -> Option<String> {
+    vars.iter()
+        .filter_map(|v| std::env::var(v).ok())
+        .find(|v| !v.is_empty())
 }
 
 impl Default for SurgeClient {
@@ -81,6 +684,7 @@ impl Default for SurgeClient {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::serve_delayed;
 
     #[test]
     fn test_client_new() {
@@ -94,6 +698,70 @@ mod tests {
         assert!(client.has_symbol("BTC/USD"));
     }
 
+    #[test]
+    fn test_client_with_proxy() {
+        let client = SurgeClient::with_proxy("http://127.0.0.1:3128");
+        assert!(client.is_ok(), "should create client with proxy");
+    }
+
+    #[test]
+    fn test_response_validator_rejects_price() {
+        let client = SurgeClient::new()
+            .unwrap()
+            .with_response_validator(|p| {
+                if p.value < 100.0 {
+                    Err(SurgeError::ApiError(format!("{} too low", p.symbol)))
+                } else {
+                    Ok(())
+                }
+            });
+        let price = |value| FeedPrice { symbol: "BTC/USD".to_string(), feed_id: String::new(), value, timestamp: None };
+
+        assert!(client.validate(&price(150.0)).is_ok());
+        let err = client.validate(&price(50.0)).unwrap_err();
+        assert_eq!(err.to_string(), "API error: validation failed: BTC/USD too low");
+    }
+
+    #[test]
+    fn test_with_http_client() {
+        let http = reqwest::Client::builder().pool_max_idle_per_host(1).build().unwrap();
+        let client = SurgeClient::with_http_client(http).unwrap();
+        assert!(client.has_symbol("BTC/USD"));
+    }
+
+    #[test]
+    fn test_request_timeout() {
+        let client = SurgeClient::mock_with_prices(HashMap::new());
+        assert_eq!(client.request_timeout, DEFAULT_REQUEST_TIMEOUT);
+        let client = client.with_request_timeout(Duration::from_millis(250));
+        assert_eq!(client.request_timeout, Duration::from_millis(250));
+    }
+
+    #[cfg(feature = "streaming")]
+    #[test]
+    fn test_with_api_key() {
+        let client = SurgeClient::mock_with_prices(HashMap::new());
+        assert_eq!(client.api_key, None);
+        assert_eq!(client.with_api_key("sk_test").api_key.as_deref(), Some("sk_test"));
+    }
+
+    #[test]
+    fn test_sanity_validation() {
+        let client = SurgeClient::new().unwrap().with_sanity_validation();
+        let price = |value| FeedPrice { symbol: "BTC/USD".to_string(), feed_id: String::new(), value, timestamp: None };
+
+        assert!(client.validate(&price(90000.0)).is_ok());
+        assert!(client.validate(&price(0.0)).is_err());
+        assert!(SurgeClient::new().unwrap().validate(&price(0.0)).is_ok());
+    }
+
+    #[test]
+    fn test_build_http_client_with_no_proxy() {
+        let hosts = vec!["localhost".to_string(), ".internal".to_string()];
+        let http = build_http_client(Some("http://127.0.0.1:3128"), Some(&hosts));
+        assert!(http.is_ok());
+    }
+
     #[test]
     fn test_client_has_symbol_with_shortcuts() {
         let client = SurgeClient::new().unwrap();
@@ -119,4 +787,319 @@ mod tests {
         assert!(symbols.contains(&"ETH/USD".to_string()));
         assert!(symbols.contains(&"SOL/USD".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_concurrent_fetch_skips_unknown_symbols() {
+        let client = SurgeClient::new().unwrap();
+        let prices = client
+            .get_multiple_prices_concurrent(&["NOTREAL", "FAKE/COIN"], 4)
+            .await
+            .unwrap();
+        assert!(prices.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_multiple_prices_is_concurrent() {
+        const DELAY: Duration = Duration::from_millis(200);
+        let body = r#"[{"results":["100.5"]}]"#;
+        let response = format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", body.len(), body);
+        let url = serve_delayed(response, DELAY).await;
+        let symbols = ["AAA/USD", "BBB/USD", "CCC/USD", "DDD/USD", "EEE/USD"];
+        let feeds = symbols.iter().enumerate().map(|(i, s)| (s.to_string(), format!("{:064x}", i))).collect();
+        let http = reqwest::Client::builder().no_proxy().build().unwrap();
+        let client = SurgeClient::from_parts(http, PriceSource::crossbar(), shared(FeedLoader::from_feeds(feeds)))
+            .with_crossbar_url(url);
+
+        let start = Instant::now();
+        let prices = client.get_multiple_prices(&symbols).await.unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(prices.len(), 5);
+        assert!(prices.iter().all(|p| p.value == 100.5));
+        assert!(elapsed < DELAY * 3, "five fetches took {:?}, expected about {:?}", elapsed, DELAY);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_fetch_cancelled_before_start() {
+        let client = SurgeClient::new().unwrap();
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let err = client
+            .get_multiple_prices_concurrent_cancellable(&["btc", "eth", "sol"], 2, token)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SurgeError::Cancelled(ref partial) if partial.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_fetch_cancel_stops_in_flight_requests() {
+        // Accept connections but never answer, so fetches hang until cancelled
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let client = SurgeClient::with_proxy(&proxy).unwrap();
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            canceller.cancel();
+        });
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            client.get_multiple_prices_concurrent_cancellable(&["btc", "eth"], 2, token),
+        )
+        .await
+        .expect("cancellation should stop the hanging fetches");
+        assert!(matches!(result, Err(SurgeError::Cancelled(_))));
+    }
+
+    #[tokio::test]
+    async fn test_health_check_unreachable_gateway() {
+        // Refuse connections so both the ping and the price check fail fast
+        let client = SurgeClient::with_proxy("http://127.0.0.1:1").unwrap();
+        let status = client.health_check().await;
+
+        assert!(status.feed_loader_ok);
+        assert!(status.symbol_count > 2000);
+        assert!(!status.gateway_reachable);
+        assert!(status.latency_ms.is_none());
+        assert!(status.error.unwrap().starts_with("Gateway unreachable"));
+    }
+
+    #[tokio::test]
+    async fn test_health_check_is_cached() {
+        let client = SurgeClient::with_proxy("http://127.0.0.1:1").unwrap();
+        let cached = HealthStatus {
+            feed_loader_ok: true,
+            gateway_reachable: true,
+            latency_ms: Some(42),
+            symbol_count: 1,
+            error: None,
+        };
+        *client.health.write().await = Some((Instant::now(), cached));
+
+        assert_eq!(client.health_check().await.latency_ms, Some(42));
+    }
+
+    fn cached_btc() -> FeedPrice {
+        FeedPrice {
+            symbol: "BTC/USD".to_string(),
+            feed_id: "feed".to_string(),
+            value: 50000.0,
+            timestamp: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_get_price_hit() {
+        let client = SurgeClient::with_cache(Duration::from_secs(60)).unwrap();
+        client.cache.as_ref().unwrap().insert(cached_btc());
+
+        let price = client.cached_get_price("btc").await.unwrap();
+        assert_eq!(price.value, 50000.0);
+        assert_eq!(client.cache_stats(), CacheStats { hits: 1, misses: 0, entries: 1 });
+    }
+
+    #[tokio::test]
+    async fn test_cached_get_price_expired_is_miss() {
+        let mut client = SurgeClient::with_proxy("http://127.0.0.1:1").unwrap();
+        client.cache = Some(PriceCache::new(Duration::ZERO));
+        client.cache.as_ref().unwrap().insert(cached_btc());
+
+        assert!(client.cached_get_price("BTC/USD").await.is_err());
+        assert_eq!(client.cache_stats().misses, 1);
+        assert_eq!(client.cache_stats().hits, 0);
+    }
+
+    #[test]
+    fn test_clear_cache() {
+        let client = SurgeClient::with_cache(Duration::from_secs(60)).unwrap();
+        client.cache.as_ref().unwrap().insert(cached_btc());
+        client.clear_cache();
+        assert_eq!(client.cache_stats().entries, 0);
+    }
+
+    #[test]
+    fn test_cache_stats_without_cache() {
+        let client = SurgeClient::new().unwrap();
+        assert_eq!(client.cache_stats(), CacheStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_watch_price_unknown_symbol_stops() {
+        let client = SurgeClient::new().unwrap();
+        let (mut rx, _handle) = client.watch_price("notacoin123", Duration::from_millis(1));
+        assert!(matches!(rx.recv().await, Some(Err(SurgeError::FeedNotFound(..)))));
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_watch_price_abort_stops_polling() {
+        let client = SurgeClient::with_proxy("http://127.0.0.1:1").unwrap();
+        let (mut rx, handle) = client.watch_price("btc", Duration::from_millis(10));
+        assert!(rx.recv().await.unwrap().is_err());
+
+        handle.abort();
+        let drained = timeout(Duration::from_secs(2), async { while rx.recv().await.is_some() {} }).await;
+        assert!(drained.is_ok(), "channel should close after abort");
+    }
+
+    fn mock_client() -> SurgeClient {
+        SurgeClient::mock_with_prices(HashMap::from([("btc", 50000.0), ("ETH/USD", 3000.0)]))
+    }
+
+    #[tokio::test]
+    async fn test_mock_with_prices_serves_fixed_prices() {
+        let client = mock_client();
+        assert_eq!(client.get_price("BTC").await.unwrap().value, 50000.0);
+
+        let prices = client.get_prices_as_map(&["btc", "eth", "sol"]).await.unwrap();
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices["ETH/USD"].value, 3000.0);
+        assert!(matches!(client.get_price("sol").await, Err(SurgeError::FeedNotFound(..))));
+        assert_eq!(client.get_all_symbols(), vec!["BTC/USD", "ETH/USD"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_price_with_retries() {
+        // Validation failures stand in for transient upstream errors
+        let calls = Arc::new(AtomicU64::new(0));
+        let counted = calls.clone();
+        let client = mock_client().with_response_validator(move |_| match counted.fetch_add(1, Ordering::SeqCst) {
+            0 | 1 => Err(SurgeError::ApiError("flaky".to_string())),
+            _ => Ok(()),
+        });
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_delay_ms: 1,
+            backoff_factor: 2.0,
+            retryable: |e| matches!(e, SurgeError::ApiError(_)),
+        };
+
+        assert_eq!(client.get_price_with_retries("btc", &policy).await.unwrap().value, 50000.0);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        calls.store(0, Ordering::SeqCst);
+        let once = RetryPolicy { max_attempts: 2, ..policy };
+        assert!(client.get_price_with_retries("btc", &once).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let never = RetryPolicy { retryable: |_| false, ..policy };
+        calls.store(0, Ordering::SeqCst);
+        assert!(client.get_price_with_retries("btc", &never).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_default_retry_policy() {
+        let policy = RetryPolicy::default();
+        assert_eq!((policy.max_attempts, policy.initial_delay_ms, policy.backoff_factor), (3, 500, 2.0));
+        assert!((policy.retryable)(&SurgeError::ConnectionError("reset".to_string())));
+        assert!(!(policy.retryable)(&SurgeError::FeedNotFound("X/USD".to_string(), Vec::new())));
+    }
+
+    #[tokio::test]
+    async fn test_get_fresh_price_treats_unknown_age_as_fresh() {
+        let price = mock_client().get_fresh_price("btc", Duration::ZERO).await.unwrap();
+        assert_eq!((price.value, price.timestamp), (50000.0, None));
+
+        let with_ts: SimulateResponse = serde_json::from_str(r#"{"results":["1.5"],"timestamp":1705936800000}"#).unwrap();
+        assert_eq!(with_ts.timestamp, Some(1705936800000));
+        let without_ts: SimulateResponse = serde_json::from_str(r#"{"results":["1.5"]}"#).unwrap();
+        assert_eq!(without_ts.timestamp, None);
+
+        let stale = SurgeError::StalePrice { symbol: "BTC/USD".to_string(), age_ms: 90_000, max_age_ms: 60_000 };
+        assert_eq!(stale.to_string(), "Stale price for BTC/USD: 90000ms old, max 60000ms");
+    }
+
+    #[tokio::test]
+    async fn test_mock_price_with_confidence() {
+        let priced = mock_client().get_price_with_confidence("btc").await.unwrap();
+        assert_eq!(priced.price.value, 50000.0);
+        assert_eq!(priced.confidence, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_mock_with_prices_watch_price() {
+        let client = mock_client().with_response_validator(|p| {
+            if p.value > 10000.0 {
+                Err(SurgeError::ApiError("too high".to_string()))
+            } else {
+                Ok(())
+            }
+        });
+        let (mut rx, handle) = client.watch_price("eth", Duration::from_millis(1));
+        assert_eq!(rx.recv().await.unwrap().unwrap().value, 3000.0);
+        handle.abort();
+
+        let (mut rx, handle) = client.watch_price("btc", Duration::from_millis(1));
+        assert!(rx.recv().await.unwrap().is_err());
+        handle.abort();
+    }
+
+    #[test]
+    fn test_index_by_symbol() {
+        let map = index_by_symbol(vec![cached_btc(), FeedPrice { symbol: "ETH/USD".to_string(), ..cached_btc() }]);
+        let mut keys: Vec<&str> = map.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, vec!["BTC/USD", "ETH/USD"]);
+        assert_eq!(map["ETH/USD"].symbol, "ETH/USD");
+    }
+
+    #[tokio::test]
+    async fn test_stream_round_robins_symbols() {
+        let client = SurgeClient::new().unwrap();
+        let errors: Vec<String> = client
+            .stream(vec!["NOPE1", "NOPE2"], Duration::from_millis(1))
+            .take(3)
+            .map(|result| match result {
+                Err(SurgeError::FeedNotFound(symbol, _)) => symbol,
+                other => panic!("expected FeedNotFound, got {:?}", other),
+            })
+            .collect()
+            .await;
+        assert_eq!(errors, vec!["NOPE1/USD", "NOPE2/USD", "NOPE1/USD"]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_empty_symbols_ends() {
+        let client = SurgeClient::new().unwrap();
+        assert_eq!(client.stream(vec![], Duration::from_millis(1)).count().await, 0);
+    }
+
+    #[test]
+    fn test_prioritize_symbols() {
+        let symbols = ["AAVE/USD", "SOL/USD", "BTC/USDT", "ZRX/USD", "BTC/USD", "ETH/USD"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(
+            prioritize_symbols(symbols),
+            vec!["BTC/USD", "BTC/USDT", "ETH/USD", "SOL/USD", "AAVE/USD", "ZRX/USD"]
+        );
+    }
+
+    #[test]
+    fn test_client_list_symbols_prioritized() {
+        let client = SurgeClient::new().unwrap();
+        let symbols = client.list_symbols_prioritized();
+        assert_eq!(symbols.len(), client.get_all_symbols().len());
+        assert_eq!(symbols[0], "BTC/USD");
+    }
+
+    #[test]
+    fn test_client_list_symbols_by_quote() {
+        let client = SurgeClient::new().unwrap();
+        let symbols = client.list_symbols_by_quote("usdt");
+        assert!(!symbols.is_empty());
+        assert!(symbols.iter().all(|s| s.ends_with("/USDT")));
+        assert_eq!(symbols[0], "BTC/USDT");
+    }
 }
@@ -5,10 +5,22 @@ use axum::{
 };
 use thiserror::Error;
 
+use crate::feed_loader::ValidationError;
+use crate::types::FeedPrice;
+
 #[derive(Debug, Error)]
 pub enum SurgeError {
     #[error("HTTP error: {0}")]
-    HttpError(#[from] reqwest::Error),
+    HttpError(reqwest::Error),
+
+    #[error("Rate limited by upstream{}", .retry_after_secs.map(|s| format!(", retry after {}s", s)).unwrap_or_default())]
+    RateLimited { retry_after_secs: Option<u64> },
+
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+
+    #[error("Connection error: {0}")]
+    ConnectionError(String),
 
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
@@ -16,22 +28,113 @@ pub enum SurgeError {
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
-    #[error("Feed not found: {0}")]
-    FeedNotFound(String),
+    /// Unknown symbol, with close registry matches when there are any
+    #[error("Feed not found for '{0}'{}", did_you_mean(.1))]
+    FeedNotFound(String, Vec<String>),
 
     #[error("API error: {0}")]
     ApiError(String),
+
+    #[error("FX error: {0}")]
+    FxError(String),
+
+    #[error("Invalid feed IDs: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    InvalidFeedId(Vec<ValidationError>),
+
+    #[cfg(feature = "protobuf")]
+    #[error("Protobuf decode error: {0}")]
+    ProtobufError(#[from] prost::DecodeError),
+
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+
+    #[error("Invalid API key: {0}")]
+    InvalidApiKey(String),
+
+    #[error("Cancelled after fetching {} prices", .0.len())]
+    Cancelled(Vec<FeedPrice>),
+
+    #[error("Conflicting feed IDs for: {}", .0.join(", "))]
+    FeedConflict(Vec<String>),
+
+    #[error("Stale price for {symbol}: {age_ms}ms old, max {max_age_ms}ms")]
+    StalePrice { symbol: String, age_ms: u64, max_age_ms: u64 },
 }
 
 impl SurgeError {
     /// Map error to HTTP status code
     pub fn status_code(&self) -> StatusCode {
         match self {
-            SurgeError::FeedNotFound(_) => StatusCode::NOT_FOUND,
+            SurgeError::FeedNotFound(..) => StatusCode::NOT_FOUND,
             SurgeError::ApiError(_) => StatusCode::BAD_GATEWAY,
+            SurgeError::FxError(_) => StatusCode::BAD_GATEWAY,
             SurgeError::HttpError(_) => StatusCode::BAD_GATEWAY,
+            SurgeError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            SurgeError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            SurgeError::ConnectionError(_) => StatusCode::BAD_GATEWAY,
             SurgeError::JsonError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             SurgeError::IoError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SurgeError::InvalidFeedId(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SurgeError::InvalidConfig(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SurgeError::InvalidApiKey(_) => StatusCode::UNAUTHORIZED,
+            SurgeError::Cancelled(_) => StatusCode::SERVICE_UNAVAILABLE,
+            SurgeError::FeedConflict(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SurgeError::StalePrice { .. } => StatusCode::BAD_GATEWAY,
+            #[cfg(feature = "protobuf")]
+            SurgeError::ProtobufError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Whether the same request may succeed if retried later
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            SurgeError::RateLimited { .. } | SurgeError::Timeout(_) | SurgeError::ConnectionError(_)
+        )
+    }
+
+    /// `RateLimited` for a 429 response, with `Retry-After` (in seconds) if present
+    pub(crate) fn from_rate_limit(response: &reqwest::Response) -> Option<Self> {
+        if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return None;
+        }
+        let retry_after_secs = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok());
+        Some(SurgeError::RateLimited { retry_after_secs })
+    }
+}
+
+/// `". Did you mean: A, B?"` suffix for [`SurgeError::FeedNotFound`]; empty without suggestions
+fn did_you_mean(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!(". Did you mean: {}?", suggestions.join(", "))
+    }
+}
+
+impl From<reqwest::Error> for SurgeError {
+    /// Keep the upstream status: 429 and 5xx, timeouts and connection
+    /// failures get their own variants; anything else stays an `HttpError`
+    fn from(err: reqwest::Error) -> Self {
+        match err.status() {
+            Some(status) if status == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                return SurgeError::RateLimited { retry_after_secs: None }
+            }
+            Some(status) if status.is_server_error() => {
+                return SurgeError::ApiError(format!("Upstream returned {}", status))
+            }
+            _ => {}
+        }
+        if err.is_timeout() {
+            SurgeError::Timeout(err.to_string())
+        } else if err.is_connect() {
+            SurgeError::ConnectionError(err.to_string())
+        } else {
+            SurgeError::HttpError(err)
         }
     }
 }
@@ -48,3 +151,101 @@ impl IntoResponse for SurgeError {
 }
 
 pub type Result<T> = std::result::Result<T, SurgeError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::serve_once;
+    use std::time::Duration;
+
+    async fn get_error(url: &str) -> SurgeError {
+        let client = reqwest::Client::builder().timeout(Duration::from_millis(200)).build().unwrap();
+        let err = match client.get(url).send().await {
+            Ok(response) => response.error_for_status().unwrap_err(),
+            Err(err) => err,
+        };
+        SurgeError::from(err)
+    }
+
+    #[tokio::test]
+    async fn test_429_maps_to_rate_limited() {
+        let url = serve_once("HTTP/1.1 429 Too Many Requests\r\ncontent-length: 0\r\n\r\n").await;
+        let err = get_error(&url).await;
+        assert!(matches!(err, SurgeError::RateLimited { retry_after_secs: None }));
+        assert_eq!(err.status_code(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_5xx_maps_to_api_error() {
+        let url = serve_once("HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\n\r\n").await;
+        match get_error(&url).await {
+            SurgeError::ApiError(message) => assert!(message.contains("503")),
+            other => panic!("expected ApiError, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_4xx_stays_http_error() {
+        let url = serve_once("HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\n\r\n").await;
+        assert!(matches!(get_error(&url).await, SurgeError::HttpError(_)));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_maps_to_timeout() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let err = get_error(&url).await;
+        assert!(matches!(err, SurgeError::Timeout(_)), "got {:?}", err);
+        assert_eq!(err.status_code(), StatusCode::GATEWAY_TIMEOUT);
+        drop(listener);
+    }
+
+    #[tokio::test]
+    async fn test_refused_connection_maps_to_connection_error() {
+        let addr = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let err = get_error(&format!("http://{}", addr)).await;
+        assert!(matches!(err, SurgeError::ConnectionError(_)), "got {:?}", err);
+    }
+
+    async fn get_response(url: &str) -> reqwest::Response {
+        reqwest::get(url).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_from_rate_limit_parses_retry_after() {
+        let url = serve_once("HTTP/1.1 429 Too Many Requests\r\nretry-after: 30\r\ncontent-length: 0\r\n\r\n").await;
+        let err = SurgeError::from_rate_limit(&get_response(&url).await);
+        assert!(matches!(err, Some(SurgeError::RateLimited { retry_after_secs: Some(30) })));
+    }
+
+    #[tokio::test]
+    async fn test_from_rate_limit_without_retry_after() {
+        let url = serve_once("HTTP/1.1 429 Too Many Requests\r\ncontent-length: 0\r\n\r\n").await;
+        let err = SurgeError::from_rate_limit(&get_response(&url).await);
+        assert!(matches!(err, Some(SurgeError::RateLimited { retry_after_secs: None })));
+    }
+
+    #[tokio::test]
+    async fn test_from_rate_limit_ignores_other_statuses() {
+        let url = serve_once("HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n").await;
+        assert!(SurgeError::from_rate_limit(&get_response(&url).await).is_none());
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(SurgeError::RateLimited { retry_after_secs: None }.is_retryable());
+        assert!(SurgeError::ConnectionError("refused".to_string()).is_retryable());
+        assert!(SurgeError::Timeout("slow".to_string()).is_retryable());
+        assert!(!SurgeError::FeedNotFound("BTC/USD".to_string(), Vec::new()).is_retryable());
+        assert!(!SurgeError::InvalidConfig("bad".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn test_rate_limited_display() {
+        assert_eq!(SurgeError::RateLimited { retry_after_secs: None }.to_string(), "Rate limited by upstream");
+        assert_eq!(
+            SurgeError::RateLimited { retry_after_secs: Some(30) }.to_string(),
+            "Rate limited by upstream, retry after 30s"
+        );
+    }
+}
@@ -1,12 +1,115 @@
-use std::collections::HashMap;
+use once_cell::sync::OnceCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 
 use crate::error::{Result, SurgeError};
+use crate::normalize_symbol;
+
+#[cfg(feature = "protobuf")]
+mod proto {
+    include!(concat!(env!("OUT_DIR"), "/surge.feeds.rs"));
+}
+
+/// Expected length of a hex-encoded feed ID
+const FEED_ID_LEN: usize = 64;
+
+/// Lowest similarity [`FeedLoader::fuzzy_search`] reports
+const FUZZY_MIN_SCORE: f64 = 0.6;
+
+/// Suggestions carried by [`SurgeError::FeedNotFound`]
+const NOT_FOUND_SUGGESTIONS: usize = 3;
+
+/// Quote currencies a bare ticker expands to, most preferred first;
+/// any other quote is picked alphabetically after these
+const QUOTE_PRIORITY: [&str; 3] = ["USD", "USDT", "USDC"];
+
+/// Why a stored feed ID is malformed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FeedValidationError {
+    #[error("expected 64 characters, got {0}")]
+    WrongLength(usize),
+
+    #[error("non-hex characters: {0}")]
+    InvalidChars(String),
+
+    #[error("symbol must be BASE/QUOTE")]
+    MalformedSymbol,
+}
+
+/// A registry entry rejected by [`FeedLoader::validate_all`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{symbol} ({reason})")]
+pub struct ValidationError {
+    pub symbol: String,
+    pub feed_id: String,
+    /// Rendered [`FeedValidationError`]
+    pub reason: String,
+}
+
+/// How [`FeedLoader::merge`] resolves a symbol mapped to different feed IDs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Fail with [`SurgeError::FeedConflict`] listing every conflicting symbol
+    ErrorOnConflict,
+    PreferSelf,
+    PreferOther,
+}
+
+/// Which loader a merged feed ID came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeSource {
+    /// The loader `merge` was called on
+    SelfLoader,
+    /// The loader passed to `merge`
+    Other,
+}
+
+/// One conflict resolved during a merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergePrecedenceEntry {
+    pub symbol: String,
+    pub winning_feed_id: String,
+    pub losing_feed_id: String,
+    pub source: MergeSource,
+}
+
+/// Audit log of the conflicts resolved by [`FeedLoader::merge`], sorted by symbol
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergePrecedenceLog {
+    pub entries: Vec<MergePrecedenceEntry>,
+}
+
+impl MergePrecedenceLog {
+    /// Render as a diff: `-` the discarded feed ID, `+` the kept one
+    pub fn dump_log(&self) -> String {
+        self.entries
+            .iter()
+            .map(|e| {
+                let source = match e.source {
+                    MergeSource::SelfLoader => "self",
+                    MergeSource::Other => "other",
+                };
+                format!("{} (kept {})\n- {}\n+ {}\n", e.symbol, source, e.losing_feed_id, e.winning_feed_id)
+            })
+            .collect()
+    }
+}
+
+/// Header row of [`FeedLoader::to_csv`]
+const CSV_HEADER: [&str; 2] = ["symbol", "feed_id"];
+
+/// Process-wide feed map leaked by [`FeedLoader::into_static`]
+static STATIC_FEEDS: OnceCell<&'static HashMap<String, String>> = OnceCell::new();
 
 /// Loads feed IDs from feedIds.json file
 pub struct FeedLoader {
     feeds: HashMap<String, String>,
+    /// File the feeds were read from, used by [`FeedLoader::reload`]
+    path: Option<PathBuf>,
+    /// Feed ID → symbol, built on first [`FeedLoader::find_by_feed_id`]
+    by_feed_id: OnceCell<HashMap<String, String>>,
 }
 
 impl FeedLoader {
@@ -26,17 +129,391 @@ impl FeedLoader {
 
     /// Load feeds from a specific path
     pub fn load_from_path(path: impl AsRef<Path>) -> Result<Self> {
-        let contents = fs::read_to_string(path)?;
-        let feeds: HashMap<String, String> = serde_json::from_str(&contents)?;
-        Ok(Self { feeds })
+        let file = fs::File::open(&path)?;
+        let mut loader = Self::from_reader(std::io::BufReader::new(file))?;
+        loader.path = Some(path.as_ref().to_path_buf());
+        Ok(loader)
+    }
+
+    /// Load feeds from a JSON object mapping symbol to feed ID
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        Ok(Self::from_feeds(serde_json::from_str(json)?))
+    }
+
+    /// Load feeds from JSON read from `reader`
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Self> {
+        Ok(Self::from_feeds(serde_json::from_reader(reader)?))
+    }
+
+    /// Feeds as a JSON object sorted by symbol, readable by [`FeedLoader::from_json_str`]
+    pub fn to_json_string(&self) -> Result<String> {
+        let sorted: BTreeMap<&str, &str> = self.iter().collect();
+        Ok(serde_json::to_string(&sorted)?)
+    }
+
+    /// Feeds as `symbol,feed_id` CSV rows sorted by symbol, after a header row
+    pub fn to_csv(&self) -> String {
+        let sorted: BTreeMap<&str, &str> = self.iter().collect();
+        let mut csv = format!("{}\n", CSV_HEADER.join(","));
+        for (symbol, feed_id) in sorted {
+            csv.push_str(&crate::pipe::csv_row(&[symbol, feed_id]));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Load feeds from CSV written by [`FeedLoader::to_csv`]; the header row
+    /// is optional. Malformed rows and repeated symbols are `IoError`s.
+    pub fn from_csv(s: &str) -> Result<Self> {
+        let malformed = |message: String| SurgeError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, message));
+        let records = crate::pipe::parse_csv(s).map_err(malformed)?;
+
+        let mut feeds = HashMap::with_capacity(records.len());
+        for (row, record) in records.into_iter().enumerate() {
+            if record.len() == 1 && record[0].is_empty() {
+                continue;
+            }
+            if row == 0 && record == CSV_HEADER {
+                continue;
+            }
+            let [symbol, feed_id]: [String; 2] = record.try_into().map_err(|record: Vec<String>| {
+                malformed(format!("row {}: expected 2 fields, found {}", row + 1, record.len()))
+            })?;
+            if feeds.contains_key(&symbol) {
+                return Err(malformed(format!("row {}: duplicate symbol {}", row + 1, symbol)));
+            }
+            feeds.insert(symbol, feed_id);
+        }
+        Ok(Self::from_feeds(feeds))
+    }
+
+    /// Write [`FeedLoader::to_csv`] to `writer`
+    pub fn write_csv<W: std::io::Write>(&self, mut writer: W) -> Result<()> {
+        writer.write_all(self.to_csv().as_bytes())?;
+        Ok(writer.flush()?)
+    }
+
+    /// [`FeedLoader::from_csv`] on everything read from `reader`
+    pub fn read_csv<R: std::io::Read>(mut reader: R) -> Result<Self> {
+        let mut csv = String::new();
+        reader.read_to_string(&mut csv)?;
+        Self::from_csv(&csv)
+    }
+
+    /// Load feeds from a JSON registry served over HTTP(S)
+    pub async fn load_from_url(url: &str) -> Result<Self> {
+        Self::load_from_url_with_client(url, &reqwest::Client::new()).await
+    }
+
+    /// [`FeedLoader::load_from_url`] reusing an existing client's connection pool
+    pub async fn load_from_url_with_client(url: &str, client: &reqwest::Client) -> Result<Self> {
+        let fetch = async {
+            client
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<HashMap<String, String>>()
+                .await
+        };
+        let feeds = fetch
+            .await
+            .map_err(|e| SurgeError::ApiError(format!("Failed to load feeds from {}: {}", url, e)))?;
+        Ok(Self::from_feeds(feeds))
+    }
+
+    /// Re-read feeds from the file this loader was created from
+    pub fn reload(&mut self) -> Result<()> {
+        let path = self.path.clone().ok_or_else(|| {
+            SurgeError::IoError(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "feeds were not loaded from a file",
+            ))
+        })?;
+        *self = Self::load_from_path(path)?;
+        Ok(())
+    }
+
+    /// Content hash of the sorted symbol list, formatted as a quoted HTTP ETag
+    pub fn symbols_etag(&self) -> String {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.get_all_symbols().hash(&mut hasher);
+        format!("\"{:016x}\"", hasher.finish())
+    }
+
+    /// Iterate over symbols without allocating, in no particular order
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.feeds.keys().map(String::as_str)
+    }
+
+    /// Iterate over `(symbol, feed_id)` pairs without allocating, in no particular order
+    pub fn iter(&self) -> Iter<'_> {
+        self.feeds.iter().map(|(symbol, feed_id)| (symbol.as_str(), feed_id.as_str()))
+    }
+
+    /// Load feeds from bytes produced by [`FeedLoader::export_protobuf`]
+    #[cfg(feature = "protobuf")]
+    pub fn load_from_protobuf(bytes: &[u8]) -> Result<Self> {
+        use prost::Message;
+
+        let registry = proto::FeedRegistry::decode(bytes)?;
+        let feeds = registry.entries.into_iter().map(|e| (e.symbol, e.feed_id)).collect();
+        Ok(Self::from_feeds(feeds))
+    }
+
+    /// Encode the registry as a protobuf `FeedRegistry` (see `proto/feeds.proto`)
+    #[cfg(feature = "protobuf")]
+    pub fn export_protobuf(&self) -> Result<Vec<u8>> {
+        use prost::Message;
+
+        let entries = self
+            .get_all_symbols()
+            .into_iter()
+            .map(|symbol| proto::FeedEntry {
+                feed_id: self.feeds[&symbol].clone(),
+                symbol,
+            })
+            .collect();
+        Ok(proto::FeedRegistry { entries }.encode_to_vec())
+    }
+
+    /// Leak the feed map for `'static` access in hot paths.
+    ///
+    /// **This permanently leaks the map**; call it once at startup. Only the
+    /// first call leaks: later calls drop `self` and return the existing map.
+    pub fn into_static(self) -> &'static HashMap<String, String> {
+        STATIC_FEEDS.get_or_init(|| Box::leak(Box::new(self.feeds)))
+    }
+
+    /// The map leaked by [`FeedLoader::into_static`], if it has been called
+    pub fn as_static(&self) -> Option<&'static HashMap<String, String>> {
+        STATIC_FEEDS.get().copied()
+    }
+
+    pub(crate) fn from_feeds(feeds: HashMap<String, String>) -> Self {
+        let loader = Self { feeds, path: None, by_feed_id: OnceCell::new() };
+
+        #[cfg(debug_assertions)]
+        for invalid in loader.validate_all() {
+            tracing::warn!("Invalid feed ID {:?} for {}: {}", invalid.feed_id, invalid.symbol, invalid.reason);
+        }
+
+        loader
+    }
+
+    /// Load feeds from a path, failing if any feed ID is malformed
+    pub fn load_strict(path: impl AsRef<Path>) -> Result<Self> {
+        let loader = Self::load_from_path(path)?;
+        let invalid = loader.validate_all();
+        if invalid.is_empty() {
+            Ok(loader)
+        } else {
+            Err(SurgeError::InvalidFeedId(invalid))
+        }
+    }
+
+    /// Load several registries, e.g. one per region. When files map a symbol
+    /// to different feed IDs the later file wins, with a warning.
+    pub fn load_multiple(paths: &[impl AsRef<Path>]) -> Result<Self> {
+        let mut loader = Self::from_feeds(HashMap::new());
+        for path in paths {
+            loader.merge_in_place(Self::load_from_path(path)?);
+        }
+        Ok(loader)
+    }
+
+    /// Add `other`'s feeds; where both map a symbol to different feed IDs,
+    /// `other` wins and a warning is logged. See [`Self::merge`] for other policies.
+    pub fn merge_in_place(&mut self, other: FeedLoader) {
+        for (symbol, feed_id) in other.feeds {
+            if let Some(previous) = self.feeds.get(&symbol).filter(|id| **id != feed_id) {
+                tracing::warn!("Duplicate feed for {}: {} replaced by {}", symbol, previous, feed_id);
+            }
+            self.feeds.insert(symbol, feed_id);
+        }
+        self.by_feed_id = OnceCell::new();
+    }
+
+    /// Register (or re-point) a single feed at runtime; `true` if `symbol` is new.
+    /// The symbol is stored as given, so pass the `BASE/QUOTE` registry form.
+    pub fn insert(&mut self, symbol: impl Into<String>, feed_id: impl Into<String>) -> bool {
+        self.by_feed_id = OnceCell::new();
+        self.feeds.insert(symbol.into(), feed_id.into()).is_none()
+    }
+
+    /// Combine two registries. Symbols mapped to the same feed ID in both are
+    /// not conflicts; the log records how each real conflict was resolved.
+    pub fn merge(self, other: FeedLoader, strategy: MergeStrategy) -> Result<(FeedLoader, MergePrecedenceLog)> {
+        let mut feeds = self.feeds;
+        let mut entries = Vec::new();
+        let mut conflicts = Vec::new();
+
+        for (symbol, other_id) in other.feeds {
+            let Some(self_id) = feeds.get(&symbol) else {
+                feeds.insert(symbol, other_id);
+                continue;
+            };
+            if *self_id == other_id {
+                continue;
+            }
+            match strategy {
+                MergeStrategy::ErrorOnConflict => conflicts.push(symbol),
+                MergeStrategy::PreferSelf => entries.push(MergePrecedenceEntry {
+                    winning_feed_id: self_id.clone(),
+                    losing_feed_id: other_id,
+                    source: MergeSource::SelfLoader,
+                    symbol,
+                }),
+                MergeStrategy::PreferOther => {
+                    let self_id = feeds.insert(symbol.clone(), other_id.clone()).unwrap_or_default();
+                    entries.push(MergePrecedenceEntry {
+                        symbol,
+                        winning_feed_id: other_id,
+                        losing_feed_id: self_id,
+                        source: MergeSource::Other,
+                    });
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            conflicts.sort();
+            return Err(SurgeError::FeedConflict(conflicts));
+        }
+        entries.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        Ok((Self::from_feeds(feeds), MergePrecedenceLog { entries }))
+    }
+
+    /// Check every symbol is `BASE/QUOTE` and every feed ID a 64-character
+    /// hex string. Returns the offending symbols, sorted.
+    pub fn validate_all(&self) -> Vec<ValidationError> {
+        let mut invalid: Vec<ValidationError> = self
+            .feeds
+            .iter()
+            .filter_map(|(symbol, feed_id)| {
+                validate_symbol(symbol)
+                    .and_then(|_| validate_feed_id(feed_id))
+                    .err()
+                    .map(|e| ValidationError { symbol: symbol.clone(), feed_id: feed_id.clone(), reason: e.to_string() })
+            })
+            .collect();
+        invalid.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        invalid
     }
 
-    /// Get feed ID for a symbol
+    /// True when [`Self::validate_all`] finds nothing wrong
+    pub fn is_valid(&self) -> bool {
+        self.validate_all().is_empty()
+    }
+
+    /// Map user input to a registry key.
+    ///
+    /// Input is trimmed and uppercased. A bare ticker ("btc") expands to the
+    /// registered pair with that base, preferring USD, then USDT, then USDC,
+    /// then the first other quote alphabetically; unknown tickers become
+    /// `TICKER/USD`.
+    pub fn resolve_symbol(&self, input: &str) -> String {
+        if self.feeds.contains_key(input) {
+            return input.to_string();
+        }
+        let input = input.trim().to_uppercase();
+        if input.contains('/') {
+            return input;
+        }
+        self.feeds
+            .keys()
+            .filter_map(|key| key.split_once('/').filter(|(base, _)| *base == input))
+            .min_by_key(|(_, quote)| {
+                let rank = QUOTE_PRIORITY.iter().position(|q| q == quote).unwrap_or(QUOTE_PRIORITY.len());
+                (rank, *quote)
+            })
+            .map(|(base, quote)| format!("{}/{}", base, quote))
+            .unwrap_or_else(|| normalize_symbol(&input))
+    }
+
+    /// Get feed ID for a symbol; accepts the same input as [`Self::resolve_symbol`]
     pub fn get_feed_id(&self, symbol: &str) -> Result<&str> {
         self.feeds
-            .get(symbol)
+            .get(&self.resolve_symbol(symbol))
             .map(|s| s.as_str())
-            .ok_or_else(|| SurgeError::FeedNotFound(symbol.to_string()))
+            .ok_or_else(|| SurgeError::FeedNotFound(symbol.to_string(), self.find_similar(symbol, NOT_FOUND_SUGGESTIONS)))
+    }
+
+    /// Symbol for a feed ID (optionally `0x`-prefixed, any case).
+    /// If several symbols share the ID, the alphabetically first wins.
+    pub fn find_by_feed_id(&self, feed_id: &str) -> Option<&str> {
+        let by_feed_id = self.by_feed_id.get_or_init(|| {
+            let mut map = HashMap::with_capacity(self.feeds.len());
+            for symbol in self.get_all_symbols() {
+                map.entry(self.feeds[&symbol].to_lowercase()).or_insert(symbol);
+            }
+            map
+        });
+        let feed_id = feed_id.trim();
+        let feed_id = feed_id.strip_prefix("0x").unwrap_or(feed_id).to_lowercase();
+        by_feed_id.get(&feed_id).map(String::as_str)
+    }
+
+    /// Every feed ID, sorted and deduplicated
+    pub fn all_feed_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.feeds.values().map(String::as_str).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Symbols whose quote currency (after the `/`) matches `quote`, case-insensitively, sorted
+    pub fn filter_by_quote(&self, quote: &str) -> Vec<&str> {
+        self.filter_pairs(|_, q| q.eq_ignore_ascii_case(quote.trim()))
+    }
+
+    /// Symbols whose base currency (before the `/`) matches `base`, case-insensitively, sorted
+    pub fn filter_by_base(&self, base: &str) -> Vec<&str> {
+        self.filter_pairs(|b, _| b.eq_ignore_ascii_case(base.trim()))
+    }
+
+    /// Every quote currency in the registry
+    pub fn all_quote_currencies(&self) -> HashSet<&str> {
+        self.pairs().map(|(_, quote)| quote).collect()
+    }
+
+    /// Every base currency in the registry
+    pub fn all_base_currencies(&self) -> HashSet<&str> {
+        self.pairs().map(|(base, _)| base).collect()
+    }
+
+    /// Quote currency → every base currency priced in it, each list sorted
+    pub fn group_by_quote(&self) -> HashMap<String, Vec<String>> {
+        group_pairs(self.pairs().map(|(base, quote)| (quote, base)))
+    }
+
+    /// Base currency → every quote currency it is priced in, each list sorted
+    pub fn group_by_base(&self) -> HashMap<String, Vec<String>> {
+        group_pairs(self.pairs())
+    }
+
+    /// Sorted, deduplicated quote currencies
+    pub fn quote_currencies(&self) -> Vec<String> {
+        sorted_strings(self.all_quote_currencies())
+    }
+
+    /// Sorted, deduplicated base currencies
+    pub fn base_currencies(&self) -> Vec<String> {
+        sorted_strings(self.all_base_currencies())
+    }
+
+    /// `(base, quote)` of each symbol; symbols without a `/` are skipped
+    fn pairs(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.symbols().filter_map(|symbol| symbol.split_once('/'))
+    }
+
+    fn filter_pairs(&self, keep: impl Fn(&str, &str) -> bool) -> Vec<&str> {
+        let mut symbols: Vec<&str> = self
+            .symbols()
+            .filter(|symbol| symbol.split_once('/').is_some_and(|(base, quote)| keep(base, quote)))
+            .collect();
+        symbols.sort_unstable();
+        symbols
     }
 
     /// Get all available symbols
@@ -46,9 +523,39 @@ impl FeedLoader {
         symbols
     }
 
+    /// Symbols resembling `query` (case-insensitive normalized Levenshtein),
+    /// best first, keeping only scores above 0.6
+    pub fn fuzzy_search(&self, query: &str, max_results: usize) -> Vec<(String, f64)> {
+        let query = query.trim().to_lowercase();
+        let mut matches: Vec<(String, f64)> = self
+            .feeds
+            .keys()
+            .map(|symbol| (symbol.clone(), strsim::normalized_levenshtein(&query, &symbol.to_lowercase())))
+            .filter(|(_, score)| *score > FUZZY_MIN_SCORE)
+            .collect();
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        matches.truncate(max_results);
+        matches
+    }
+
+    /// Up to `max_results` symbols within a Levenshtein distance of a third of
+    /// the trimmed, uppercased input's length (at least 1), closest first;
+    /// ties sort alphabetically
+    pub fn find_similar(&self, input: &str, max_results: usize) -> Vec<String> {
+        let input = input.trim().to_uppercase();
+        let max_distance = (input.chars().count() / 3).max(1);
+        let mut matches: Vec<(usize, &str)> = self
+            .symbols()
+            .map(|symbol| (strsim::levenshtein(&input, symbol), symbol))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect();
+        matches.sort_unstable();
+        matches.into_iter().take(max_results).map(|(_, symbol)| symbol.to_string()).collect()
+    }
+
     /// Check if a symbol exists
     pub fn has_symbol(&self, symbol: &str) -> bool {
-        self.feeds.contains_key(symbol)
+        self.feeds.contains_key(&self.resolve_symbol(symbol))
     }
 
     /// Get the total number of feeds
@@ -62,9 +569,65 @@ impl FeedLoader {
     }
 }
 
+/// Iterator returned by [`FeedLoader::iter`]
+pub type Iter<'a> = std::iter::Map<
+    std::collections::hash_map::Iter<'a, String, String>,
+    fn((&'a String, &'a String)) -> (&'a str, &'a str),
+>;
+
+impl<'a> IntoIterator for &'a FeedLoader {
+    type Item = (&'a str, &'a str);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Collect `(key, member)` pairs into sorted, deduplicated member lists
+fn group_pairs<'a>(pairs: impl Iterator<Item = (&'a str, &'a str)>) -> HashMap<String, Vec<String>> {
+    let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+    for (key, member) in pairs {
+        groups.entry(key.to_string()).or_default().push(member.to_string());
+    }
+    for members in groups.values_mut() {
+        members.sort_unstable();
+        members.dedup();
+    }
+    groups
+}
+
+fn sorted_strings(set: HashSet<&str>) -> Vec<String> {
+    let mut values: Vec<String> = set.into_iter().map(str::to_string).collect();
+    values.sort_unstable();
+    values
+}
+
+/// Check a registry key has a non-empty base and quote around a single `/`
+fn validate_symbol(symbol: &str) -> std::result::Result<(), FeedValidationError> {
+    match symbol.split_once('/') {
+        Some((base, quote)) if !base.is_empty() && !quote.is_empty() && !quote.contains('/') => Ok(()),
+        _ => Err(FeedValidationError::MalformedSymbol),
+    }
+}
+
+/// Check a feed ID is a 64-character hex string
+pub fn validate_feed_id(feed_id: &str) -> std::result::Result<(), FeedValidationError> {
+    if feed_id.len() != FEED_ID_LEN {
+        return Err(FeedValidationError::WrongLength(feed_id.len()));
+    }
+    let bad: String = feed_id.chars().filter(|c| !c.is_ascii_hexdigit()).collect();
+    if bad.is_empty() {
+        Ok(())
+    } else {
+        Err(FeedValidationError::InvalidChars(bad))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::serve_once;
 
     // === Loading tests ===
 
@@ -81,6 +644,50 @@ mod tests {
         assert!(result.is_err(), "should fail for invalid path");
     }
 
+    #[test]
+    fn test_json_string_round_trip() {
+        let loader = FeedLoader::from_json_str(r#"{"ETH/USD": "aa", "BTC/USD": "bb"}"#).unwrap();
+        let json = loader.to_json_string().unwrap();
+        assert_eq!(json, r#"{"BTC/USD":"bb","ETH/USD":"aa"}"#);
+
+        let reloaded = FeedLoader::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(reloaded.get_all_symbols(), loader.get_all_symbols());
+        assert_eq!(reloaded.get_feed_id("ETH/USD").unwrap(), "aa");
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let loader = loader_with(&[("ETH/USD", "aa"), ("BTC/USD", "bb"), ("ODD,NAME/\"USD\"", "cc")]);
+        let csv = loader.to_csv();
+        assert_eq!(csv, "symbol,feed_id\nBTC/USD,bb\nETH/USD,aa\n\"ODD,NAME/\"\"USD\"\"\",cc\n");
+
+        let reloaded = FeedLoader::from_csv(&csv).unwrap();
+        assert_eq!(reloaded.feeds, loader.feeds);
+
+        let mut written = Vec::new();
+        loader.write_csv(&mut written).unwrap();
+        assert_eq!(FeedLoader::read_csv(written.as_slice()).unwrap().feeds, loader.feeds);
+    }
+
+    #[test]
+    fn test_from_csv_header_optional() {
+        let loader = FeedLoader::from_csv("BTC/USD,bb\r\n\nETH/USD,aa").unwrap();
+        assert_eq!(loader.get_all_symbols(), vec!["BTC/USD", "ETH/USD"]);
+        assert!(FeedLoader::from_csv("symbol,feed_id\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_from_csv_rejects_malformed_input() {
+        for csv in ["symbol,feed_id\nBTC/USD\n", "BTC/USD,a,b\n", "BTC/USD,a\nBTC/USD,b\n", "\"BTC/USD,a\n"] {
+            assert!(matches!(FeedLoader::from_csv(csv), Err(SurgeError::IoError(_))), "accepted {:?}", csv);
+        }
+    }
+
+    #[test]
+    fn test_from_json_str_rejects_invalid_json() {
+        assert!(matches!(FeedLoader::from_json_str("[1, 2]"), Err(SurgeError::JsonError(_))));
+    }
+
     // === Symbol lookup tests ===
 
     #[test]
@@ -96,7 +703,16 @@ mod tests {
         let loader = FeedLoader::load_default().unwrap();
         assert!(!loader.has_symbol("INVALID/SYMBOL"));
         assert!(!loader.has_symbol(""));
-        assert!(!loader.has_symbol("btc")); // lowercase without /USD
+    }
+
+    #[test]
+    fn test_lookup_normalizes_input() {
+        let loader = FeedLoader::load_default().unwrap();
+        let expected = loader.get_feed_id("BTC/USD").unwrap();
+        for input in ["btc", "BTC", "  btc  ", "btc/usd"] {
+            assert!(loader.has_symbol(input), "{input:?}");
+            assert_eq!(loader.get_feed_id(input).unwrap(), expected, "{input:?}");
+        }
     }
 
     #[test]
@@ -123,13 +739,127 @@ mod tests {
         let result = loader.get_feed_id("INVALID/SYMBOL");
         assert!(result.is_err(), "should return error for unknown symbol");
 
-        if let Err(SurgeError::FeedNotFound(symbol)) = result {
+        if let Err(SurgeError::FeedNotFound(symbol, _)) = result {
             assert_eq!(symbol, "INVALID/SYMBOL");
         } else {
             panic!("should be FeedNotFound error");
         }
     }
 
+    #[test]
+    fn test_find_similar() {
+        let loader = loader_with(&[("BTC/USD", "a"), ("BTC/USDT", "b"), ("ETH/USD", "c")]);
+        assert_eq!(loader.find_similar("BTCUSD", 1), vec!["BTC/USD"]);
+        assert_eq!(loader.find_similar("btc-usd", 5), vec!["BTC/USD", "BTC/USDT"]);
+        assert!(loader.find_similar("DOGE/EUR", 5).is_empty());
+    }
+
+    #[test]
+    fn test_get_feed_id_suggests_similar() {
+        let loader = loader_with(&[("BTC/USD", "a"), ("ETH/USD", "b")]);
+        let err = loader.get_feed_id("BTC-USD").unwrap_err();
+        assert_eq!(err.to_string(), "Feed not found for 'BTC-USD'. Did you mean: BTC/USD?");
+    }
+
+    // === Reverse lookup tests ===
+
+    #[test]
+    fn test_find_by_feed_id_round_trip() {
+        let loader = FeedLoader::load_default().unwrap();
+        for symbol in ["BTC/USD", "ETH/USD", "SOL/USD"] {
+            let feed_id = loader.get_feed_id(symbol).unwrap();
+            let found = loader.find_by_feed_id(feed_id).unwrap();
+            assert_eq!(loader.get_feed_id(found).unwrap(), feed_id);
+        }
+    }
+
+    #[test]
+    fn test_find_by_feed_id_normalizes_input() {
+        let loader = loader_with(&[("BTC/USD", "abcdef"), ("XBT/USD", "abcdef"), ("ETH/USD", "123456")]);
+        assert_eq!(loader.find_by_feed_id("0xABCDEF"), Some("BTC/USD"));
+        assert_eq!(loader.find_by_feed_id(" 123456 "), Some("ETH/USD"));
+        assert_eq!(loader.find_by_feed_id("ffffff"), None);
+    }
+
+    #[test]
+    fn test_all_feed_ids() {
+        let loader = loader_with(&[("BTC/USD", "bb"), ("XBT/USD", "bb"), ("ETH/USD", "aa")]);
+        assert_eq!(loader.all_feed_ids(), vec!["aa", "bb"]);
+    }
+
+    // === Iteration tests ===
+
+    #[test]
+    fn test_iter_yields_symbol_feed_id_pairs() {
+        let loader = loader_with(&[("BTC/USD", "bb"), ("ETH/USD", "aa")]);
+        let mut pairs: Vec<(&str, &str)> = loader.iter().collect();
+        pairs.sort_unstable();
+        assert_eq!(pairs, vec![("BTC/USD", "bb"), ("ETH/USD", "aa")]);
+
+        let mut seen = 0;
+        for (symbol, feed_id) in &loader {
+            assert_eq!(loader.get_feed_id(symbol).unwrap(), feed_id);
+            seen += 1;
+        }
+        assert_eq!(seen, loader.len());
+    }
+
+    // === Currency filter tests ===
+
+    #[test]
+    fn test_filter_by_quote_and_base() {
+        let loader = loader_with(&[("ETH/USD", "aa"), ("BTC/USD", "bb"), ("BTC/USDT", "cc"), ("ETH/BTC", "dd")]);
+        assert_eq!(loader.filter_by_quote("usd"), vec!["BTC/USD", "ETH/USD"]);
+        assert_eq!(loader.filter_by_quote("BTC"), vec!["ETH/BTC"]);
+        assert_eq!(loader.filter_by_base("btc"), vec!["BTC/USD", "BTC/USDT"]);
+        assert!(loader.filter_by_quote("EUR").is_empty());
+    }
+
+    #[test]
+    fn test_all_currencies() {
+        let loader = loader_with(&[("ETH/USD", "aa"), ("BTC/USD", "bb"), ("BTC/USDT", "cc")]);
+        assert_eq!(loader.all_quote_currencies(), HashSet::from(["USD", "USDT"]));
+        assert_eq!(loader.all_base_currencies(), HashSet::from(["BTC", "ETH"]));
+    }
+
+    const GROUPING_FIXTURE: &str = r#"{
+        "BTC/USD": "01", "ETH/USD": "02", "SOL/USD": "03",
+        "BTC/USDT": "04", "ETH/USDT": "05", "ETH/BTC": "06"
+    }"#;
+
+    #[test]
+    fn test_group_by_quote_and_base() {
+        let loader = FeedLoader::from_json_str(GROUPING_FIXTURE).unwrap();
+
+        let by_quote = loader.group_by_quote();
+        assert_eq!(by_quote.len(), 3);
+        assert_eq!(by_quote["USD"], ["BTC", "ETH", "SOL"]);
+        assert_eq!(by_quote["USDT"], ["BTC", "ETH"]);
+        assert_eq!(by_quote["BTC"], ["ETH"]);
+
+        let by_base = loader.group_by_base();
+        assert_eq!(by_base.len(), 3);
+        assert_eq!(by_base["ETH"], ["BTC", "USD", "USDT"]);
+        assert_eq!(by_base["SOL"], ["USD"]);
+    }
+
+    #[test]
+    fn test_sorted_currency_lists() {
+        let loader = FeedLoader::from_json_str(GROUPING_FIXTURE).unwrap();
+        assert_eq!(loader.quote_currencies(), ["BTC", "USD", "USDT"]);
+        assert_eq!(loader.base_currencies(), ["BTC", "ETH", "SOL"]);
+        assert!(FeedLoader::from_json_str("{}").unwrap().group_by_quote().is_empty());
+    }
+
+    #[test]
+    fn test_filter_by_quote_default_registry() {
+        let loader = FeedLoader::load_default().unwrap();
+        let usd = loader.filter_by_quote("USD");
+        assert!(usd.contains(&"BTC/USD"));
+        assert!(usd.iter().all(|s| s.ends_with("/USD")));
+        assert!(loader.all_quote_currencies().contains("USD"));
+    }
+
     // === get_all_symbols tests ===
 
     #[test]
@@ -158,6 +888,110 @@ mod tests {
         assert!(symbols.contains(&"SOL/USD".to_string()));
     }
 
+    // === Validation tests ===
+
+    fn loader_with(entries: &[(&str, &str)]) -> FeedLoader {
+        FeedLoader {
+            feeds: entries.iter().map(|(s, f)| (s.to_string(), f.to_string())).collect(),
+            path: None,
+            by_feed_id: OnceCell::new(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_symbol_quote_priority() {
+        let loader = loader_with(&[
+            ("FOO/EUR", "a"),
+            ("FOO/USDC", "b"),
+            ("FOO/USDT", "c"),
+            ("BAR/EUR", "d"),
+            ("BAR/BTC", "e"),
+        ]);
+        assert_eq!(loader.resolve_symbol("foo"), "FOO/USDT");
+        assert_eq!(loader.resolve_symbol("bar"), "BAR/BTC");
+        assert_eq!(loader.resolve_symbol("baz"), "BAZ/USD");
+        assert_eq!(loader.get_feed_id("foo").unwrap(), "c");
+    }
+
+    const VALID_ID: &str = "4cd1cad962425681af07b9254b7d804de3ca3446fbfd1371bb258d2c75059812";
+
+    #[test]
+    fn test_validate_all_accepts_valid_ids() {
+        let loader = loader_with(&[("BTC/USD", VALID_ID)]);
+        assert!(loader.validate_all().is_empty());
+    }
+
+    #[test]
+    fn test_validate_all_wrong_length() {
+        let loader = loader_with(&[("BTC/USD", "abc123")]);
+        assert_eq!(
+            loader.validate_all(),
+            vec![ValidationError {
+                symbol: "BTC/USD".to_string(),
+                feed_id: "abc123".to_string(),
+                reason: FeedValidationError::WrongLength(6).to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_all_invalid_chars() {
+        let bad_id = format!("{}xz", &VALID_ID[..62]);
+        let loader = loader_with(&[("ETH/USD", &bad_id), ("BTC/USD", VALID_ID)]);
+        assert_eq!(
+            loader.validate_all(),
+            vec![ValidationError {
+                symbol: "ETH/USD".to_string(),
+                feed_id: bad_id,
+                reason: FeedValidationError::InvalidChars("xz".to_string()).to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_all_malformed_symbol() {
+        let loader = loader_with(&[("BTCUSD", VALID_ID), ("/USD", VALID_ID), ("A/B/C", VALID_ID), ("ETH/USD", VALID_ID)]);
+        let symbols: Vec<String> = loader.validate_all().into_iter().map(|e| e.symbol).collect();
+        assert_eq!(symbols, vec!["/USD", "A/B/C", "BTCUSD"]);
+        assert!(!loader.is_valid());
+    }
+
+    #[test]
+    fn test_is_valid() {
+        assert!(loader_with(&[("BTC/USD", VALID_ID)]).is_valid());
+        assert!(!loader_with(&[("BTC/USD", "")]).is_valid());
+    }
+
+    #[test]
+    fn test_validate_all_sorted_by_symbol() {
+        let loader = loader_with(&[("SOL/USD", ""), ("ADA/USD", "zz"), ("BTC/USD", VALID_ID)]);
+        let symbols: Vec<String> = loader.validate_all().into_iter().map(|e| e.symbol).collect();
+        assert_eq!(symbols, vec!["ADA/USD", "SOL/USD"]);
+    }
+
+    #[test]
+    fn test_load_strict_rejects_malformed_file() {
+        let path = std::env::temp_dir().join(format!("surge_strict_{}.json", std::process::id()));
+        fs::write(&path, r#"{"BTC/USD": "not-a-feed-id"}"#).unwrap();
+
+        let result = FeedLoader::load_strict(&path);
+        fs::remove_file(&path).ok();
+
+        match result {
+            Err(SurgeError::InvalidFeedId(invalid)) => {
+                assert_eq!(invalid.len(), 1);
+                assert_eq!(invalid[0].symbol, "BTC/USD");
+                assert_eq!(invalid[0].feed_id, "not-a-feed-id");
+            }
+            _ => panic!("should be InvalidFeedId error"),
+        }
+    }
+
+    #[test]
+    fn test_load_strict_default_file() {
+        assert!(FeedLoader::load_strict("feedIds.json").is_ok());
+    }
+
     // === len/is_empty tests ===
 
     #[test]
@@ -172,4 +1006,225 @@ mod tests {
         let loader = FeedLoader::load_default().unwrap();
         assert!(!loader.is_empty());
     }
+
+    // === Merge tests ===
+
+    const OTHER_ID: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+
+    #[test]
+    fn test_merge_without_conflicts_combines_feeds() {
+        let a = loader_with(&[("BTC/USD", VALID_ID), ("ETH/USD", VALID_ID)]);
+        let b = loader_with(&[("ETH/USD", VALID_ID), ("SOL/USD", OTHER_ID)]);
+
+        let (merged, log) = a.merge(b, MergeStrategy::ErrorOnConflict).unwrap();
+        assert_eq!(merged.get_all_symbols(), ["BTC/USD", "ETH/USD", "SOL/USD"]);
+        assert!(log.entries.is_empty());
+    }
+
+    #[test]
+    fn test_merge_error_on_conflict_lists_symbols() {
+        let a = loader_with(&[("BTC/USD", VALID_ID), ("ETH/USD", VALID_ID)]);
+        let b = loader_with(&[("ETH/USD", OTHER_ID), ("BTC/USD", OTHER_ID)]);
+
+        match a.merge(b, MergeStrategy::ErrorOnConflict) {
+            Err(SurgeError::FeedConflict(symbols)) => assert_eq!(symbols, ["BTC/USD", "ETH/USD"]),
+            _ => panic!("expected FeedConflict"),
+        }
+    }
+
+    #[test]
+    fn test_merge_prefer_self_logs_winner() {
+        let a = loader_with(&[("BTC/USD", VALID_ID)]);
+        let b = loader_with(&[("BTC/USD", OTHER_ID)]);
+
+        let (merged, log) = a.merge(b, MergeStrategy::PreferSelf).unwrap();
+        assert_eq!(merged.get_feed_id("BTC/USD").unwrap(), VALID_ID);
+        assert_eq!(
+            log.entries,
+            [MergePrecedenceEntry {
+                symbol: "BTC/USD".to_string(),
+                winning_feed_id: VALID_ID.to_string(),
+                losing_feed_id: OTHER_ID.to_string(),
+                source: MergeSource::SelfLoader,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_merge_prefer_other_logs_winner() {
+        let a = loader_with(&[("BTC/USD", VALID_ID)]);
+        let b = loader_with(&[("BTC/USD", OTHER_ID)]);
+
+        let (merged, log) = a.merge(b, MergeStrategy::PreferOther).unwrap();
+        assert_eq!(merged.get_feed_id("BTC/USD").unwrap(), OTHER_ID);
+        assert_eq!(log.entries[0].source, MergeSource::Other);
+        assert_eq!(log.entries[0].losing_feed_id, VALID_ID);
+        assert_eq!(
+            log.dump_log(),
+            format!("BTC/USD (kept other)\n- {}\n+ {}\n", VALID_ID, OTHER_ID)
+        );
+    }
+
+    #[test]
+    fn test_merge_in_place_prefers_other() {
+        let mut a = loader_with(&[("BTC/USD", VALID_ID), ("ETH/USD", VALID_ID)]);
+        assert_eq!(a.find_by_feed_id(VALID_ID), Some("BTC/USD"));
+        a.merge_in_place(loader_with(&[("BTC/USD", OTHER_ID), ("SOL/USD", VALID_ID)]));
+
+        assert_eq!(a.get_all_symbols(), ["BTC/USD", "ETH/USD", "SOL/USD"]);
+        assert_eq!(a.get_feed_id("BTC/USD").unwrap(), OTHER_ID);
+        assert_eq!(a.find_by_feed_id(OTHER_ID), Some("BTC/USD"));
+    }
+
+    #[test]
+    fn test_load_multiple_last_file_wins() {
+        let dir = std::env::temp_dir();
+        let first = dir.join(format!("surge_multi_a_{}.json", std::process::id()));
+        let second = dir.join(format!("surge_multi_b_{}.json", std::process::id()));
+        fs::write(&first, format!(r#"{{"BTC/USD": "{}", "ETH/USD": "{}"}}"#, VALID_ID, VALID_ID)).unwrap();
+        fs::write(&second, format!(r#"{{"BTC/USD": "{}"}}"#, OTHER_ID)).unwrap();
+
+        let result = FeedLoader::load_multiple(&[&first, &second]);
+        fs::remove_file(&first).ok();
+        fs::remove_file(&second).ok();
+
+        let loader = result.unwrap();
+        assert_eq!(loader.len(), 2);
+        assert_eq!(loader.get_feed_id("BTC/USD").unwrap(), OTHER_ID);
+        assert!(FeedLoader::load_multiple(&[dir.join("surge_missing_registry.json")]).is_err());
+    }
+
+    #[test]
+    fn test_insert_reports_new_symbols() {
+        let mut loader = loader_with(&[("BTC/USD", VALID_ID)]);
+        assert_eq!(loader.find_by_feed_id(OTHER_ID), None);
+
+        assert!(loader.insert("SOL/USD", OTHER_ID));
+        assert!(!loader.insert("BTC/USD", OTHER_ID));
+        assert_eq!(loader.len(), 2);
+        assert_eq!(loader.find_by_feed_id(OTHER_ID), Some("BTC/USD"));
+    }
+
+    // === Fuzzy search tests ===
+
+    #[test]
+    fn test_fuzzy_search_ranks_closest_first() {
+        let loader = loader_with(&[("BTC/USD", VALID_ID), ("BTC/USDT", VALID_ID), ("ETH/USD", VALID_ID)]);
+        let matches = loader.fuzzy_search("btcusd", 3);
+
+        let symbols: Vec<&str> = matches.iter().map(|(s, _)| s.as_str()).collect();
+        assert_eq!(symbols, ["BTC/USD", "BTC/USDT"]);
+        assert!((matches[0].1 - 6.0 / 7.0).abs() < 1e-9);
+        assert!(matches.iter().all(|(_, score)| *score > 0.6));
+    }
+
+    #[test]
+    fn test_fuzzy_search_truncates_and_drops_weak_matches() {
+        let loader = loader_with(&[("BTC/USD", VALID_ID), ("BTC/USDT", VALID_ID), ("BTC/USDC", VALID_ID)]);
+        assert_eq!(loader.fuzzy_search("BTC/USD", 1)[0].0, "BTC/USD");
+        assert!(loader.fuzzy_search("dogecoin", 5).is_empty());
+    }
+
+    // === Remote loading tests ===
+
+    fn http_response(status: &str, body: &str) -> String {
+        format!("HTTP/1.1 {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", status, body.len(), body)
+    }
+
+    #[tokio::test]
+    async fn test_load_from_url() {
+        let body = format!(r#"{{"BTC/USD": "{0}", "ETH/USD": "{0}"}}"#, VALID_ID);
+        let url = serve_once(http_response("200 OK", &body)).await;
+
+        let loader = FeedLoader::load_from_url(&url).await.unwrap();
+        assert_eq!(loader.len(), 2);
+        assert_eq!(loader.get_feed_id("BTC/USD").unwrap(), VALID_ID);
+    }
+
+    #[tokio::test]
+    async fn test_load_from_url_http_error() {
+        let url = serve_once(http_response("404 Not Found", "")).await;
+        let result = FeedLoader::load_from_url_with_client(&url, &reqwest::Client::new()).await;
+        assert!(matches!(result, Err(SurgeError::ApiError(msg)) if msg.contains("404")));
+    }
+
+    #[tokio::test]
+    async fn test_load_from_url_invalid_json() {
+        let url = serve_once(http_response("200 OK", "[1, 2]")).await;
+        assert!(matches!(FeedLoader::load_from_url(&url).await, Err(SurgeError::ApiError(_))));
+    }
+
+    // === Reload and ETag tests ===
+
+    #[test]
+    fn test_symbols_etag_depends_only_on_symbols() {
+        let a = loader_with(&[("BTC/USD", VALID_ID), ("ETH/USD", VALID_ID)]);
+        let b = loader_with(&[("ETH/USD", "other"), ("BTC/USD", "ids")]);
+        let c = loader_with(&[("BTC/USD", VALID_ID)]);
+
+        assert_eq!(a.symbols_etag(), b.symbols_etag());
+        assert_ne!(a.symbols_etag(), c.symbols_etag());
+        assert!(a.symbols_etag().starts_with('"') && a.symbols_etag().ends_with('"'));
+    }
+
+    #[test]
+    fn test_reload_picks_up_file_changes() {
+        let path = std::env::temp_dir().join(format!("surge_reload_{}.json", std::process::id()));
+        fs::write(&path, format!(r#"{{"BTC/USD": "{}"}}"#, VALID_ID)).unwrap();
+        let mut loader = FeedLoader::load_from_path(&path).unwrap();
+
+        fs::write(&path, format!(r#"{{"BTC/USD": "{0}", "ETH/USD": "{0}"}}"#, VALID_ID)).unwrap();
+        loader.reload().unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(loader.len(), 2);
+        assert!(loader.has_symbol("ETH/USD"));
+    }
+
+    #[test]
+    fn test_reload_without_path_fails() {
+        let mut loader = loader_with(&[("BTC/USD", VALID_ID)]);
+        assert!(loader.reload().is_err());
+        assert_eq!(loader.len(), 1);
+    }
+
+    // === Static map tests ===
+
+    #[test]
+    fn test_into_static_leaks_once() {
+        let first = FeedLoader::load_default().unwrap().into_static();
+        let second = FeedLoader::load_default().unwrap().into_static();
+        assert!(std::ptr::eq(first, second), "only the first call should leak");
+        assert!(first.contains_key("BTC/USD"));
+
+        let loader = FeedLoader::load_default().unwrap();
+        assert!(loader.as_static().is_some_and(|m| std::ptr::eq(m, first)));
+    }
+
+    // === Protobuf tests ===
+
+    #[cfg(feature = "protobuf")]
+    #[test]
+    fn test_protobuf_roundtrip() {
+        let loader = loader_with(&[("BTC/USD", VALID_ID), ("ETH/USD", "abc")]);
+        let bytes = loader.export_protobuf().unwrap();
+        let decoded = FeedLoader::load_from_protobuf(&bytes).unwrap();
+        assert_eq!(decoded.feeds, loader.feeds);
+    }
+
+    #[cfg(feature = "protobuf")]
+    #[test]
+    fn test_protobuf_smaller_than_json() {
+        let loader = FeedLoader::load_default().unwrap();
+        let bytes = loader.export_protobuf().unwrap();
+        let json = serde_json::to_vec(&loader.feeds).unwrap();
+        assert!(bytes.len() < json.len(), "protobuf {} bytes vs json {} bytes", bytes.len(), json.len());
+    }
+
+    #[cfg(feature = "protobuf")]
+    #[test]
+    fn test_load_from_protobuf_rejects_garbage() {
+        let result = FeedLoader::load_from_protobuf(&[0xff, 0xff, 0xff]);
+        assert!(matches!(result, Err(SurgeError::ProtobufError(_))));
+    }
 }
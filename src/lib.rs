@@ -28,18 +28,29 @@
 //! }
 //! ```
 
+pub mod analytics;
 pub mod client;
 pub mod error;
 pub mod feed_loader;
+pub mod pipe;
 pub mod server;
 pub mod streaming;
+#[cfg(test)]
+mod test_util;
+pub mod tracing_ext;
 pub mod types;
 
 pub use client::SurgeClient;
 pub use error::{Result, SurgeError};
-pub use feed_loader::FeedLoader;
-pub use streaming::Surge;
-pub use types::{FeedPrice, SurgeConfig, SurgeEvent, SurgeFeedInfo, SurgeUpdate, SurgeUpdateData};
+pub use feed_loader::{FeedLoader, FeedValidationError, MergePrecedenceLog, MergeStrategy, ValidationError};
+pub use pipe::PipeFormat;
+pub use streaming::{CachedPrice, EventCounter, Surge, SurgeStatistics};
+pub use tracing_ext::{LoggingObserver, StreamObserver, SurgeTracer};
+#[cfg(feature = "pyth-compat")]
+pub use types::PythPriceAttestation;
+#[cfg(feature = "decimal")]
+pub use rust_decimal::Decimal;
+pub use types::{BackoffConfig, CacheStats, FeedPrice, FeedPriceWithConfidence, HealthStatus, RetryPolicy, SurgeConfig, SurgeConfigBuilder, SurgeEvent, SurgeFeedInfo, SurgeUpdate, SurgeUpdateData};
 
 /// Normalize symbol input: "btc" -> "BTC/USD", "eth/usdt" -> "ETH/USDT"
 pub fn normalize_symbol(input: &str) -> String {
@@ -51,6 +62,36 @@ pub fn normalize_symbol(input: &str) -> String {
     }
 }
 
+/// Get a single price. Accepts shortcuts like "btc" for "BTC/USD".
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example() {
+/// let price = i_am_surging::get_price("btc").await.unwrap();
+/// println!("${:.2}", price.value);
+/// # }
+/// ```
+pub async fn get_price(symbol: &str) -> Result<FeedPrice> {
+    SurgeClient::new()?.get_price(symbol).await
+}
+
+/// Get multiple prices at once.
+///
+/// # Example
+/// ```rust,no_run
+/// # async fn example() {
+/// let prices = i_am_surging::get_prices(&["btc", "eth", "sol"]).await.unwrap();
+/// # }
+/// ```
+pub async fn get_prices(symbols: &[&str]) -> Result<Vec<FeedPrice>> {
+    SurgeClient::new()?.get_multiple_prices(symbols).await
+}
+
+/// List all available symbols.
+pub fn list_symbols() -> Result<Vec<String>> {
+    Ok(FeedLoader::load_default()?.get_all_symbols())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,33 +135,3 @@ mod tests {
         assert_eq!(normalize_symbol(" sol/usdt "), "SOL/USDT");
     }
 }
-
-/// Get a single price. Accepts shortcuts like "btc" for "BTC/USD".
-///
-/// # Example
-/// ```rust,no_run
-/// # async fn example() {
-/// let price = i_am_surging::get_price("btc").await.unwrap();
-/// println!("${:.2}", price.value);
-/// # }
-/// ```
-pub async fn get_price(symbol: &str) -> Result<FeedPrice> {
-    SurgeClient::new()?.get_price(symbol).await
-}
-
-/// Get multiple prices at once.
-///
-/// # Example
-/// ```rust,no_run
-/// # async fn example() {
-/// let prices = i_am_surging::get_prices(&["btc", "eth", "sol"]).await.unwrap();
-/// # }
-/// ```
-pub async fn get_prices(symbols: &[&str]) -> Result<Vec<FeedPrice>> {
-    SurgeClient::new()?.get_multiple_prices(symbols).await
-}
-
-/// List all available symbols.
-pub fn list_symbols() -> Result<Vec<String>> {
-    Ok(FeedLoader::load_default()?.get_all_symbols())
-}
@@ -1,6 +1,8 @@
-use clap::{Parser, Subcommand};
-use i_am_surging::{FeedLoader, Result, Surge, SurgeClient, SurgeEvent};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use i_am_surging::pipe::{csv_row, CSV_HEADER};
+use i_am_surging::{normalize_symbol, FeedLoader, Result, Surge, SurgeClient, SurgeError, SurgeEvent, SurgeStatistics, SurgeUpdateData};
 use std::process;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Parser)]
 #[command(
@@ -14,10 +16,22 @@ use std::process;
     surge list             List all 2000+ supported symbols"
 )]
 struct Cli {
-    /// Output as JSON
+    /// Output as JSON (same as --format json)
     #[arg(short, long)]
     json: bool,
 
+    /// Output format
+    #[arg(long, visible_alias = "output", value_enum, global = true, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Omit the CSV header row, e.g. when appending to an existing file
+    #[arg(long, global = true)]
+    csv_no_header: bool,
+
+    /// Route price lookups through a proxy (defaults to HTTPS_PROXY / HTTP_PROXY)
+    #[arg(long)]
+    proxy: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 
@@ -26,12 +40,33 @@ struct Cli {
     symbols: Vec<String>,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Stream real-time prices via WebSocket
     Stream {
         /// Symbols to stream
         symbols: Vec<String>,
+        /// Stop after this many seconds
+        #[arg(long, value_name = "SECONDS")]
+        duration: Option<u64>,
+        /// Stop after this many price updates
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+        max_updates: Option<u64>,
+        /// Print connection statistics to stderr every 10 seconds
+        #[arg(short, long)]
+        verbose: bool,
+        /// Show how long each update took to arrive (text output)
+        #[arg(long)]
+        show_lag: bool,
+        #[command(flatten)]
+        alert: PriceAlert,
     },
     /// List available symbols
     List {
@@ -41,6 +76,109 @@ enum Commands {
     },
 }
 
+/// Exit code when a `stream` price alert fires
+const ALERT_EXIT_CODE: i32 = 2;
+
+/// Thresholds that end `stream` with [`ALERT_EXIT_CODE`]
+#[derive(Args, Debug, Default)]
+struct PriceAlert {
+    /// Alert and exit once a price rises above this
+    #[arg(long, value_name = "PRICE")]
+    alert_above: Option<f64>,
+    /// Alert and exit once a price falls below this
+    #[arg(long, value_name = "PRICE")]
+    alert_below: Option<f64>,
+    /// Only alert on this symbol instead of any streamed one
+    #[arg(long, value_name = "SYMBOL")]
+    alert_symbol: Option<String>,
+}
+
+impl PriceAlert {
+    /// Alert message when `update` is past a threshold
+    fn check(&self, update: &SurgeUpdateData) -> Option<String> {
+        if self.alert_symbol.as_deref().is_some_and(|symbol| normalize_symbol(symbol) != update.symbol) {
+            return None;
+        }
+        let (direction, threshold) = match (self.alert_above, self.alert_below) {
+            (Some(above), _) if update.price > above => ("above", above),
+            (_, Some(below)) if update.price < below => ("below", below),
+            _ => return None,
+        };
+        Some(format!(
+            "ALERT: {} crossed {} ${:.2} (current: ${:.2})",
+            update.symbol, direction, threshold, update.price
+        ))
+    }
+}
+
+impl Cli {
+    /// Reject obviously malformed input before any network call
+    fn validate(&self) -> std::result::Result<(), String> {
+        match &self.command {
+            Some(_) if self.proxy.is_some() => Err("--proxy only applies to price lookups (surge --proxy <URL> btc)".to_string()),
+            Some(command) => command.validate(),
+            None if self.symbols.is_empty() => Err(
+                "Usage: surge <SYMBOLS>...\nExample: surge btc eth sol\n\nRun 'surge --help' for more options".to_string(),
+            ),
+            None => validate_symbols(&self.symbols, "surge btc eth sol/usdt"),
+        }
+    }
+}
+
+impl Commands {
+    fn validate(&self) -> std::result::Result<(), String> {
+        match self {
+            Commands::Stream { symbols, .. } if symbols.is_empty() => {
+                Err("Usage: surge stream <SYMBOLS>...\nExample: surge stream btc eth sol".to_string())
+            }
+            Commands::Stream { symbols, .. } => validate_symbols(symbols, "surge stream btc eth sol/usdt"),
+            Commands::List { .. } => Ok(()),
+        }
+    }
+}
+
+/// Symbols must be a shortcut ("btc") or a "BASE/QUOTE" pair
+fn validate_symbols(symbols: &[String], example: &str) -> std::result::Result<(), String> {
+    let is_part = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || "._-".contains(c));
+
+    for symbol in symbols {
+        let trimmed = symbol.trim();
+        let valid = match trimmed.split_once('/') {
+            Some((base, quote)) => is_part(base) && is_part(quote),
+            None => is_part(trimmed),
+        };
+        if !valid {
+            return Err(format!(
+                "Invalid symbol '{}': use a shortcut like 'btc' or a pair like 'BTC/USD'\nExample: {}",
+                symbol, example
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// How often `stream --verbose` reports connection statistics
+const STATUS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// One-line connection status for `stream --verbose`
+fn print_statistics(stats: &SurgeStatistics) {
+    let last = stats
+        .last_message_at
+        .map(|at| format!("{:.1}s ago", at.elapsed().as_secs_f64()))
+        .unwrap_or_else(|| "never".to_string());
+    let lag = stats.avg_lag_ms.map(|lag| format!(", avg lag {:.0}ms", lag)).unwrap_or_default();
+    eprintln!(
+        "[status] up {}s, {} messages (last {}), {} reconnects, {} subscriptions{}",
+        stats.uptime_secs, stats.messages_received, last, stats.reconnect_count, stats.active_subscriptions, lag
+    );
+}
+
+/// Milliseconds from an update's source timestamp until now
+fn update_lag_ms(update: &SurgeUpdateData) -> i64 {
+    let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as i64);
+    (now_ms - update.source_timestamp_ms).max(0)
+}
+
 #[tokio::main]
 async fn main() {
     if let Err(e) = run().await {
@@ -51,7 +189,16 @@ async fn main() {
 
 async fn run() -> Result<()> {
     let cli = Cli::parse();
-    let json = cli.json;
+    if let Err(message) = cli.validate() {
+        eprintln!("{}", message);
+        process::exit(2);
+    }
+    let format = if cli.json { OutputFormat::Json } else { cli.format };
+    let csv_header = |fields: &[&str]| {
+        if !cli.csv_no_header {
+            println!("{}", csv_row(fields));
+        }
+    };
 
     match cli.command {
         Some(Commands::List { filter }) => {
@@ -63,64 +210,131 @@ async fn run() -> Result<()> {
                 symbols.retain(|s| s.to_lowercase().contains(&filter_lower));
             }
 
-            if json {
-                println!("{}", serde_json::to_string_pretty(&symbols)?);
-            } else {
-                for s in &symbols {
-                    println!("{}", s);
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&symbols)?),
+                OutputFormat::Csv => {
+                    csv_header(&["symbol"]);
+                    for s in &symbols {
+                        println!("{}", csv_row(&[s]));
+                    }
+                }
+                OutputFormat::Text => {
+                    for s in &symbols {
+                        println!("{}", s);
+                    }
+                    eprintln!("\n{} symbols", symbols.len());
                 }
-                eprintln!("\n{} symbols", symbols.len());
             }
         }
 
-        Some(Commands::Stream { symbols }) => {
-            if symbols.is_empty() {
-                eprintln!("Usage: surge stream <SYMBOLS>...");
-                eprintln!("Example: surge stream btc eth sol");
-                process::exit(1);
-            }
-
+        Some(Commands::Stream { symbols, duration, max_updates, verbose, show_lag, alert }) => {
             let symbol_refs: Vec<&str> = symbols.iter().map(String::as_str).collect();
-            let mut surge = Surge::new(""); // API key not required
+            // Use SURGE_API_KEY when set; streaming also works without a key
+            let mut surge = Surge::from_env().unwrap_or_else(|_| Surge::new(""));
             let mut event_rx = surge.subscribe_events();
             surge.connect_and_subscribe(symbol_refs).await?;
 
-            if !json {
-                eprintln!("Streaming {} (Ctrl+C to stop)\n", symbols.join(", "));
+            match format {
+                OutputFormat::Text => eprintln!("Streaming {} (Ctrl+C to stop)\n", symbols.join(", ")),
+                OutputFormat::Csv => csv_header(&CSV_HEADER),
+                OutputFormat::Json => {}
             }
 
-            while let Ok(event) = event_rx.recv().await {
-                match event {
-                    SurgeEvent::PriceUpdate(u) => {
-                        if json {
-                            println!("{}", serde_json::to_string(&u)?);
-                        } else {
-                            println!("{}: ${:.2}", u.data.symbol, u.data.price);
+            let mut received: u64 = 0;
+            let mut status_timer = tokio::time::interval_at(tokio::time::Instant::now() + STATUS_INTERVAL, STATUS_INTERVAL);
+            let stream = async {
+                loop {
+                    let event = tokio::select! {
+                        event = event_rx.recv() => match event {
+                            Ok(event) => event,
+                            Err(_) => return Ok(None),
+                        },
+                        _ = status_timer.tick(), if verbose => {
+                            print_statistics(&surge.statistics().await);
+                            continue;
+                        }
+                    };
+                    match event {
+                        SurgeEvent::PriceUpdate(_) | SurgeEvent::PriceBatch(_) | SurgeEvent::Snapshot { .. } => {
+                            for u in event.into_updates() {
+                                match format {
+                                    OutputFormat::Json => println!("{}", serde_json::to_string(&u)?),
+                                    OutputFormat::Csv => println!(
+                                        "{}",
+                                        csv_row(&[
+                                            &u.data.source_timestamp_ms.to_string(),
+                                            &u.data.symbol,
+                                            &u.data.price.to_string(),
+                                            u.data.feed_id.as_deref().unwrap_or(""),
+                                        ])
+                                    ),
+                                    OutputFormat::Text if show_lag => {
+                                        println!("{}: ${:.2} (lag: {}ms)", u.data.symbol, u.data.price, update_lag_ms(&u.data))
+                                    }
+                                    OutputFormat::Text => println!("{}: ${:.2}", u.data.symbol, u.data.price),
+                                }
+                                received += 1;
+                                if let Some(message) = alert.check(&u.data) {
+                                    return Ok(Some(message));
+                                }
+                                if max_updates.is_some_and(|max| received >= max) {
+                                    return Ok(None);
+                                }
+                            }
+                        }
+                        SurgeEvent::Error(e) => eprintln!("Error: {}", e),
+                        SurgeEvent::ReconnectFailed { attempts, reason } => {
+                            return Err(SurgeError::ConnectionError(format!(
+                                "gave up after {} reconnection attempts: {}",
+                                attempts, reason
+                            )));
                         }
+                        _ => {}
                     }
-                    SurgeEvent::Error(e) => eprintln!("Error: {}", e),
-                    _ => {}
                 }
+            };
+
+            let alerted: Option<String> = match duration {
+                // Elapsing is a normal, successful end of the stream
+                Some(secs) => tokio::time::timeout(Duration::from_secs(secs), stream).await.unwrap_or(Ok(None))?,
+                None => stream.await?,
+            };
+            if duration.is_some() || max_updates.is_some() {
+                eprintln!("\n{} updates received", received);
+            }
+            surge.disconnect().await?;
+            if let Some(message) = alerted {
+                eprintln!("{}", message);
+                process::exit(ALERT_EXIT_CODE);
             }
         }
 
         None => {
-            if cli.symbols.is_empty() {
-                eprintln!("Usage: surge <SYMBOLS>...");
-                eprintln!("Example: surge btc eth sol");
-                eprintln!("\nRun 'surge --help' for more options");
-                process::exit(1);
-            }
-
-            let client = SurgeClient::new()?;
+            let client = match cli.proxy {
+                Some(ref proxy) => SurgeClient::with_proxy(proxy)?,
+                None => SurgeClient::new()?,
+            };
             let symbol_refs: Vec<&str> = cli.symbols.iter().map(String::as_str).collect();
-            let prices = client.get_multiple_prices(&symbol_refs).await?;
+            let mut by_symbol = client.get_prices_as_map(&symbol_refs).await?;
+            // Requested order, each symbol once ("surge btc BTC" prints BTC/USD once)
+            let prices: Vec<_> = cli
+                .symbols
+                .iter()
+                .filter_map(|symbol| by_symbol.remove(&normalize_symbol(symbol)))
+                .collect();
 
-            if json {
-                println!("{}", serde_json::to_string_pretty(&prices)?);
-            } else {
-                for p in &prices {
-                    println!("{}: ${:.2}", p.symbol, p.value);
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&prices)?),
+                OutputFormat::Csv => {
+                    csv_header(&["symbol", "price", "feed_id"]);
+                    for p in &prices {
+                        println!("{}", csv_row(&[&p.symbol, &p.value.to_string(), &p.feed_id]));
+                    }
+                }
+                OutputFormat::Text => {
+                    for p in &prices {
+                        println!("{}: ${:.2}", p.symbol, p.value);
+                    }
                 }
             }
         }
@@ -128,3 +342,104 @@ async fn run() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once("surge").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn test_format_flags() {
+        let cli = parse(&["stream", "btc", "--format", "csv", "--csv-no-header"]);
+        assert!(cli.format == OutputFormat::Csv);
+        assert!(cli.csv_no_header);
+        assert!(parse(&["btc"]).format == OutputFormat::Text);
+        assert!(parse(&["list", "--output", "csv"]).format == OutputFormat::Csv);
+    }
+
+    #[test]
+    fn test_validate_accepts_shortcuts_and_pairs() {
+        assert!(parse(&["btc", "ETH/USDT", "1000pepe"]).validate().is_ok());
+        assert!(parse(&["stream", "sol", "sol/usdc"]).validate().is_ok());
+        assert!(parse(&["list"]).validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_requires_symbols() {
+        assert!(parse(&[]).validate().is_err());
+        assert!(parse(&["stream"]).validate().unwrap_err().contains("surge stream"));
+    }
+
+    #[test]
+    fn test_proxy_only_for_price_lookups() {
+        assert_eq!(parse(&["--proxy", "http://proxy:3128", "btc"]).proxy.as_deref(), Some("http://proxy:3128"));
+        assert!(parse(&["--proxy", "http://proxy:3128", "stream", "btc"]).validate().unwrap_err().contains("--proxy"));
+        assert!(Cli::try_parse_from(["surge", "stream", "btc", "--proxy", "http://proxy:3128"]).is_err());
+    }
+
+    #[test]
+    fn test_stream_limits() {
+        let Some(Commands::Stream { duration, max_updates, .. }) = parse(&["stream", "btc"]).command else {
+            panic!("expected stream command");
+        };
+        assert_eq!((duration, max_updates), (None, None));
+
+        let cli = parse(&["stream", "btc", "--duration", "10", "--max-updates", "5"]);
+        let Some(Commands::Stream { symbols, duration, max_updates, verbose, .. }) = cli.command else {
+            panic!("expected stream command");
+        };
+        assert_eq!(symbols, vec!["btc"]);
+        assert_eq!((duration, max_updates), (Some(10), Some(5)));
+        assert!(!verbose);
+
+        let Some(Commands::Stream { verbose, show_lag, .. }) = parse(&["stream", "btc", "-v", "--show-lag"]).command else {
+            panic!("expected stream command");
+        };
+        assert!(verbose && show_lag);
+
+        let args = ["surge", "stream", "btc", "--max-updates", "0"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_price_alert() {
+        let args = ["stream", "btc", "eth", "--alert-above", "100000", "--alert-below", "90000"];
+        let Some(Commands::Stream { alert, .. }) = parse(&args).command else {
+            panic!("expected stream command");
+        };
+        let data = |symbol: &str, price| SurgeUpdateData {
+            symbol: symbol.to_string(),
+            price,
+            source_timestamp_ms: 0,
+            feed_id: None,
+            signature: None,
+            conf: None,
+        };
+
+        assert_eq!(alert.check(&data("BTC/USD", 95000.0)), None);
+        assert_eq!(
+            alert.check(&data("BTC/USD", 100050.234)).unwrap(),
+            "ALERT: BTC/USD crossed above $100000.00 (current: $100050.23)"
+        );
+        assert_eq!(
+            alert.check(&data("ETH/USD", 3000.0)).unwrap(),
+            "ALERT: ETH/USD crossed below $90000.00 (current: $3000.00)"
+        );
+
+        let only_btc = PriceAlert { alert_symbol: Some("btc".to_string()), ..alert };
+        assert_eq!(only_btc.check(&data("ETH/USD", 3000.0)), None);
+        assert!(only_btc.check(&data("BTC/USD", 80000.0)).is_some());
+        assert_eq!(PriceAlert::default().check(&data("BTC/USD", 1e9)), None);
+    }
+
+    #[test]
+    fn test_validate_rejects_malformed_symbols() {
+        for bad in ["btc/", "/usd", "btc/usd/eur", "b tc", "btc$"] {
+            let err = parse(&[bad]).validate().unwrap_err();
+            assert!(err.contains("Invalid symbol"), "{} should be rejected", bad);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Encodings for writing price updates to files and sockets
+
+use crate::error::{Result, SurgeError};
+use crate::types::SurgeUpdate;
+
+/// Column names written before the first CSV row
+pub const CSV_HEADER: [&str; 4] = ["timestamp", "symbol", "price", "feed_id"];
+
+/// Wire format for [`crate::Surge::pipe_to`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipeFormat {
+    /// One JSON object per line
+    JsonLines,
+    /// RFC 4180 rows under [`CSV_HEADER`]
+    Csv,
+    /// Concatenated MessagePack maps; each value is self-delimiting
+    MessagePack,
+}
+
+impl PipeFormat {
+    /// Bytes written once before any update
+    pub fn header(&self) -> Option<Vec<u8>> {
+        match self {
+            PipeFormat::Csv => Some(format!("{}\n", csv_row(&CSV_HEADER)).into_bytes()),
+            PipeFormat::JsonLines | PipeFormat::MessagePack => None,
+        }
+    }
+
+    /// Encode one update as a complete record
+    pub fn encode(&self, update: &SurgeUpdate) -> Result<Vec<u8>> {
+        match self {
+            PipeFormat::JsonLines => {
+                let mut line = serde_json::to_vec(update)?;
+                line.push(b'\n');
+                Ok(line)
+            }
+            PipeFormat::Csv => {
+                let row = csv_row(&[
+                    &update.data.source_timestamp_ms.to_string(),
+                    &update.data.symbol,
+                    &update.data.price.to_string(),
+                    update.data.feed_id.as_deref().unwrap_or(""),
+                ]);
+                Ok(format!("{}\n", row).into_bytes())
+            }
+            PipeFormat::MessagePack => {
+                rmp_serde::to_vec_named(update).map_err(|e| SurgeError::IoError(std::io::Error::other(e)))
+            }
+        }
+    }
+}
+
+/// Quote a field per RFC 4180 when it contains a comma, quote or line break
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+/// Join fields into one CSV row
+pub fn csv_row(fields: &[&str]) -> String {
+    fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(",")
+}
+
+/// Split RFC 4180 text into records, undoing [`csv_row`]'s quoting.
+/// Errors name the line where the input stops making sense.
+pub(crate) fn parse_csv(input: &str) -> std::result::Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() => {
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            line += usize::from(c == '\n');
+                            field.push(c);
+                        }
+                        None => return Err(format!("unterminated quote on line {}", line)),
+                    }
+                }
+                if !matches!(chars.peek(), None | Some(',' | '\r' | '\n')) {
+                    return Err(format!("unexpected text after closing quote on line {}", line));
+                }
+            }
+            '"' => return Err(format!("unexpected quote on line {}", line)),
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+                line += 1;
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UPDATE_JSON: &str = r#"{"type":"price","data":{"symbol":"BTC/USD","price":50000.5,"source_ts_ms":1705936800000,"feed_id":"abc"}}"#;
+
+    fn update() -> SurgeUpdate {
+        serde_json::from_str(UPDATE_JSON).unwrap()
+    }
+
+    #[test]
+    fn test_csv_row_plain_fields() {
+        assert_eq!(csv_row(&["1705936800000", "BTC/USD", "89846.94", "abc"]), "1705936800000,BTC/USD,89846.94,abc");
+    }
+
+    #[test]
+    fn test_csv_row_quotes_special_fields() {
+        assert_eq!(csv_row(&["a,b", "say \"hi\"", "line\nbreak", ""]), "\"a,b\",\"say \"\"hi\"\"\",\"line\nbreak\",");
+    }
+
+    #[test]
+    fn test_parse_csv_undoes_csv_row() {
+        let fields = ["a,b", "say \"hi\"", "line\nbreak", ""];
+        let text = format!("{}\r\nplain,row\n", csv_row(&fields));
+        assert_eq!(parse_csv(&text).unwrap(), vec![fields.to_vec(), vec!["plain", "row"]]);
+
+        assert_eq!(parse_csv("\"open,row\n").unwrap_err(), "unterminated quote on line 2");
+        assert_eq!(parse_csv("a,b\nc\"d\n").unwrap_err(), "unexpected quote on line 2");
+        assert!(parse_csv("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_encode_json_lines() {
+        let bytes = PipeFormat::JsonLines.encode(&update()).unwrap();
+        assert!(bytes.ends_with(b"\n"));
+        let decoded: SurgeUpdate = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(decoded.data.price, 50000.5);
+        assert!(PipeFormat::JsonLines.header().is_none());
+    }
+
+    #[test]
+    fn test_encode_csv() {
+        assert_eq!(PipeFormat::Csv.header().unwrap(), b"timestamp,symbol,price,feed_id\n");
+        assert_eq!(PipeFormat::Csv.encode(&update()).unwrap(), b"1705936800000,BTC/USD,50000.5,abc\n");
+    }
+
+    #[test]
+    fn test_encode_message_pack_round_trip() {
+        let bytes = PipeFormat::MessagePack.encode(&update()).unwrap();
+        let decoded: SurgeUpdate = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded.data.symbol, "BTC/USD");
+    }
+}
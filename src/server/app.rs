@@ -1,7 +1,9 @@
 //! Axum application builder with all routes and middleware
 
 use axum::{
+    extract::{MatchedPath, Request},
     middleware,
+    response::Response,
     routing::{get, Router},
 };
 use std::time::Duration;
@@ -11,16 +13,25 @@ use tower_http::{
     timeout::TimeoutLayer,
     trace::TraceLayer,
 };
+use tracing::Span;
 
-use super::auth::require_api_key;
+use super::auth;
+use super::indicators;
 use super::metrics::track_metrics;
+use super::middleware::{inject_request_id, rate_limit, RequestId};
 use super::routes::{self, AppState};
+use super::routes_v2::create_v2_router;
 use super::websocket;
 use crate::error::SurgeError;
 
-/// Create the Axum application with all routes and middleware
-pub fn create_app() -> Result<Router, SurgeError> {
-    let state = AppState::new()?;
+/// Create the Axum application with all routes and middleware.
+///
+/// Pass `None` to build state from the environment, or inject a pre-built [`AppState`].
+pub fn create_app(state: Option<AppState>) -> Result<Router, SurgeError> {
+    let state = match state {
+        Some(state) => state,
+        None => AppState::new()?,
+    };
 
     // Public routes (no auth required)
     let public_routes = Router::new()
@@ -31,19 +42,57 @@ pub fn create_app() -> Result<Router, SurgeError> {
     // Protected API routes (auth required)
     let api_routes = Router::new()
         .route("/prices/:symbol", get(routes::get_price))
-        .route("/prices", get(routes::get_prices))
+        .route("/prices/:symbol/zscore", get(indicators::get_zscore))
+        .route("/prices/:symbol/candle", get(indicators::get_candle))
+        .route("/prices/:symbol/moving-average", get(indicators::get_moving_average))
+        .route("/prices/:symbol/bollinger", get(indicators::get_bollinger))
+        .route("/prices/:symbol/regime", get(indicators::get_regime))
+        .route("/prices", get(routes::get_prices).post(routes::post_prices))
+        .route("/prices/arbitrage-matrix", get(indicators::get_arbitrage_matrix))
+        .route("/prices/heatmap", get(indicators::get_heatmap))
         .route("/symbols", get(routes::list_symbols))
         .route("/stream", get(websocket::ws_handler))
-        .with_state(state.clone())
-        .layer(middleware::from_fn(require_api_key));
+        .route("/stream/sse", get(routes::sse_price_stream))
+        .route("/admin/throughput", get(routes::throughput));
+
+    #[cfg(feature = "multi-source")]
+    let api_routes = api_routes.route("/prices/:symbol/spread", get(super::spread::get_spread));
+
+    let api_routes = if state.debug_endpoints {
+        api_routes.route("/debug/feed/:feed_id", get(routes::debug_feed))
+    } else {
+        api_routes
+    };
+
+    #[cfg(not(feature = "solana"))]
+    let authenticate = auth::require_api_key;
+    #[cfg(feature = "solana")]
+    let authenticate = auth::require_api_key_or_solana_signature;
+
+    // Layers run bottom-up, so requests are authenticated before being counted
+    let protect = |routes: Router| {
+        routes
+            .layer(middleware::from_fn_with_state(state.clone(), rate_limit))
+            .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+    };
+    let api_routes = protect(api_routes.with_state(state.clone()));
+    let v2_routes = protect(create_v2_router(state.clone()));
 
     // Combine all routes
     let app = Router::new()
         .merge(public_routes)
         .nest("/v1", api_routes)
+        .nest("/v2", v2_routes)
         .layer(
             ServiceBuilder::new()
-                .layer(TraceLayer::new_for_http())
+                .layer(middleware::from_fn(inject_request_id))
+                .layer(
+                    TraceLayer::new_for_http()
+                        .make_span_with(request_span)
+                        .on_response(|response: &Response, _latency: Duration, span: &Span| {
+                            span.record("http.status_code", response.status().as_u16());
+                        }),
+                )
                 .layer(middleware::from_fn(track_metrics))
                 .layer(TimeoutLayer::new(Duration::from_secs(30)))
                 .layer(
@@ -57,6 +106,34 @@ pub fn create_app() -> Result<Router, SurgeError> {
     Ok(app)
 }
 
+/// Span for one HTTP request, carrying the OpenTelemetry HTTP attributes.
+/// `http.status_code` is recorded once the response is ready.
+fn request_span(request: &Request) -> Span {
+    let request_id = request.extensions().get::<RequestId>().map(|id| id.0.as_str()).unwrap_or("");
+    let route = request.extensions().get::<MatchedPath>().map(MatchedPath::as_str);
+    let symbol = route.and_then(|route| path_param(route, request.uri().path(), "symbol")).unwrap_or_default();
+    tracing::info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        request_id = %request_id,
+        "http.method" = %request.method(),
+        "http.route" = route.unwrap_or_default(),
+        "http.status_code" = tracing::field::Empty,
+        "surge.symbol" = symbol,
+    )
+}
+
+/// Value of `:name` in `path`, found by lining it up against the matched `route`
+fn path_param<'a>(route: &str, path: &'a str, name: &str) -> Option<&'a str> {
+    let placeholder = format!(":{}", name);
+    route
+        .split('/')
+        .zip(path.split('/'))
+        .find(|(segment, _)| *segment == placeholder)
+        .map(|(_, value)| value)
+}
+
 /// Server configuration
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
@@ -81,3 +158,15 @@ impl ServerConfig {
         format!("{}:{}", self.host, self.port)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_param_lines_up_route_and_path() {
+        assert_eq!(path_param("/v1/prices/:symbol/zscore", "/v1/prices/BTC/zscore", "symbol"), Some("BTC"));
+        assert_eq!(path_param("/v1/prices", "/v1/prices", "symbol"), None);
+        assert_eq!(path_param("/v1/debug/feed/:feed_id", "/v1/debug/feed/0xab", "symbol"), None);
+    }
+}
@@ -1,25 +1,94 @@
 //! API key authentication middleware
+//!
+//! With the `solana` feature, [`require_api_key_or_solana_signature`] also
+//! accepts requests signed by an allowed Solana wallet.
 
 use axum::{
-    extract::Request,
-    http::{header::AUTHORIZATION, StatusCode},
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
     middleware::Next,
     response::Response,
 };
 
-/// Middleware to validate API key from Authorization header
-pub async fn require_api_key(request: Request, next: Next) -> Result<Response, StatusCode> {
-    let expected_key = std::env::var("SURGE_API_KEY").unwrap_or_default();
+use super::routes::AppState;
+
+/// Base58 public key of the wallet signing the request
+#[cfg(feature = "solana")]
+pub const WALLET_ADDRESS_HEADER: &str = "x-wallet-address";
+
+/// Base58 ed25519 signature over [`wallet_auth_message`]
+#[cfg(feature = "solana")]
+pub const SIGNATURE_HEADER: &str = "x-signature";
 
+/// Unix seconds included in the signed message
+#[cfg(feature = "solana")]
+pub const TIMESTAMP_HEADER: &str = "x-timestamp";
+
+/// Signed timestamps further than this from the server clock are rejected as replays
+#[cfg(feature = "solana")]
+pub const MAX_SIGNATURE_AGE_SECS: i64 = 60;
+
+/// Middleware to validate API key from Authorization header
+pub async fn require_api_key(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
     // If no API key is configured, skip auth
-    if expected_key.is_empty() {
+    let Some(expected_key) = state.api_key.as_deref() else {
+        return Ok(next.run(request).await);
+    };
+
+    check_bearer(request.headers(), expected_key)?;
+    Ok(next.run(request).await)
+}
+
+/// Like [`require_api_key`], but a request signed by one of the configured
+/// wallets is accepted in place of the Bearer token.
+///
+/// The client sends [`WALLET_ADDRESS_HEADER`], [`TIMESTAMP_HEADER`] and
+/// [`SIGNATURE_HEADER`], signing [`wallet_auth_message`] for the request's
+/// method and path. Auth is skipped when neither an API key nor wallets are configured.
+#[cfg(feature = "solana")]
+pub async fn require_api_key_or_solana_signature(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if state.api_key.is_none() && state.allowed_wallets.is_empty() {
         return Ok(next.run(request).await);
     }
 
-    let auth_header = request
-        .headers()
-        .get(AUTHORIZATION)
-        .and_then(|value| value.to_str().ok());
+    if !request.headers().contains_key(WALLET_ADDRESS_HEADER) {
+        let Some(expected_key) = state.api_key.as_deref() else {
+            tracing::warn!("Missing wallet signature");
+            return Err(StatusCode::UNAUTHORIZED);
+        };
+        check_bearer(request.headers(), expected_key)?;
+        return Ok(next.run(request).await);
+    }
+
+    // Nested routers see the path without their prefix; clients sign the full one
+    let path = request
+        .extensions()
+        .get::<axum::extract::OriginalUri>()
+        .map_or_else(|| request.uri().path(), |uri| uri.path());
+    let now_secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    if let Err(reason) =
+        verify_wallet_signature(&state.allowed_wallets, request.method().as_str(), path, request.headers(), now_secs)
+    {
+        tracing::warn!("Rejected wallet signature: {}", reason);
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Require `Authorization: Bearer <expected_key>`
+fn check_bearer(headers: &HeaderMap, expected_key: &str) -> Result<(), StatusCode> {
+    let auth_header = headers.get(AUTHORIZATION).and_then(|value| value.to_str().ok());
 
     let Some(header) = auth_header else {
         tracing::warn!("Missing authorization header");
@@ -35,6 +104,103 @@ pub async fn require_api_key(request: Request, next: Next) -> Result<Response, S
         tracing::warn!("Invalid API key provided");
         return Err(StatusCode::UNAUTHORIZED);
     }
+    Ok(())
+}
 
-    Ok(next.run(request).await)
+/// Digest a wallet signs to authenticate: `sha256(method + path + unix_timestamp_seconds)`,
+/// e.g. over `"GET/v1/prices/btc1705936800"`
+#[cfg(feature = "solana")]
+pub fn wallet_auth_message(method: &str, path: &str, timestamp_secs: i64) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(format!("{}{}{}", method, path, timestamp_secs)).into()
+}
+
+/// Check the wallet headers: an allowed signer, a recent timestamp and a valid signature
+#[cfg(feature = "solana")]
+fn verify_wallet_signature(
+    allowed_wallets: &std::collections::HashSet<String>,
+    method: &str,
+    path: &str,
+    headers: &HeaderMap,
+    now_secs: i64,
+) -> Result<(), &'static str> {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let wallet = header(WALLET_ADDRESS_HEADER).ok_or("missing wallet address")?;
+    if !allowed_wallets.contains(wallet) {
+        return Err("wallet not allowed");
+    }
+    let timestamp: i64 = header(TIMESTAMP_HEADER).and_then(|t| t.parse().ok()).ok_or("missing or invalid timestamp")?;
+    if (now_secs - timestamp).abs() > MAX_SIGNATURE_AGE_SECS {
+        return Err("signature expired");
+    }
+
+    let key_bytes: [u8; 32] = bs58::decode(wallet)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("invalid wallet address")?;
+    let key = VerifyingKey::from_bytes(&key_bytes).map_err(|_| "invalid wallet address")?;
+    let sig_bytes: [u8; 64] = header(SIGNATURE_HEADER)
+        .and_then(|sig| bs58::decode(sig).into_vec().ok())
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("missing or invalid signature")?;
+
+    key.verify(&wallet_auth_message(method, path, timestamp), &Signature::from_bytes(&sig_bytes))
+        .map_err(|_| "signature mismatch")
+}
+
+#[cfg(all(test, feature = "solana"))]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use std::collections::HashSet;
+
+    const NOW: i64 = 1705936800;
+
+    fn signed_headers(key: &SigningKey, method: &str, path: &str, timestamp: i64) -> HeaderMap {
+        let signature = key.sign(&wallet_auth_message(method, path, timestamp));
+        let mut headers = HeaderMap::new();
+        headers.insert(WALLET_ADDRESS_HEADER, bs58::encode(key.verifying_key().to_bytes()).into_string().parse().unwrap());
+        headers.insert(TIMESTAMP_HEADER, timestamp.to_string().parse().unwrap());
+        headers.insert(SIGNATURE_HEADER, bs58::encode(signature.to_bytes()).into_string().parse().unwrap());
+        headers
+    }
+
+    fn allowed(key: &SigningKey) -> HashSet<String> {
+        HashSet::from([bs58::encode(key.verifying_key().to_bytes()).into_string()])
+    }
+
+    #[test]
+    fn test_valid_wallet_signature() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let headers = signed_headers(&key, "GET", "/v1/prices/btc", NOW - 30);
+        assert_eq!(verify_wallet_signature(&allowed(&key), "GET", "/v1/prices/btc", &headers, NOW), Ok(()));
+    }
+
+    #[test]
+    fn test_wallet_signature_rejects_other_request() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let headers = signed_headers(&key, "GET", "/v1/prices/btc", NOW);
+        let result = verify_wallet_signature(&allowed(&key), "GET", "/v1/prices/eth", &headers, NOW);
+        assert_eq!(result, Err("signature mismatch"));
+    }
+
+    #[test]
+    fn test_wallet_signature_expires() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let headers = signed_headers(&key, "GET", "/v1/symbols", NOW - MAX_SIGNATURE_AGE_SECS - 1);
+        let result = verify_wallet_signature(&allowed(&key), "GET", "/v1/symbols", &headers, NOW);
+        assert_eq!(result, Err("signature expired"));
+    }
+
+    #[test]
+    fn test_wallet_must_be_allowed() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let other = SigningKey::from_bytes(&[8u8; 32]);
+        let headers = signed_headers(&key, "GET", "/v1/symbols", NOW);
+        let result = verify_wallet_signature(&allowed(&other), "GET", "/v1/symbols", &headers, NOW);
+        assert_eq!(result, Err("wallet not allowed"));
+    }
 }
@@ -0,0 +1,160 @@
+//! Fiat exchange rates for converting USD prices into other currencies
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::error::{Result, SurgeError};
+use crate::types::now_ms;
+
+/// Default FX endpoint (USD-based daily reference rates)
+pub const DEFAULT_FX_API_URL: &str = "https://api.exchangerate-api.com/v4/latest/USD";
+
+/// How long fetched rates are reused before refreshing
+const FX_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// USD-based exchange rate table
+#[derive(Debug, Clone, Deserialize)]
+pub struct FxTable {
+    /// Units of each currency per 1 USD
+    pub rates: HashMap<String, f64>,
+    /// When the provider last updated the rates (unix seconds)
+    #[serde(default)]
+    pub time_last_updated: Option<i64>,
+}
+
+impl FxTable {
+    /// Rate for a currency code (case-insensitive), i.e. units of `currency` per 1 USD
+    pub fn rate(&self, currency: &str) -> Option<f64> {
+        self.rates.get(&currency.to_uppercase()).copied()
+    }
+}
+
+/// Cached FX rate provider
+pub struct FxRates {
+    http: reqwest::Client,
+    url: String,
+    cache: RwLock<Option<(FxTable, i64, Instant)>>,
+}
+
+impl FxRates {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url: url.into(),
+            cache: RwLock::new(None),
+        }
+    }
+
+    /// Look up the USD → `currency` rate, returning `(rate, rate_timestamp_ms)`.
+    ///
+    /// Returns `Ok(None)` when the currency code is unknown to the provider.
+    pub async fn rate(&self, currency: &str) -> Result<Option<(f64, i64)>> {
+        if let Some((table, fetched_at_ms, fetched)) = self.cache.read().await.as_ref() {
+            if fetched.elapsed() < FX_CACHE_TTL {
+                return Ok(table.rate(currency).map(|r| (r, *fetched_at_ms)));
+            }
+        }
+
+        let table = self.fetch().await?;
+        let rate_at_ms = table
+            .time_last_updated
+            .map(|secs| secs * 1000)
+            .unwrap_or_else(now_ms);
+        let rate = table.rate(currency).map(|r| (r, rate_at_ms));
+        *self.cache.write().await = Some((table, rate_at_ms, Instant::now()));
+        Ok(rate)
+    }
+
+    async fn fetch(&self) -> Result<FxTable> {
+        let response = self
+            .http
+            .get(&self.url)
+            .send()
+            .await
+            .map_err(|e| SurgeError::FxError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(SurgeError::FxError(format!("FX API returned {}", response.status())));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| SurgeError::FxError(format!("Invalid FX response: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Json, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const FX_FIXTURE: &str = r#"{
+        "base": "USD",
+        "time_last_updated": 1705936800,
+        "rates": { "USD": 1.0, "EUR": 0.92, "JPY": 148.5 }
+    }"#;
+
+    /// Serve the fixture from a local server, counting requests
+    async fn mock_fx_api() -> (String, Arc<AtomicUsize>) {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = Router::new().route(
+            "/latest/USD",
+            get(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { Json(serde_json::from_str::<serde_json::Value>(FX_FIXTURE).unwrap()) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/latest/USD", addr), hits)
+    }
+
+    #[test]
+    fn test_fx_table_deserialization() {
+        let table: FxTable = serde_json::from_str(FX_FIXTURE).unwrap();
+        assert_eq!(table.rate("EUR"), Some(0.92));
+        assert_eq!(table.time_last_updated, Some(1705936800));
+    }
+
+    #[test]
+    fn test_fx_table_rate_is_case_insensitive() {
+        let table: FxTable = serde_json::from_str(FX_FIXTURE).unwrap();
+        assert_eq!(table.rate("eur"), Some(0.92));
+        assert_eq!(table.rate("XYZ"), None);
+    }
+
+    #[tokio::test]
+    async fn test_fx_rates_fetch_and_cache() {
+        let (url, hits) = mock_fx_api().await;
+        let fx = FxRates::new(url);
+
+        let (rate, at_ms) = fx.rate("EUR").await.unwrap().unwrap();
+        assert_eq!(rate, 0.92);
+        assert_eq!(at_ms, 1705936800000);
+
+        let (rate, _) = fx.rate("JPY").await.unwrap().unwrap();
+        assert_eq!(rate, 148.5);
+        assert_eq!(hits.load(Ordering::SeqCst), 1, "second lookup should hit the cache");
+    }
+
+    #[tokio::test]
+    async fn test_fx_rates_unknown_currency() {
+        let (url, _) = mock_fx_api().await;
+        let fx = FxRates::new(url);
+        assert!(fx.rate("XYZ").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fx_rates_unreachable_api() {
+        let fx = FxRates::new("http://127.0.0.1:1/latest/USD");
+        let err = fx.rate("EUR").await.unwrap_err();
+        assert!(matches!(err, SurgeError::FxError(_)));
+    }
+}
@@ -0,0 +1,199 @@
+//! In-memory price history fed by the price endpoints
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::types::now_ms;
+use crate::FeedPrice;
+
+/// How long samples are kept
+pub const DEFAULT_HISTORY_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Upper bound on samples kept per symbol
+const MAX_SAMPLES_PER_SYMBOL: usize = 10_000;
+
+/// Where a sample came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SampleSource {
+    /// Observed while serving a request
+    #[default]
+    Live,
+    /// Seeded at startup by [`super::routes::AppState::preload_cache`]
+    Preload,
+}
+
+impl SampleSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SampleSource::Live => "live",
+            SampleSource::Preload => "preload",
+        }
+    }
+}
+
+/// A single observed price
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceSample {
+    pub timestamp_ms: i64,
+    pub price: f64,
+    pub source: SampleSource,
+}
+
+/// Rolling per-symbol price samples
+pub struct PriceHistory {
+    retention_ms: i64,
+    samples: RwLock<HashMap<String, VecDeque<PriceSample>>>,
+}
+
+impl Default for PriceHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_RETENTION)
+    }
+}
+
+impl PriceHistory {
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            retention_ms: retention.as_millis() as i64,
+            samples: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Record a price observed now
+    pub fn record(&self, symbol: &str, price: f64) {
+        self.record_at(symbol, price, now_ms());
+    }
+
+    /// Record every price in a batch
+    pub fn record_all(&self, prices: &[FeedPrice]) {
+        let now = now_ms();
+        for price in prices {
+            self.record_at(&price.symbol, price.value, now);
+        }
+    }
+
+    /// Record a price with an explicit timestamp, dropping samples past retention
+    pub fn record_at(&self, symbol: &str, price: f64, timestamp_ms: i64) {
+        self.push(symbol, PriceSample { timestamp_ms, price, source: SampleSource::Live });
+    }
+
+    /// Record a last-known price fetched before serving traffic, tagged [`SampleSource::Preload`]
+    pub fn record_preload(&self, symbol: &str, price: f64) {
+        self.push(symbol, PriceSample { timestamp_ms: now_ms(), price, source: SampleSource::Preload });
+    }
+
+    fn push(&self, symbol: &str, sample: PriceSample) {
+        let mut samples = self.samples.write().unwrap_or_else(|e| e.into_inner());
+        let series = samples.entry(symbol.to_string()).or_default();
+        series.push_back(sample);
+
+        let cutoff = sample.timestamp_ms - self.retention_ms;
+        while series.front().is_some_and(|s| s.timestamp_ms < cutoff) || series.len() > MAX_SAMPLES_PER_SYMBOL {
+            series.pop_front();
+        }
+    }
+
+    /// Samples for `symbol` with `since_ms <= timestamp_ms <= until_ms`, oldest first
+    pub fn range(&self, symbol: &str, since_ms: i64, until_ms: i64) -> Vec<PriceSample> {
+        let samples = self.samples.read().unwrap_or_else(|e| e.into_inner());
+        samples
+            .get(symbol)
+            .map(|series| {
+                series
+                    .iter()
+                    .filter(|s| s.timestamp_ms >= since_ms && s.timestamp_ms <= until_ms)
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Symbols with at least one retained sample, sorted
+    pub fn symbols(&self) -> Vec<String> {
+        let samples = self.samples.read().unwrap_or_else(|e| e.into_inner());
+        let mut symbols: Vec<String> = samples.iter().filter(|(_, s)| !s.is_empty()).map(|(k, _)| k.clone()).collect();
+        symbols.sort();
+        symbols
+    }
+
+    /// Every retained sample for `symbol`, oldest first
+    pub fn all(&self, symbol: &str) -> Vec<PriceSample> {
+        self.range(symbol, i64::MIN, i64::MAX)
+    }
+
+    /// Samples for `symbol` from the last `window`, oldest first
+    pub fn window(&self, symbol: &str, window: Duration) -> Vec<PriceSample> {
+        let now = now_ms();
+        let window_ms = i64::try_from(window.as_millis()).unwrap_or(i64::MAX);
+        self.range(symbol, now.saturating_sub(window_ms), now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_range() {
+        let history = PriceHistory::default();
+        history.record_at("BTC/USD", 100.0, 1_000);
+        history.record_at("BTC/USD", 101.0, 2_000);
+        history.record_at("BTC/USD", 102.0, 3_000);
+        history.record_at("ETH/USD", 10.0, 2_000);
+
+        let prices: Vec<f64> = history.range("BTC/USD", 1_500, 3_000).iter().map(|s| s.price).collect();
+        assert_eq!(prices, vec![101.0, 102.0]);
+        assert!(history.range("SOL/USD", 0, i64::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_retention_drops_old_samples() {
+        let history = PriceHistory::new(Duration::from_secs(10));
+        history.record_at("BTC/USD", 100.0, 0);
+        history.record_at("BTC/USD", 101.0, 5_000);
+        history.record_at("BTC/USD", 102.0, 12_000);
+
+        let samples = history.range("BTC/USD", 0, i64::MAX);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].timestamp_ms, 5_000);
+    }
+
+    #[test]
+    fn test_window_uses_current_time() {
+        let history = PriceHistory::default();
+        history.record("BTC/USD", 100.0);
+        history.record_at("BTC/USD", 90.0, now_ms() - 120_000);
+
+        assert_eq!(history.window("BTC/USD", Duration::from_secs(60)).len(), 1);
+        assert_eq!(history.window("BTC/USD", Duration::from_secs(300)).len(), 2);
+    }
+
+    #[test]
+    fn test_record_preload_is_tagged() {
+        let history = PriceHistory::default();
+        history.record_preload("BTC/USD", 100.0);
+        history.record("BTC/USD", 101.0);
+
+        let sources: Vec<&str> = history.all("BTC/USD").iter().map(|s| s.source.as_str()).collect();
+        assert_eq!(sources, ["preload", "live"]);
+    }
+
+    #[test]
+    fn test_symbols_sorted() {
+        let history = PriceHistory::default();
+        history.record("SOL/USD", 1.0);
+        history.record("BTC/USD", 2.0);
+        assert_eq!(history.symbols(), ["BTC/USD", "SOL/USD"]);
+    }
+
+    #[test]
+    fn test_record_all() {
+        let history = PriceHistory::default();
+        history.record_all(&[
+            FeedPrice { symbol: "BTC/USD".to_string(), feed_id: "a".to_string(), value: 1.0, timestamp: None },
+            FeedPrice { symbol: "ETH/USD".to_string(), feed_id: "b".to_string(), value: 2.0, timestamp: None },
+        ]);
+        assert_eq!(history.window("ETH/USD", Duration::from_secs(60))[0].price, 2.0);
+    }
+}
@@ -0,0 +1,587 @@
+//! Analytics endpoints computed from the server's price history
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use futures_util::future::join_all;
+use std::time::Duration;
+
+use super::routes::{ApiResponse, AppState};
+use crate::analytics::{self, CandleAggregator, OhlcBar, Regime, ZScoreLevel};
+use crate::types::now_ms;
+
+/// Fewest history samples needed for a meaningful Z-score
+const MIN_ZSCORE_SAMPLES: usize = 30;
+
+/// Bounds on the number of symbols in an arbitrage matrix
+const MIN_MATRIX_SYMBOLS: usize = 2;
+const MAX_MATRIX_SYMBOLS: usize = 10;
+
+/// Most symbols in one heatmap
+const MAX_HEATMAP_SYMBOLS: usize = 200;
+
+fn default_window_m() -> u64 {
+    60
+}
+
+/// Lookback window in minutes
+#[derive(Deserialize)]
+pub struct WindowQuery {
+    #[serde(default = "default_window_m")]
+    pub window_m: u64,
+}
+
+/// Z-score of the current price against recent history
+#[derive(Debug, Serialize)]
+pub struct ZScoreResponse {
+    pub symbol: String,
+    pub current_price: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub z_score: f64,
+    pub window_m: u64,
+    pub sample_count: usize,
+    pub interpretation: ZScoreLevel,
+}
+
+fn default_ma_period() -> usize {
+    20
+}
+
+/// Moving average flavour
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MaType {
+    #[default]
+    Sma,
+    Ema,
+}
+
+/// Moving average parameters
+#[derive(Deserialize)]
+pub struct MovingAverageQuery {
+    #[serde(default = "default_ma_period")]
+    pub period: usize,
+    #[serde(rename = "type", default)]
+    pub ma_type: MaType,
+}
+
+/// Moving average over recorded history, compared with the latest tick
+#[derive(Debug, Serialize)]
+pub struct MovingAverageResponse {
+    pub symbol: String,
+    pub ma_type: MaType,
+    pub period: usize,
+    pub value: f64,
+    pub sample_count: usize,
+    pub current_price: f64,
+    pub current_is_above_ma: bool,
+}
+
+fn default_std_devs() -> f64 {
+    2.0
+}
+
+/// Bollinger Band parameters
+#[derive(Deserialize)]
+pub struct BollingerQuery {
+    #[serde(default = "default_ma_period")]
+    pub period: usize,
+    #[serde(default = "default_std_devs")]
+    pub std_devs: f64,
+}
+
+/// Bollinger Bands over recorded history, with the latest tick's position in them
+#[derive(Debug, Serialize)]
+pub struct BollingerResponse {
+    pub symbol: String,
+    pub upper_band: f64,
+    pub middle_band: f64,
+    pub lower_band: f64,
+    pub current_price: f64,
+    /// `(price - lower) / (upper - lower)`; `None` when the bands have zero width
+    pub percent_b: Option<f64>,
+    pub bandwidth_pct: f64,
+}
+
+/// Heatmap parameters; `symbols` defaults to every symbol in history
+#[derive(Deserialize)]
+pub struct HeatmapQuery {
+    pub symbols: Option<String>,
+    #[serde(default = "default_window_m")]
+    pub window_m: u64,
+}
+
+/// Sign of a price change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Up,
+    Down,
+    Flat,
+}
+
+/// Suggested cell colour for a price change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorHint {
+    Green,
+    Red,
+    Neutral,
+}
+
+/// One heatmap cell
+#[derive(Debug, Serialize)]
+pub struct HeatmapItem {
+    pub symbol: String,
+    pub current_price: f64,
+    pub price_at_window_start: f64,
+    pub change_pct: f64,
+    pub direction: Direction,
+    pub color_hint: ColorHint,
+}
+
+impl HeatmapItem {
+    fn new(symbol: String, current_price: f64, price_at_window_start: f64) -> Self {
+        let change_pct = (current_price - price_at_window_start) / price_at_window_start * 100.0;
+        let (direction, color_hint) = match change_pct {
+            c if c > 0.0 => (Direction::Up, ColorHint::Green),
+            c if c < 0.0 => (Direction::Down, ColorHint::Red),
+            _ => (Direction::Flat, ColorHint::Neutral),
+        };
+        Self {
+            symbol,
+            current_price,
+            price_at_window_start,
+            change_pct,
+            direction,
+            color_hint,
+        }
+    }
+}
+
+/// Price changes over a window, largest moves first
+#[derive(Debug, Serialize)]
+pub struct HeatmapResponse {
+    pub computed_at_ms: i64,
+    pub items: Vec<HeatmapItem>,
+}
+
+/// Market regime over recent history
+#[derive(Debug, Serialize)]
+pub struct RegimeResponse {
+    pub symbol: String,
+    pub window_m: u64,
+    pub sample_count: usize,
+    #[serde(flatten)]
+    pub regime: Regime,
+}
+
+/// Time range for a single candle
+#[derive(Deserialize)]
+pub struct CandleQuery {
+    pub since_ms: i64,
+    pub until_ms: i64,
+}
+
+/// One OHLC bar over a caller-defined range
+#[derive(Debug, Serialize)]
+pub struct CandleResponse {
+    pub symbol: String,
+    pub since_ms: i64,
+    pub until_ms: i64,
+    #[serde(flatten)]
+    pub bar: OhlcBar,
+}
+
+/// Comma-separated symbols
+#[derive(Deserialize)]
+pub struct SymbolsQuery {
+    pub symbols: String,
+}
+
+/// Pairwise implied cross rates
+#[derive(Debug, Serialize)]
+pub struct ArbitrageMatrixResponse {
+    pub symbols: Vec<String>,
+    /// `matrix[i][j]` is the price of `symbols[i]` in units of `symbols[j]`
+    pub matrix: Vec<Vec<f64>>,
+    pub computed_at_ms: i64,
+    /// Spread between the first and last price fetch to complete
+    pub max_price_age_ms: i64,
+}
+
+fn unprocessable(message: impl Into<String>) -> axum::response::Response {
+    (StatusCode::UNPROCESSABLE_ENTITY, ApiResponse::<()>::error(message)).into_response()
+}
+
+/// `window_m` minutes as a `Duration`; errors when the seconds overflow `u64`
+fn window_from_minutes(window_m: u64) -> Result<Duration, String> {
+    window_m
+        .checked_mul(60)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("window_m {} is too large", window_m))
+}
+
+/// Z-score of the current price over the last `window_m` minutes
+/// GET /v1/prices/:symbol/zscore?window_m=60
+pub async fn get_zscore(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<WindowQuery>,
+) -> impl IntoResponse {
+    let window = match window_from_minutes(query.window_m) {
+        Ok(window) => window,
+        Err(message) => return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(message)).into_response(),
+    };
+    let symbol = crate::normalize_symbol(&symbol);
+    let history: Vec<f64> = state
+        .history
+        .window(&symbol, window)
+        .iter()
+        .map(|s| s.price)
+        .collect();
+
+    if history.len() < MIN_ZSCORE_SAMPLES {
+        return unprocessable(format!(
+            "Need at least {} samples in the last {}m, have {}",
+            MIN_ZSCORE_SAMPLES,
+            query.window_m,
+            history.len()
+        ));
+    }
+
+    let current = match state.fetch_price(&symbol).await {
+        Ok(price) => price.value,
+        Err(e) => return (e.status_code(), ApiResponse::<()>::error(e.to_string())).into_response(),
+    };
+
+    let (Some(mean), Some(std_dev), Some(z_score)) = (
+        analytics::mean(&history),
+        analytics::std_dev(&history),
+        analytics::z_score(current, &history),
+    ) else {
+        return unprocessable("Price history has zero variance");
+    };
+
+    let response = ZScoreResponse {
+        symbol,
+        current_price: current,
+        mean,
+        std_dev,
+        z_score,
+        window_m: query.window_m,
+        sample_count: history.len(),
+        interpretation: ZScoreLevel::from_z(z_score),
+    };
+    (StatusCode::OK, ApiResponse::success(response)).into_response()
+}
+
+/// SMA or EMA over recorded history; the latest tick is the current price
+/// GET /v1/prices/:symbol/moving-average?period=20&type=sma|ema
+pub async fn get_moving_average(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<MovingAverageQuery>,
+) -> impl IntoResponse {
+    if query.period == 0 {
+        return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error("period must be at least 1")).into_response();
+    }
+
+    let symbol = crate::normalize_symbol(&symbol);
+    let history: Vec<f64> = state.history.all(&symbol).iter().map(|s| s.price).collect();
+    let value = match query.ma_type {
+        MaType::Sma => analytics::sma(&history, query.period),
+        MaType::Ema => analytics::ema(&history, query.period),
+    };
+    let (Some(value), Some(&current_price)) = (value, history.last()) else {
+        return unprocessable(format!(
+            "Need at least {} samples, have {}",
+            query.period,
+            history.len()
+        ));
+    };
+
+    let response = MovingAverageResponse {
+        symbol,
+        ma_type: query.ma_type,
+        period: query.period,
+        value,
+        sample_count: history.len(),
+        current_price,
+        current_is_above_ma: current_price > value,
+    };
+    (StatusCode::OK, ApiResponse::success(response)).into_response()
+}
+
+/// Bollinger Bands over the last `period` recorded ticks
+/// GET /v1/prices/:symbol/bollinger?period=20&std_devs=2
+pub async fn get_bollinger(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<BollingerQuery>,
+) -> impl IntoResponse {
+    if query.period == 0 || !query.std_devs.is_finite() || query.std_devs < 0.0 {
+        let message = "period must be at least 1 and std_devs non-negative";
+        return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(message)).into_response();
+    }
+
+    let symbol = crate::normalize_symbol(&symbol);
+    let history: Vec<f64> = state.history.all(&symbol).iter().map(|s| s.price).collect();
+    let (Some((upper, middle, lower)), Some(&current_price)) =
+        (analytics::bollinger_bands(&history, query.period, query.std_devs), history.last())
+    else {
+        return unprocessable(format!(
+            "Need at least {} samples, have {}",
+            query.period,
+            history.len()
+        ));
+    };
+
+    let width = upper - lower;
+    let response = BollingerResponse {
+        symbol,
+        upper_band: upper,
+        middle_band: middle,
+        lower_band: lower,
+        current_price,
+        percent_b: (width > 0.0).then(|| (current_price - lower) / width),
+        bandwidth_pct: width / middle * 100.0,
+    };
+    (StatusCode::OK, ApiResponse::success(response)).into_response()
+}
+
+/// Trending vs ranging classification of the last `window_m` minutes of ticks
+/// GET /v1/prices/:symbol/regime?window_m=60
+pub async fn get_regime(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<WindowQuery>,
+) -> impl IntoResponse {
+    let window = match window_from_minutes(query.window_m) {
+        Ok(window) => window,
+        Err(message) => return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(message)).into_response(),
+    };
+    let symbol = crate::normalize_symbol(&symbol);
+    let history: Vec<f64> = state
+        .history
+        .window(&symbol, window)
+        .iter()
+        .map(|s| s.price)
+        .collect();
+
+    let response = RegimeResponse {
+        symbol,
+        window_m: query.window_m,
+        sample_count: history.len(),
+        regime: analytics::classify_regime(&history),
+    };
+    (StatusCode::OK, ApiResponse::success(response)).into_response()
+}
+
+/// Single OHLC candle from recorded ticks in `[since_ms, until_ms]`
+/// GET /v1/prices/:symbol/candle?since_ms=<t1>&until_ms=<t2>
+pub async fn get_candle(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<CandleQuery>,
+) -> impl IntoResponse {
+    if query.since_ms >= query.until_ms {
+        return unprocessable("since_ms must be before until_ms");
+    }
+
+    let symbol = crate::normalize_symbol(&symbol);
+    let mut aggregator = CandleAggregator::new();
+    for sample in state.history.range(&symbol, query.since_ms, query.until_ms) {
+        aggregator.push(sample.price);
+    }
+
+    match aggregator.finish() {
+        Some(bar) => {
+            let response = CandleResponse {
+                symbol,
+                since_ms: query.since_ms,
+                until_ms: query.until_ms,
+                bar,
+            };
+            (StatusCode::OK, ApiResponse::success(response)).into_response()
+        }
+        None => unprocessable(format!("No price data for {} in the requested range", symbol)),
+    }
+}
+
+/// N×N implied cross-rate matrix for 2-10 symbols
+/// GET /v1/prices/arbitrage-matrix?symbols=BTC,ETH,SOL
+pub async fn get_arbitrage_matrix(State(state): State<AppState>, Query(query): Query<SymbolsQuery>) -> impl IntoResponse {
+    let symbols: Vec<String> = query
+        .symbols
+        .split(',')
+        .map(|s| s.trim().to_uppercase())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if !(MIN_MATRIX_SYMBOLS..=MAX_MATRIX_SYMBOLS).contains(&symbols.len()) {
+        let message = format!("Provide between {} and {} symbols", MIN_MATRIX_SYMBOLS, MAX_MATRIX_SYMBOLS);
+        return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(message)).into_response();
+    }
+
+    let results = join_all(symbols.iter().map(|symbol| async {
+        let price = state.fetch_price(symbol).await;
+        (price, now_ms())
+    }))
+    .await;
+
+    let mut prices = Vec::with_capacity(results.len());
+    let mut fetched_at = Vec::with_capacity(results.len());
+    for (price, at_ms) in results {
+        match price {
+            Ok(price) => prices.push(price.value),
+            Err(e) => return (e.status_code(), ApiResponse::<()>::error(e.to_string())).into_response(),
+        }
+        fetched_at.push(at_ms);
+    }
+
+    let oldest = fetched_at.iter().min().copied().unwrap_or_default();
+    let newest = fetched_at.iter().max().copied().unwrap_or_default();
+    let response = ArbitrageMatrixResponse {
+        symbols,
+        matrix: analytics::cross_rate_matrix(&prices),
+        computed_at_ms: now_ms(),
+        max_price_age_ms: newest - oldest,
+    };
+    (StatusCode::OK, ApiResponse::success(response)).into_response()
+}
+
+/// Percentage change per symbol against the oldest tick in the last `window_m` minutes.
+/// Symbols without history in the window, or whose price can't be fetched, are left out.
+/// GET /v1/prices/heatmap?symbols=BTC,ETH,SOL&window_m=60
+pub async fn get_heatmap(State(state): State<AppState>, Query(query): Query<HeatmapQuery>) -> impl IntoResponse {
+    let symbols: Vec<String> = match &query.symbols {
+        Some(symbols) => symbols
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(crate::normalize_symbol)
+            .collect(),
+        None => state.history.symbols().into_iter().take(MAX_HEATMAP_SYMBOLS).collect(),
+    };
+    if symbols.len() > MAX_HEATMAP_SYMBOLS {
+        let message = format!("At most {} symbols per request", MAX_HEATMAP_SYMBOLS);
+        return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(message)).into_response();
+    }
+
+    // Read baselines before fetching, since fetching records a new sample
+    let window = match window_from_minutes(query.window_m) {
+        Ok(window) => window,
+        Err(message) => return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(message)).into_response(),
+    };
+    let baselines: Vec<(String, f64)> = symbols
+        .into_iter()
+        .filter_map(|symbol| {
+            let start = state.history.window(&symbol, window).first()?.price;
+            Some((symbol, start))
+        })
+        .collect();
+
+    let prices = join_all(baselines.iter().map(|(symbol, _)| state.fetch_price(symbol))).await;
+    let mut items: Vec<HeatmapItem> = baselines
+        .into_iter()
+        .zip(prices)
+        .filter_map(|((symbol, start), price)| Some(HeatmapItem::new(symbol, price.ok()?.value, start)))
+        .collect();
+    items.sort_by(|a, b| b.change_pct.abs().total_cmp(&a.change_pct.abs()));
+
+    let response = HeatmapResponse {
+        computed_at_ms: now_ms(),
+        items,
+    };
+    (StatusCode::OK, ApiResponse::success(response)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_query_default() {
+        let query: WindowQuery = serde_json::from_str("{}").unwrap();
+        assert_eq!(query.window_m, 60);
+    }
+
+    #[test]
+    fn test_window_from_minutes() {
+        assert_eq!(window_from_minutes(60).unwrap(), Duration::from_secs(3600));
+        assert!(window_from_minutes(u64::MAX).unwrap_err().contains("too large"));
+    }
+
+    #[test]
+    fn test_moving_average_query_defaults() {
+        let query: MovingAverageQuery = serde_json::from_str("{}").unwrap();
+        assert_eq!(query.period, 20);
+        assert_eq!(query.ma_type, MaType::Sma);
+
+        let query: MovingAverageQuery = serde_json::from_str(r#"{"period": 5, "type": "ema"}"#).unwrap();
+        assert_eq!(query.ma_type, MaType::Ema);
+    }
+
+    #[test]
+    fn test_bollinger_query_defaults() {
+        let query: BollingerQuery = serde_json::from_str("{}").unwrap();
+        assert_eq!(query.period, 20);
+        assert_eq!(query.std_devs, 2.0);
+    }
+
+    #[test]
+    fn test_heatmap_item_change_and_hints() {
+        let up = HeatmapItem::new("BTC/USD".to_string(), 110.0, 100.0);
+        assert!((up.change_pct - 10.0).abs() < 1e-9);
+        assert_eq!((up.direction, up.color_hint), (Direction::Up, ColorHint::Green));
+
+        let down = HeatmapItem::new("ETH/USD".to_string(), 95.0, 100.0);
+        assert!((down.change_pct + 5.0).abs() < 1e-9);
+        assert_eq!((down.direction, down.color_hint), (Direction::Down, ColorHint::Red));
+
+        let flat = HeatmapItem::new("SOL/USD".to_string(), 100.0, 100.0);
+        assert_eq!(flat.change_pct, 0.0);
+        let json = serde_json::to_value(&flat).unwrap();
+        assert_eq!(json["direction"], "flat");
+        assert_eq!(json["color_hint"], "neutral");
+    }
+
+    #[test]
+    fn test_zscore_response_serialization() {
+        let response = ZScoreResponse {
+            symbol: "BTC/USD".to_string(),
+            current_price: 105.0,
+            mean: 100.0,
+            std_dev: 2.0,
+            z_score: 2.5,
+            window_m: 60,
+            sample_count: 30,
+            interpretation: ZScoreLevel::from_z(2.5),
+        };
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["interpretation"], "elevated");
+        assert_eq!(json["sample_count"], 30);
+    }
+
+    #[test]
+    fn test_candle_response_flattens_bar() {
+        let mut aggregator = CandleAggregator::new();
+        aggregator.push(1.0);
+        aggregator.push(2.0);
+        let response = CandleResponse {
+            symbol: "BTC/USD".to_string(),
+            since_ms: 0,
+            until_ms: 10,
+            bar: aggregator.finish().unwrap(),
+        };
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["open"], 1.0);
+        assert_eq!(json["close"], 2.0);
+        assert_eq!(json["tick_count"], 2);
+    }
+}
@@ -63,11 +63,17 @@ pub fn ws_connection_opened() {
 }
 
 /// Decrement active WebSocket connection count
+#[allow(clippy::unnecessary_min_or_max)]
 pub fn ws_connection_closed() {
     let count = ACTIVE_WS_CONNECTIONS.fetch_sub(1, Ordering::SeqCst) - 1;
     gauge!("active_websocket_connections").set(count.max(0) as f64);
 }
 
+/// Count a price request for a registered `symbol`
+pub fn price_requested(symbol: &str) {
+    counter!("surge_price_requests_total", "symbol" => symbol.to_string()).increment(1);
+}
+
 /// Get current active WebSocket connection count
 pub fn get_active_ws_connections() -> usize {
     ACTIVE_WS_CONNECTIONS.load(Ordering::SeqCst)
@@ -83,6 +89,21 @@ pub fn reset_ws_connections() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_price_requested_labels_symbol() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        metrics::with_local_recorder(&recorder, || {
+            price_requested("BTC/USD");
+            price_requested("BTC/USD");
+            price_requested("ETH/USD");
+        });
+
+        let rendered = handle.render();
+        assert!(rendered.contains(r#"surge_price_requests_total{symbol="BTC/USD"} 2"#));
+        assert!(rendered.contains(r#"surge_price_requests_total{symbol="ETH/USD"} 1"#));
+    }
+
     #[test]
     fn test_ws_connection_counter_initial() {
         reset_ws_connections();
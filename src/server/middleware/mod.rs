@@ -0,0 +1,7 @@
+//! Request-scoped middleware
+
+pub mod rate_limit;
+pub mod request_id;
+
+pub use rate_limit::{rate_limit, RateLimiter, DEFAULT_RATE_LIMIT_RPM};
+pub use request_id::{inject_request_id, RequestId, REQUEST_ID_HEADER};
@@ -0,0 +1,135 @@
+//! Per-client token-bucket rate limiting for `/v1/*` routes
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::server::routes::{ApiResponse, AppState};
+
+/// Requests per minute when `SURGE_RATE_LIMIT_RPM` is unset
+pub const DEFAULT_RATE_LIMIT_RPM: u32 = 60;
+
+/// Tracked clients above which full (idle) buckets are dropped
+const PRUNE_THRESHOLD: usize = 10_000;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token buckets holding up to `rpm` requests per client, refilled continuously
+#[derive(Clone)]
+pub struct RateLimiter {
+    rpm: u32,
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+impl RateLimiter {
+    /// Allow `rpm` requests per minute per client; 0 disables limiting
+    pub fn new(rpm: u32) -> Self {
+        Self { rpm, buckets: Arc::default() }
+    }
+
+    /// Take a token for `client`, or the whole seconds until one is available
+    pub fn check(&self, client: &str) -> Result<(), u64> {
+        self.check_at(client, Instant::now())
+    }
+
+    fn check_at(&self, client: &str, now: Instant) -> Result<(), u64> {
+        if self.rpm == 0 {
+            return Ok(());
+        }
+        let capacity = f64::from(self.rpm);
+        let per_sec = capacity / 60.0;
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() > PRUNE_THRESHOLD {
+            buckets.retain(|_, b| b.tokens + now.saturating_duration_since(b.updated).as_secs_f64() * per_sec < capacity);
+        }
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket { tokens: capacity, updated: now });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_sec).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) / per_sec).ceil() as u64)
+        }
+    }
+}
+
+/// Bucket key: the API key when auth is on, otherwise the peer IP
+fn client_key(state: &AppState, request: &Request) -> String {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if let (Some(_), Some(token)) = (state.api_key.as_deref(), token) {
+        return format!("key:{}", token);
+    }
+    match request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(addr)) => format!("ip:{}", addr.ip()),
+        None => "ip:unknown".to_string(),
+    }
+}
+
+/// Reject with `429` and `Retry-After` once a client's bucket is empty
+pub async fn rate_limit(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let client = client_key(&state, &request);
+    match state.rate_limiter.check(&client) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after_secs) => {
+            tracing::warn!("Rate limit exceeded for {}", client.split(':').next().unwrap_or_default());
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                ApiResponse::<()>::error("Rate limit exceeded"),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bucket_empties_then_refills() {
+        let limiter = RateLimiter::new(2);
+        let start = Instant::now();
+        assert!(limiter.check_at("a", start).is_ok());
+        assert!(limiter.check_at("a", start).is_ok());
+        assert_eq!(limiter.check_at("a", start), Err(30));
+
+        // One token every 30s at 2 rpm
+        assert_eq!(limiter.check_at("a", start + Duration::from_millis(20_500)), Err(10));
+        assert!(limiter.check_at("a", start + Duration::from_secs(31)).is_ok());
+    }
+
+    #[test]
+    fn test_clients_have_separate_buckets() {
+        let limiter = RateLimiter::new(1);
+        let now = Instant::now();
+        assert!(limiter.check_at("a", now).is_ok());
+        assert!(limiter.check_at("a", now).is_err());
+        assert!(limiter.check_at("b", now).is_ok());
+    }
+
+    #[test]
+    fn test_zero_rpm_disables_limit() {
+        let limiter = RateLimiter::new(0);
+        assert!((0..1000).all(|_| limiter.check("a").is_ok()));
+    }
+}
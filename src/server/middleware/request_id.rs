@@ -0,0 +1,85 @@
+//! Per-request IDs, taken from or echoed in the `x-request-id` header
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::{request::Parts, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::convert::Infallible;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied ID that is kept; longer ones are replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT: RequestId;
+}
+
+/// ID of the request being handled; usable as a handler argument
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// ID of the request running on this task, if [`inject_request_id`] set one
+    pub fn current() -> Option<String> {
+        CURRENT.try_with(|id| id.0.clone()).ok()
+    }
+
+    fn generate() -> Self {
+        Self(uuid::Uuid::new_v4().to_string())
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestId {
+    type Rejection = Infallible;
+
+    /// Falls back to a fresh ID when the middleware is not installed
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts.extensions.get::<RequestId>().cloned().unwrap_or_else(RequestId::generate))
+    }
+}
+
+/// Reuse the client's `x-request-id` or generate a UUID v4, store it in the
+/// request extensions and echo it on the response
+pub async fn inject_request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= MAX_REQUEST_ID_LEN)
+        .map(|value| RequestId(value.to_string()))
+        .unwrap_or_else(RequestId::generate);
+
+    request.extensions_mut().insert(id.clone());
+    let mut response = CURRENT.scope(id.clone(), next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&id.0) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_outside_request_is_none() {
+        assert!(RequestId::current().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_current_inside_scope() {
+        let id = CURRENT.scope(RequestId("abc".to_string()), async { RequestId::current() }).await;
+        assert_eq!(id.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn test_generated_id_is_uuid() {
+        let RequestId(id) = RequestId::generate();
+        assert!(uuid::Uuid::parse_str(&id).is_ok());
+    }
+}
@@ -1,11 +1,31 @@
 //! Production API server for I Am Surging
 //!
 //! Provides REST and WebSocket APIs for cryptocurrency price data.
+//!
+//! # API versions
+//!
+//! `/v1` ([`routes`]) and `/v2` ([`routes_v2`]) are served side by side from
+//! the same [`routes::AppState`], behind the same auth and rate limit. A
+//! version's response shapes never change once released; breaking changes go
+//! into the next version instead. `/v2` currently covers the price endpoints
+//! and differs only in sending `price` as a decimal string with a `precision`
+//! field. To migrate, switch `/v1/prices...` to `/v2/prices...` and parse
+//! `price` as a decimal. Everything else stays on `/v1` for now.
 
 pub mod app;
 pub mod auth;
+pub mod fx;
+pub mod history;
+pub mod indicators;
 pub mod metrics;
+pub mod middleware;
+pub mod pool;
 pub mod routes;
+pub mod routes_v2;
+#[cfg(feature = "multi-source")]
+pub mod spread;
+#[cfg(feature = "otel")]
+pub mod telemetry;
 pub mod websocket;
 
 pub use app::create_app;
@@ -0,0 +1,235 @@
+//! Upstream Surge connections shared between WebSocket clients
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::{broadcast, Mutex};
+
+use crate::{EventCounter, Result, Surge, SurgeClient, SurgeEvent};
+
+/// Subscription set, sorted so equal sets share a key
+type PoolKey = Vec<String>;
+
+struct PoolEntry {
+    surge: Arc<Surge>,
+    subscribers: usize,
+}
+
+/// One upstream [`Surge`] connection per distinct symbol set, shared by every
+/// client subscribed to exactly that set and closed when the last one leaves
+#[derive(Clone, Default)]
+pub struct SharedSurgePool {
+    entries: Arc<Mutex<HashMap<PoolKey, PoolEntry>>>,
+    events: EventCounter,
+    snapshot_client: Option<Arc<SurgeClient>>,
+}
+
+/// A client's hold on a pooled connection; give it back with [`SharedSurgePool::release`],
+/// or hold a [`PoolLease`] instead to release it on drop
+pub struct PooledSurge {
+    key: PoolKey,
+    surge: Arc<Surge>,
+}
+
+impl PooledSurge {
+    /// Events from the shared upstream connection
+    pub fn subscribe_events(&self) -> broadcast::Receiver<SurgeEvent> {
+        self.surge.subscribe_events()
+    }
+
+    /// Symbols this connection is subscribed to, sorted
+    pub fn symbols(&self) -> &[String] {
+        &self.key
+    }
+}
+
+impl SharedSurgePool {
+    /// Pool whose connections all count events into `events`
+    pub fn new(events: EventCounter) -> Self {
+        Self { entries: Arc::default(), events, snapshot_client: None }
+    }
+
+    /// Fetch connect snapshots for every pooled connection with `client`
+    pub fn with_snapshot_client(mut self, client: Arc<SurgeClient>) -> Self {
+        self.snapshot_client = Some(client);
+        self
+    }
+
+    /// Join the connection for `symbols`, opening it if no client holds a live one
+    pub async fn acquire(&self, symbols: &HashSet<String>) -> Result<PooledSurge> {
+        let mut key: PoolKey = symbols.iter().cloned().collect();
+        key.sort_unstable();
+
+        if let Some(pooled) = join_live(&mut *self.entries.lock().await, &key) {
+            return Ok(pooled);
+        }
+
+        // Connect without the lock held, then re-check in case another client got there first
+        let mut surge = Surge::new("").with_event_counter(self.events.clone());
+        if let Some(client) = &self.snapshot_client {
+            surge = surge.with_snapshot_client(client.clone());
+        }
+        surge.connect_and_subscribe(key.iter().map(String::as_str).collect()).await?;
+
+        let mut entries = self.entries.lock().await;
+        if let Some(pooled) = join_live(&mut entries, &key) {
+            drop(entries);
+            let _ = surge.disconnect().await;
+            return Ok(pooled);
+        }
+        let surge = Arc::new(surge);
+        entries.insert(key.clone(), PoolEntry { surge: surge.clone(), subscribers: 1 });
+        Ok(PooledSurge { key, surge })
+    }
+
+    /// [`Self::acquire`] wrapped in a guard that releases the connection when dropped
+    pub async fn lease(&self, symbols: &HashSet<String>) -> Result<PoolLease> {
+        let pooled = self.acquire(symbols).await?;
+        Ok(PoolLease { pool: self.clone(), pooled: Some(pooled) })
+    }
+
+    /// Leave a connection, disconnecting it if this was the last subscriber
+    pub async fn release(&self, pooled: PooledSurge) {
+        let mut entries = self.entries.lock().await;
+        // A dead connection may have been replaced under the same key since
+        let Some(entry) = entries.get_mut(&pooled.key).filter(|entry| Arc::ptr_eq(&entry.surge, &pooled.surge)) else {
+            return;
+        };
+        entry.subscribers -= 1;
+        if entry.subscribers == 0 {
+            if let Some(entry) = entries.remove(&pooled.key) {
+                let _ = entry.surge.disconnect().await;
+            }
+        }
+    }
+
+    /// Number of open upstream connections
+    pub async fn len(&self) -> usize {
+        self.entries.lock().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.entries.lock().await.is_empty()
+    }
+}
+
+/// Join the entry for `key` if its connection is still running, evicting it if not
+fn join_live(entries: &mut HashMap<PoolKey, PoolEntry>, key: &PoolKey) -> Option<PooledSurge> {
+    let entry = entries.get_mut(key)?;
+    if !entry.surge.is_running() {
+        entries.remove(key);
+        return None;
+    }
+    entry.subscribers += 1;
+    Some(PooledSurge { key: key.clone(), surge: entry.surge.clone() })
+}
+
+/// Hands a pooled connection back to its pool when dropped
+pub struct PoolLease {
+    pool: SharedSurgePool,
+    pooled: Option<PooledSurge>,
+}
+
+impl PoolLease {
+    /// Events from the shared upstream connection
+    pub fn subscribe_events(&self) -> broadcast::Receiver<SurgeEvent> {
+        self.pooled.as_ref().expect("held until drop").subscribe_events()
+    }
+
+    /// Symbols this connection is subscribed to, sorted
+    pub fn symbols(&self) -> &[String] {
+        self.pooled.as_ref().expect("held until drop").symbols()
+    }
+}
+
+impl Drop for PoolLease {
+    fn drop(&mut self) {
+        if let Some(pooled) = self.pooled.take() {
+            let pool = self.pool.clone();
+            tokio::spawn(async move { pool.release(pooled).await });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(symbols: &[&str]) -> HashSet<String> {
+        symbols.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_same_symbol_set_shares_connection() {
+        let pool = SharedSurgePool::default();
+        let a = pool.acquire(&set(&["BTC/USD", "ETH/USD"])).await.unwrap();
+        let b = pool.acquire(&set(&["ETH/USD", "BTC/USD"])).await.unwrap();
+
+        assert!(Arc::ptr_eq(&a.surge, &b.surge));
+        assert_eq!(pool.len().await, 1);
+        assert_eq!(a.symbols(), ["BTC/USD", "ETH/USD"]);
+
+        pool.release(a).await;
+        pool.release(b).await;
+    }
+
+    #[tokio::test]
+    async fn test_different_symbol_sets_get_own_connections() {
+        let pool = SharedSurgePool::default();
+        let a = pool.acquire(&set(&["BTC/USD"])).await.unwrap();
+        let b = pool.acquire(&set(&["BTC/USD", "SOL/USD"])).await.unwrap();
+
+        assert!(!Arc::ptr_eq(&a.surge, &b.surge));
+        assert_eq!(pool.len().await, 2);
+
+        pool.release(a).await;
+        pool.release(b).await;
+    }
+
+    #[tokio::test]
+    async fn test_entry_removed_when_last_subscriber_releases() {
+        let pool = SharedSurgePool::default();
+        let a = pool.acquire(&set(&["BTC/USD"])).await.unwrap();
+        let b = pool.acquire(&set(&["BTC/USD"])).await.unwrap();
+
+        pool.release(a).await;
+        assert_eq!(pool.len().await, 1);
+        pool.release(b).await;
+        assert!(pool.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_dead_entry_is_replaced() {
+        let pool = SharedSurgePool::default();
+        let key = vec!["BTC/USD".to_string()];
+        // Never connected, so its loop is not running
+        let dead = Arc::new(Surge::new(""));
+        pool.entries.lock().await.insert(key.clone(), PoolEntry { surge: dead.clone(), subscribers: 1 });
+
+        let a = pool.acquire(&set(&["BTC/USD"])).await.unwrap();
+        assert!(!Arc::ptr_eq(&a.surge, &dead));
+        assert_eq!(pool.entries.lock().await[&key].subscribers, 1);
+
+        // Releasing the dead connection leaves its replacement alone
+        pool.release(PooledSurge { key: key.clone(), surge: dead }).await;
+        assert_eq!(pool.entries.lock().await[&key].subscribers, 1);
+        pool.release(a).await;
+        assert!(pool.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_lease_releases_on_drop() {
+        let pool = SharedSurgePool::default();
+        let lease = pool.lease(&set(&["BTC/USD"])).await.unwrap();
+        assert_eq!(lease.symbols(), ["BTC/USD"]);
+        assert_eq!(pool.len().await, 1);
+
+        drop(lease);
+        tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            while !pool.is_empty().await {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("dropping the lease should release the connection");
+    }
+}
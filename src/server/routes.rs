@@ -2,31 +2,221 @@
 
 use axum::{
     extract::{Path, Query},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
     Json,
 };
+use futures_util::{stream, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio_stream::wrappers::BroadcastStream;
+
+use super::fx::{FxRates, DEFAULT_FX_API_URL};
+use super::history::PriceHistory;
+use super::middleware::{RateLimiter, RequestId, DEFAULT_RATE_LIMIT_RPM};
+use super::pool::SharedSurgePool;
 use crate::error::SurgeError;
-use crate::SurgeClient;
+use crate::{EventCounter, FeedLoader, FeedPrice, SurgeClient};
+
+/// Suggestions offered for an unknown symbol
+const SUGGESTION_COUNT: usize = 3;
+
+/// Results returned by `/v1/symbols?fuzzy=`
+const FUZZY_RESULT_COUNT: usize = 10;
+
+/// `/v1/symbols` page size when only `page` is given, and the largest allowed
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 500;
+
+/// Most symbols accepted by `/v1/prices`, whether as `?symbols=` or a POST body
+const MAX_BODY_SYMBOLS: usize = 100;
+
+/// Fetches in flight at once when `/v1/prices?group_by=` covers every feed
+const ALL_SYMBOLS_CONCURRENCY: usize = 16;
+
+/// Interval between SSE keep-alive comments on `/v1/stream/sse`
+const SSE_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Settings used to build [`AppState`], injectable for tests
+#[derive(Debug, Clone)]
+pub struct AppStateConfig {
+    /// API key required on `/v1/*` routes; `None` disables auth
+    pub api_key: Option<String>,
+    /// Exchange rate source for `?currency=` conversion
+    pub fx_api_url: String,
+    /// Feed registry file; the default `feedIds.json` lookup when `None`
+    pub feeds_path: Option<PathBuf>,
+    /// Mount the `/v1/debug/*` diagnostics routes
+    pub debug_endpoints: bool,
+    /// Requests per minute allowed per client on `/v1/*`; 0 disables limiting
+    pub rate_limit_rpm: u32,
+    /// Base58 wallet addresses whose signed requests are accepted without an API key
+    #[cfg(feature = "solana")]
+    pub allowed_wallets: Vec<String>,
+}
+
+impl Default for AppStateConfig {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            fx_api_url: DEFAULT_FX_API_URL.to_string(),
+            feeds_path: None,
+            debug_endpoints: false,
+            rate_limit_rpm: DEFAULT_RATE_LIMIT_RPM,
+            #[cfg(feature = "solana")]
+            allowed_wallets: Vec::new(),
+        }
+    }
+}
+
+impl AppStateConfig {
+    /// Read `SURGE_API_KEY`, `SURGE_FX_API_URL`, `SURGE_DEBUG_ENDPOINTS`,
+    /// `SURGE_RATE_LIMIT_RPM` and (with `solana`) `SURGE_ALLOWED_WALLETS` from the environment
+    pub fn from_env() -> Self {
+        Self {
+            api_key: std::env::var("SURGE_API_KEY").ok().filter(|k| !k.is_empty()),
+            fx_api_url: std::env::var("SURGE_FX_API_URL").unwrap_or_else(|_| DEFAULT_FX_API_URL.to_string()),
+            feeds_path: None,
+            debug_endpoints: std::env::var("SURGE_DEBUG_ENDPOINTS").is_ok_and(|v| v == "true"),
+            rate_limit_rpm: std::env::var("SURGE_RATE_LIMIT_RPM")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_RATE_LIMIT_RPM),
+            #[cfg(feature = "solana")]
+            allowed_wallets: std::env::var("SURGE_ALLOWED_WALLETS")
+                .map(|v| v.split(',').map(str::trim).filter(|w| !w.is_empty()).map(str::to_owned).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
 
 /// Shared application state
 #[derive(Clone)]
 pub struct AppState {
     pub client: Arc<SurgeClient>,
     pub ready: Arc<std::sync::atomic::AtomicBool>,
+    pub fx: Arc<FxRates>,
+    pub api_key: Option<Arc<str>>,
+    /// Upstream events relayed across all WebSocket clients
+    pub events: EventCounter,
+    pub started_at: Instant,
+    /// Prices observed by the REST endpoints, used for analytics
+    pub history: Arc<PriceHistory>,
+    /// Feed registry shared with `client`, swappable via [`AppState::reload_feeds`]
+    pub feeds: Arc<RwLock<FeedLoader>>,
+    /// ETag of the current symbol list
+    pub symbols_etag: Arc<RwLock<String>>,
+    /// Whether `/v1/debug/*` routes are mounted
+    pub debug_endpoints: bool,
+    /// Upstream connections shared by WebSocket clients with the same subscriptions
+    pub surge_pool: SharedSurgePool,
+    /// Per-client request budget for `/v1/*`
+    pub rate_limiter: RateLimiter,
+    /// Wallets accepted by [`super::auth::require_api_key_or_solana_signature`]
+    #[cfg(feature = "solana")]
+    pub allowed_wallets: Arc<HashSet<String>>,
 }
 
 impl AppState {
+    /// Build state from environment variables
     pub fn new() -> Result<Self, SurgeError> {
+        Self::new_with_config(AppStateConfig::from_env())
+    }
+
+    /// Build state from an explicit config without touching the environment
+    pub fn new_with_config(config: AppStateConfig) -> Result<Self, SurgeError> {
+        let feeds = match config.feeds_path {
+            Some(path) => FeedLoader::load_from_path(path)?,
+            None => FeedLoader::load_default()?,
+        };
+        let events = EventCounter::new();
+        let etag = feeds.symbols_etag();
+        let feeds = Arc::new(RwLock::new(feeds));
+        let client = Arc::new(SurgeClient::with_feeds(feeds.clone())?);
+        let surge_pool = SharedSurgePool::new(events.clone()).with_snapshot_client(client.clone());
         Ok(Self {
-            client: Arc::new(SurgeClient::new()?),
+            client,
             ready: Arc::new(std::sync::atomic::AtomicBool::new(true)),
+            fx: Arc::new(FxRates::new(config.fx_api_url)),
+            api_key: config.api_key.map(Arc::from),
+            surge_pool,
+            events,
+            started_at: Instant::now(),
+            history: Arc::new(PriceHistory::default()),
+            symbols_etag: Arc::new(RwLock::new(etag)),
+            feeds,
+            debug_endpoints: config.debug_endpoints,
+            rate_limiter: RateLimiter::new(config.rate_limit_rpm),
+            #[cfg(feature = "solana")]
+            allowed_wallets: Arc::new(config.allowed_wallets.into_iter().collect()),
         })
     }
 
+    /// Re-read the feed registry from disk and refresh the symbols ETag
+    pub fn reload_feeds(&self) -> Result<(), SurgeError> {
+        let mut feeds = self.feeds.write().unwrap_or_else(|e| e.into_inner());
+        feeds.reload()?;
+        *self.symbols_etag.write().unwrap_or_else(|e| e.into_inner()) = feeds.symbols_etag();
+        Ok(())
+    }
+
+    /// Seed the history buffer with one `preload`-tagged price per symbol so
+    /// analytics have a starting point before live traffic arrives.
+    ///
+    /// Symbols that fail are logged and skipped; errors only if none loaded.
+    pub async fn preload_cache(&self, symbols: &[&str]) -> Result<(), SurgeError> {
+        let results = futures_util::future::join_all(symbols.iter().map(|s| self.client.get_price(s))).await;
+        let mut loaded = 0;
+        let mut last_error = None;
+        for result in results {
+            match result {
+                Ok(price) => {
+                    self.history.record_preload(&price.symbol, price.value);
+                    loaded += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to preload price: {}", e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        tracing::info!("Preloaded {} of {} symbols into price history", loaded, symbols.len());
+        match last_error {
+            Some(e) if loaded == 0 => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fetch a price and record it in the history buffer
+    pub async fn fetch_price(&self, symbol: &str) -> Result<FeedPrice, SurgeError> {
+        let price = self.client.get_price(symbol).await?;
+        self.history.record(&price.symbol, price.value);
+        Ok(price)
+    }
+
+    /// Fetch several prices and record them in the history buffer
+    pub async fn fetch_prices(&self, symbols: &[&str]) -> Result<Vec<FeedPrice>, SurgeError> {
+        let prices = self.client.get_multiple_prices(symbols).await?;
+        self.history.record_all(&prices);
+        Ok(prices)
+    }
+
+    /// Fetch every feed's price, [`ALL_SYMBOLS_CONCURRENCY`] at a time, and
+    /// record them in the history buffer
+    pub async fn fetch_all_prices(&self) -> Result<Vec<FeedPrice>, SurgeError> {
+        let all_symbols = self.client.get_all_symbols();
+        let symbols: Vec<&str> = all_symbols.iter().map(String::as_str).collect();
+        let prices = self.client.get_multiple_prices_concurrent(&symbols, ALL_SYMBOLS_CONCURRENCY).await?;
+        self.history.record_all(&prices);
+        Ok(prices)
+    }
+
     pub fn is_ready(&self) -> bool {
         self.ready.load(std::sync::atomic::Ordering::SeqCst)
     }
@@ -40,6 +230,12 @@ pub struct ApiResponse<T> {
     pub data: Option<T>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Close matches for an unknown symbol
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub did_you_mean: Option<Vec<String>>,
+    /// Same value as the `x-request-id` response header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl<T: Serialize> ApiResponse<T> {
@@ -48,6 +244,8 @@ impl<T: Serialize> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            did_you_mean: None,
+            request_id: RequestId::current(),
         })
     }
 
@@ -56,6 +254,19 @@ impl<T: Serialize> ApiResponse<T> {
             success: false,
             data: None,
             error: Some(message.into()),
+            did_you_mean: None,
+            request_id: RequestId::current(),
+        })
+    }
+
+    /// Error carrying symbol suggestions
+    pub fn error_with_suggestions(message: impl Into<String>, suggestions: Vec<String>) -> Json<ApiResponse<()>> {
+        Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(message.into()),
+            did_you_mean: Some(suggestions),
+            request_id: RequestId::current(),
         })
     }
 }
@@ -78,16 +289,115 @@ impl From<crate::FeedPrice> for PriceResponse {
     }
 }
 
+/// Price converted from USD into another fiat currency
+#[derive(Serialize)]
+pub struct ConvertedPriceResponse {
+    pub symbol: String,
+    pub price_usd: f64,
+    pub price_target: f64,
+    pub currency: String,
+    pub fx_rate: f64,
+    pub fx_rate_at: i64,
+}
+
+/// Symbol component to group prices by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupByField {
+    Quote,
+    Base,
+}
+
+impl GroupByField {
+    /// Extract the grouping key from a "BASE/QUOTE" symbol
+    fn key<'a>(&self, symbol: &'a str) -> &'a str {
+        let (base, quote) = symbol.split_once('/').unwrap_or((symbol, ""));
+        match self {
+            GroupByField::Quote => quote,
+            GroupByField::Base => base,
+        }
+    }
+}
+
+/// Prices grouped by base or quote currency
+#[derive(Serialize)]
+pub struct PriceGroupedResponse {
+    pub groups: HashMap<String, Vec<PriceResponse>>,
+}
+
+impl PriceGroupedResponse {
+    pub fn new(prices: Vec<crate::FeedPrice>, field: GroupByField) -> Self {
+        let mut groups: HashMap<String, Vec<PriceResponse>> = HashMap::new();
+        for price in prices {
+            let key = field.key(&price.symbol).to_string();
+            groups.entry(key).or_default().push(PriceResponse::from(price));
+        }
+        for group in groups.values_mut() {
+            group.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        }
+        Self { groups }
+    }
+}
+
 /// Query parameters for multiple prices
 #[derive(Deserialize)]
 pub struct PricesQuery {
-    pub symbols: String,
+    /// Comma-separated symbols; may be omitted with `group_by` to fetch every feed
+    pub symbols: Option<String>,
+    /// Group results by quote or base currency
+    pub group_by: Option<GroupByField>,
+    /// Target fiat currency (e.g. "EUR"); prices are USD when omitted
+    pub currency: Option<String>,
+}
+
+/// JSON body for `POST /v1/prices`
+#[derive(Deserialize)]
+pub struct PricesBody {
+    pub symbols: Vec<String>,
+}
+
+/// Query parameters for the SSE price stream
+#[derive(Deserialize)]
+pub struct SseQuery {
+    /// Comma-separated symbols, normalized like the REST routes ("btc" → "BTC/USD")
+    pub symbols: Option<String>,
+}
+
+/// Payload of an SSE `price` event
+#[derive(Serialize)]
+pub struct SsePriceEvent {
+    pub symbol: String,
+    pub price: f64,
+    pub timestamp: i64,
+}
+
+/// Event throughput across all WebSocket relays
+#[derive(Serialize)]
+pub struct ThroughputResponse {
+    pub events_total: u64,
+    pub events_per_second: f64,
+    pub uptime_secs: u64,
+}
+
+/// Raw upstream result for a feed ID
+#[derive(Serialize)]
+pub struct DebugFeedResponse {
+    pub feed_id: String,
+    pub raw_price: f64,
+    /// When this server answered (Unix ms), not an upstream timestamp
+    pub served_at_ms: i64,
 }
 
 /// Query parameters for symbol listing
 #[derive(Deserialize, Default)]
 pub struct SymbolsQuery {
     pub filter: Option<String>,
+    /// Typo-tolerant lookup; takes precedence over `filter`
+    pub fuzzy: Option<String>,
+    /// 1-indexed page; setting this or `page_size` paginates the list
+    pub page: Option<usize>,
+    /// Symbols per page (default 100, max 500)
+    pub page_size: Option<usize>,
 }
 
 /// Health check endpoint - always returns 200
@@ -97,20 +407,23 @@ pub async fn health() -> impl IntoResponse {
     }))
 }
 
-/// Readiness check endpoint - returns 200 if feeds are loaded
+/// Readiness check endpoint - returns 200 if feeds are loaded and the gateway is reachable
 pub async fn ready(state: axum::extract::State<AppState>) -> impl IntoResponse {
-    if state.is_ready() {
+    let health = state.client.health_check().await;
+    if state.is_ready() && health.gateway_reachable {
         (
             StatusCode::OK,
             Json(serde_json::json!({
-                "status": "ready"
+                "status": "ready",
+                "health": health
             })),
         )
     } else {
         (
             StatusCode::SERVICE_UNAVAILABLE,
             Json(serde_json::json!({
-                "status": "not ready"
+                "status": "not ready",
+                "health": health
             })),
         )
     }
@@ -133,29 +446,93 @@ pub async fn get_price(
     state: axum::extract::State<AppState>,
     Path(symbol): Path<String>,
 ) -> impl IntoResponse {
+    record_price_requests(&state, [symbol.as_str()]);
     state
-        .client
-        .get_price(&symbol)
+        .fetch_price(&symbol)
         .await
         .map(|price| (StatusCode::OK, ApiResponse::success(PriceResponse::from(price))).into_response())
-        .unwrap_or_else(|e| (e.status_code(), ApiResponse::<()>::error(e.to_string())).into_response())
+        .unwrap_or_else(|e| price_error_response(&state, &symbol, e))
+}
+
+/// Error body for a failed single-price fetch, with suggestions for unknown symbols
+pub(crate) fn price_error_response(state: &AppState, symbol: &str, e: SurgeError) -> axum::response::Response {
+    match e {
+        SurgeError::FeedNotFound(..) => {
+            let feeds = state.feeds.read().unwrap_or_else(|e| e.into_inner());
+            let suggestions = feeds.fuzzy_search(symbol, SUGGESTION_COUNT).into_iter().map(|(s, _)| s).collect();
+            (e.status_code(), ApiResponse::<()>::error_with_suggestions(e.to_string(), suggestions)).into_response()
+        }
+        e => (e.status_code(), ApiResponse::<()>::error(e.to_string())).into_response(),
+    }
 }
 
 /// Get prices for multiple symbols
-/// GET /v1/prices?symbols=btc,eth,sol
+/// GET /v1/prices?symbols=btc,eth,sol&currency=EUR
+/// GET /v1/prices?group_by=quote
 pub async fn get_prices(
     state: axum::extract::State<AppState>,
     Query(query): Query<PricesQuery>,
 ) -> impl IntoResponse {
-    let symbols: Vec<&str> = query.symbols.split(',').map(str::trim).collect();
+    let symbols: Vec<&str> = match query.symbols.as_deref() {
+        Some(list) => list.split(',').map(str::trim).collect(),
+        None => Vec::new(),
+    };
+    if symbols.len() > MAX_BODY_SYMBOLS {
+        let message = format!("At most {} symbols per request, got {}", MAX_BODY_SYMBOLS, symbols.len());
+        return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(message)).into_response();
+    }
+    record_price_requests(&state, symbols.iter().copied());
 
-    if symbols.is_empty() {
+    if symbols.is_empty() && query.group_by.is_none() {
+        return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error("No symbols provided")).into_response();
+    }
+
+    if let Some(currency) = query.currency.as_deref() {
+        return get_prices_in_currency(&state, &symbols, currency).await;
+    }
+
+    if let Some(field) = query.group_by {
+        return fetch_listed_or_all(&state, &symbols)
+            .await
+            .map(|prices| (StatusCode::OK, ApiResponse::success(PriceGroupedResponse::new(prices, field))).into_response())
+            .unwrap_or_else(|e| (e.status_code(), ApiResponse::<()>::error(e.to_string())).into_response());
+    }
+
+    prices_response(&state, &symbols).await
+}
+
+/// Get multiple prices from a JSON body, for symbol lists too long for a query string
+/// POST /v1/prices
+pub async fn post_prices(state: axum::extract::State<AppState>, Json(body): Json<PricesBody>) -> impl IntoResponse {
+    if body.symbols.is_empty() {
         return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error("No symbols provided")).into_response();
     }
+    if body.symbols.len() > MAX_BODY_SYMBOLS {
+        let message = format!("At most {} symbols per request, got {}", MAX_BODY_SYMBOLS, body.symbols.len());
+        return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(message)).into_response();
+    }
 
+    let symbols: Vec<&str> = body.symbols.iter().map(|s| s.trim()).collect();
+    record_price_requests(&state, symbols.iter().copied());
+    prices_response(&state, &symbols).await
+}
+
+/// Count requested symbols by registry key; unknown input is skipped so
+/// arbitrary strings can't grow the metric's label set
+pub(crate) fn record_price_requests<'a>(state: &AppState, symbols: impl IntoIterator<Item = &'a str>) {
+    let feeds = state.feeds.read().unwrap_or_else(|e| e.into_inner());
+    for symbol in symbols {
+        let key = feeds.resolve_symbol(symbol);
+        if feeds.has_symbol(&key) {
+            super::metrics::price_requested(&key);
+        }
+    }
+}
+
+/// Plain price list for `symbols`, shared by the GET and POST `/v1/prices` routes
+async fn prices_response(state: &AppState, symbols: &[&str]) -> axum::response::Response {
     state
-        .client
-        .get_multiple_prices(&symbols)
+        .fetch_prices(symbols)
         .await
         .map(|prices| {
             let response: Vec<PriceResponse> = prices.into_iter().map(PriceResponse::from).collect();
@@ -164,30 +541,188 @@ pub async fn get_prices(
         .unwrap_or_else(|e| (e.status_code(), ApiResponse::<()>::error(e.to_string())).into_response())
 }
 
+/// Prices for `symbols`, or for every feed when `group_by` was given without a list
+async fn fetch_listed_or_all(state: &AppState, symbols: &[&str]) -> Result<Vec<FeedPrice>, SurgeError> {
+    if symbols.is_empty() {
+        state.fetch_all_prices().await
+    } else {
+        state.fetch_prices(symbols).await
+    }
+}
+
+/// Convert USD prices into `currency` using cached FX rates
+async fn get_prices_in_currency(state: &AppState, symbols: &[&str], currency: &str) -> axum::response::Response {
+    let currency = currency.trim().to_uppercase();
+    let (fx_rate, fx_rate_at) = match state.fx.rate(&currency).await {
+        Ok(Some(rate)) => rate,
+        Ok(None) => {
+            return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(format!("Unknown currency: {}", currency)))
+                .into_response()
+        }
+        Err(e) => return (e.status_code(), ApiResponse::<()>::error(e.to_string())).into_response(),
+    };
+
+    fetch_listed_or_all(state, symbols)
+        .await
+        .map(|prices| {
+            let response: Vec<ConvertedPriceResponse> = prices
+                .into_iter()
+                .map(|p| ConvertedPriceResponse {
+                    symbol: p.symbol,
+                    price_usd: p.value,
+                    price_target: p.value * fx_rate,
+                    currency: currency.clone(),
+                    fx_rate,
+                    fx_rate_at,
+                })
+                .collect();
+            (StatusCode::OK, ApiResponse::success(response)).into_response()
+        })
+        .unwrap_or_else(|e| (e.status_code(), ApiResponse::<()>::error(e.to_string())).into_response())
+}
+
 /// List available symbols
 /// GET /v1/symbols?filter=sol
+/// GET /v1/symbols?fuzzy=btcusd
+/// GET /v1/symbols?page=2&page_size=100
+///
+/// Sends an `ETag`; a matching `If-None-Match` gets `304 Not Modified`.
 pub async fn list_symbols(
     state: axum::extract::State<AppState>,
+    headers: HeaderMap,
     Query(query): Query<SymbolsQuery>,
 ) -> impl IntoResponse {
-    let mut symbols = state.client.get_all_symbols();
+    let etag = state.symbols_etag.read().unwrap_or_else(|e| e.into_inner()).clone();
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| tag.trim() == "*" || tag.trim() == etag));
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+
+    let feeds = state.feeds.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(fuzzy) = query.fuzzy.as_deref() {
+        let matches: Vec<serde_json::Value> = feeds
+            .fuzzy_search(fuzzy, FUZZY_RESULT_COUNT)
+            .into_iter()
+            .map(|(symbol, score)| serde_json::json!({ "symbol": symbol, "score": score }))
+            .collect();
+        let count = matches.len();
+        let body = serde_json::json!({ "success": true, "data": { "matches": matches, "count": count } });
+        return (StatusCode::OK, [(header::ETAG, etag)], Json(body)).into_response();
+    }
+
+    let filter_lower = query.filter.as_deref().map(str::to_lowercase);
+    let mut symbols: Vec<&str> = feeds
+        .symbols()
+        .filter(|s| filter_lower.as_ref().is_none_or(|f| s.to_lowercase().contains(f)))
+        .collect();
+    symbols.sort_unstable();
+
+    if query.page.is_none() && query.page_size.is_none() {
+        let count = symbols.len();
+        let body = serde_json::json!({ "success": true, "data": { "symbols": symbols, "count": count } });
+        return (StatusCode::OK, [(header::ETAG, etag)], Json(body)).into_response();
+    }
 
-    if let Some(ref filter_term) = query.filter {
-        let filter_lower = filter_term.to_lowercase();
-        symbols.retain(|s| s.to_lowercase().contains(&filter_lower));
+    let page = query.page.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+    if page == 0 || page_size == 0 {
+        let message = "page and page_size must be at least 1";
+        return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(message)).into_response();
     }
+    let page_size = page_size.min(MAX_PAGE_SIZE);
+    let total = symbols.len();
+    let page_symbols: Vec<&str> =
+        symbols.into_iter().skip((page - 1).saturating_mul(page_size)).take(page_size).collect();
 
-    let count = symbols.len();
     (
         StatusCode::OK,
+        [(header::ETAG, etag)],
         Json(serde_json::json!({
             "success": true,
             "data": {
-                "symbols": symbols,
-                "count": count
+                "count": page_symbols.len(),
+                "symbols": page_symbols,
+                "total": total,
+                "page": page,
+                "page_size": page_size
             }
         })),
     )
+        .into_response()
+}
+
+/// Live prices as Server-Sent Events, for clients that cannot use WebSocket.
+/// Each update is an `event: price` with an [`SsePriceEvent`] JSON body.
+/// GET /v1/stream/sse?symbols=btc,eth
+pub async fn sse_price_stream(state: axum::extract::State<AppState>, Query(query): Query<SseQuery>) -> impl IntoResponse {
+    let symbols: HashSet<String> = query
+        .symbols
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(crate::normalize_symbol)
+        .collect();
+    if symbols.is_empty() {
+        return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error("No symbols provided")).into_response();
+    }
+
+    // Held by the stream, so the pooled connection is released when the client goes away
+    let lease = match state.surge_pool.lease(&symbols).await {
+        Ok(lease) => lease,
+        Err(e) => return (e.status_code(), ApiResponse::<()>::error(e.to_string())).into_response(),
+    };
+    let events = BroadcastStream::new(lease.subscribe_events());
+
+    let prices = events.flat_map(move |event| {
+        let _lease = &lease;
+        let updates = event.map(crate::SurgeEvent::into_updates).unwrap_or_default();
+        stream::iter(updates.into_iter().map(|update| {
+            Event::default().event("price").json_data(SsePriceEvent {
+                symbol: update.data.symbol,
+                price: update.data.price,
+                timestamp: update.data.source_timestamp_ms,
+            })
+        }))
+    });
+    Sse::new(prices).keep_alive(KeepAlive::new().interval(SSE_KEEP_ALIVE)).into_response()
+}
+
+/// Upstream event throughput
+/// GET /v1/admin/throughput
+pub async fn throughput(state: axum::extract::State<AppState>) -> impl IntoResponse {
+    let response = ThroughputResponse {
+        events_total: state.events.get(),
+        events_per_second: state.events.events_per_second(),
+        uptime_secs: state.started_at.elapsed().as_secs(),
+    };
+    (StatusCode::OK, ApiResponse::success(response))
+}
+
+/// Price for a feed ID with no symbol lookup or normalization.
+/// Only mounted when `SURGE_DEBUG_ENDPOINTS=true`.
+/// GET /v1/debug/feed/:feed_id
+pub async fn debug_feed(state: axum::extract::State<AppState>, Path(feed_id): Path<String>) -> impl IntoResponse {
+    if let Err(e) = crate::feed_loader::validate_feed_id(&feed_id) {
+        let message = format!("Invalid feed ID: {}", e);
+        return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(message)).into_response();
+    }
+
+    match state.client.get_price_by_feed_id(&feed_id).await {
+        Ok(raw_price) => {
+            let response = DebugFeedResponse {
+                feed_id,
+                raw_price,
+                served_at_ms: crate::types::now_ms(),
+            };
+            (StatusCode::OK, ApiResponse::success(response)).into_response()
+        }
+        Err(e) => (e.status_code(), ApiResponse::<()>::error(e.to_string())).into_response(),
+    }
 }
 
 #[cfg(test)]
@@ -202,6 +737,22 @@ mod tests {
         assert!(state.is_ok(), "should create app state");
     }
 
+    #[test]
+    fn test_app_state_new_with_config() {
+        let config = AppStateConfig {
+            api_key: Some("secret".to_string()),
+            ..AppStateConfig::default()
+        };
+        let state = AppState::new_with_config(config).unwrap();
+        assert_eq!(state.api_key.as_deref(), Some("secret"));
+    }
+
+    #[test]
+    fn test_app_state_config_default_disables_auth() {
+        let state = AppState::new_with_config(AppStateConfig::default()).unwrap();
+        assert!(state.api_key.is_none());
+    }
+
     #[test]
     fn test_app_state_is_ready_default() {
         let state = AppState::new().unwrap();
@@ -291,6 +842,7 @@ mod tests {
             symbol: "BTC/USD".to_string(),
             feed_id: "abc123".to_string(),
             value: 50000.0,
+            timestamp: None,
         };
         let price_response = PriceResponse::from(feed_price);
 
@@ -304,7 +856,73 @@ mod tests {
     #[test]
     fn test_prices_query_deserialization() {
         let query: PricesQuery = serde_json::from_str(r#"{"symbols": "btc,eth,sol"}"#).unwrap();
-        assert_eq!(query.symbols, "btc,eth,sol");
+        assert_eq!(query.symbols.as_deref(), Some("btc,eth,sol"));
+        assert!(query.currency.is_none());
+        assert!(query.group_by.is_none());
+    }
+
+    #[test]
+    fn test_prices_query_group_by_without_symbols() {
+        let query: PricesQuery = serde_json::from_str(r#"{"group_by": "quote"}"#).unwrap();
+        assert!(query.symbols.is_none());
+        assert_eq!(query.group_by, Some(GroupByField::Quote));
+    }
+
+    #[test]
+    fn test_prices_query_invalid_group_by() {
+        assert!(serde_json::from_str::<PricesQuery>(r#"{"group_by": "exchange"}"#).is_err());
+    }
+
+    // === Grouping tests ===
+
+    fn feed_price(symbol: &str, value: f64) -> crate::FeedPrice {
+        crate::FeedPrice {
+            symbol: symbol.to_string(),
+            feed_id: "abc".to_string(),
+            value,
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn test_group_prices_by_quote() {
+        let prices = vec![
+            feed_price("SOL/USDT", 150.0),
+            feed_price("BTC/USD", 50000.0),
+            feed_price("ETH/USD", 3000.0),
+        ];
+        let grouped = PriceGroupedResponse::new(prices, GroupByField::Quote);
+
+        assert_eq!(grouped.groups.len(), 2);
+        let usd: Vec<&str> = grouped.groups["USD"].iter().map(|p| p.symbol.as_str()).collect();
+        assert_eq!(usd, vec!["BTC/USD", "ETH/USD"]);
+        assert_eq!(grouped.groups["USDT"][0].symbol, "SOL/USDT");
+    }
+
+    #[test]
+    fn test_group_prices_by_base() {
+        let prices = vec![
+            feed_price("BTC/USD", 50000.0),
+            feed_price("BTC/USDT", 50010.0),
+            feed_price("ETH/USD", 3000.0),
+        ];
+        let grouped = PriceGroupedResponse::new(prices, GroupByField::Base);
+
+        assert_eq!(grouped.groups["BTC"].len(), 2);
+        assert_eq!(grouped.groups["ETH"].len(), 1);
+    }
+
+    #[test]
+    fn test_grouped_response_serialization() {
+        let grouped = PriceGroupedResponse::new(vec![feed_price("BTC/USD", 1.0)], GroupByField::Quote);
+        let json = serde_json::to_value(&grouped).unwrap();
+        assert_eq!(json["groups"]["USD"][0]["symbol"], "BTC/USD");
+    }
+
+    #[test]
+    fn test_prices_query_with_currency() {
+        let query: PricesQuery = serde_json::from_str(r#"{"symbols": "btc", "currency": "EUR"}"#).unwrap();
+        assert_eq!(query.currency, Some("EUR".to_string()));
     }
 
     #[test]
@@ -324,24 +942,28 @@ mod tests {
     #[test]
     fn test_parse_symbols_from_query() {
         let query = PricesQuery {
-            symbols: "btc,eth,sol".to_string(),
+            symbols: Some("btc,eth,sol".to_string()),
+            group_by: None,
+            currency: None,
         };
-        let symbols: Vec<&str> = query.symbols.split(',').map(str::trim).collect();
+        let symbols: Vec<&str> = query.symbols.as_deref().unwrap().split(',').map(str::trim).collect();
         assert_eq!(symbols, vec!["btc", "eth", "sol"]);
     }
 
     #[test]
     fn test_parse_symbols_with_whitespace() {
         let query = PricesQuery {
-            symbols: "btc , eth , sol".to_string(),
+            symbols: Some("btc , eth , sol".to_string()),
+            group_by: None,
+            currency: None,
         };
-        let symbols: Vec<&str> = query.symbols.split(',').map(str::trim).collect();
+        let symbols: Vec<&str> = query.symbols.as_deref().unwrap().split(',').map(str::trim).collect();
         assert_eq!(symbols, vec!["btc", "eth", "sol"]);
     }
 
     #[test]
     fn test_filter_symbols() {
-        let all_symbols = vec![
+        let all_symbols = [
             "BTC/USD".to_string(),
             "ETH/USD".to_string(),
             "SOL/USD".to_string(),
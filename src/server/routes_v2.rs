@@ -0,0 +1,110 @@
+//! `/v2` API: prices as fixed-precision decimal strings
+//!
+//! JSON numbers are read as `f64` by most clients, so v2 sends each price as a
+//! string with an explicit `precision` instead. Everything else (auth, rate
+//! limiting, error envelope) is shared with `/v1`.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+
+use super::routes::{price_error_response, record_price_requests, ApiResponse, AppState};
+use crate::FeedPrice;
+
+/// Decimal places in v2 price strings
+pub const PRICE_PRECISION: u8 = 6;
+
+/// v2 price: `price` is a decimal string with `precision` fractional digits
+#[derive(Debug, Serialize)]
+pub struct PriceResponseV2 {
+    pub symbol: String,
+    pub feed_id: String,
+    pub price: String,
+    pub precision: u8,
+}
+
+impl From<FeedPrice> for PriceResponseV2 {
+    fn from(p: FeedPrice) -> Self {
+        Self {
+            price: format!("{:.*}", PRICE_PRECISION as usize, p.value),
+            precision: PRICE_PRECISION,
+            symbol: p.symbol,
+            feed_id: p.feed_id,
+        }
+    }
+}
+
+/// Query parameters for `GET /v2/prices`
+#[derive(Debug, Deserialize)]
+pub struct PricesQueryV2 {
+    /// Comma-separated symbols
+    pub symbols: Option<String>,
+}
+
+/// Routes mounted under `/v2`; the caller adds auth and rate limiting
+pub fn create_v2_router(state: AppState) -> Router {
+    Router::new()
+        .route("/prices/:symbol", get(get_price))
+        .route("/prices", get(get_prices))
+        .with_state(state)
+}
+
+/// Get price for a single symbol
+/// GET /v2/prices/:symbol
+pub async fn get_price(State(state): State<AppState>, Path(symbol): Path<String>) -> Response {
+    record_price_requests(&state, [symbol.as_str()]);
+    state
+        .fetch_price(&symbol)
+        .await
+        .map(|price| (StatusCode::OK, ApiResponse::success(PriceResponseV2::from(price))).into_response())
+        .unwrap_or_else(|e| price_error_response(&state, &symbol, e))
+}
+
+/// Get prices for multiple symbols
+/// GET /v2/prices?symbols=btc,eth,sol
+pub async fn get_prices(State(state): State<AppState>, Query(query): Query<PricesQueryV2>) -> Response {
+    let symbols: Vec<&str> = query
+        .symbols
+        .as_deref()
+        .map(|list| list.split(',').map(str::trim).filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    if symbols.is_empty() {
+        return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error("No symbols provided")).into_response();
+    }
+
+    record_price_requests(&state, symbols.iter().copied());
+    state
+        .fetch_prices(&symbols)
+        .await
+        .map(|prices| {
+            let response: Vec<PriceResponseV2> = prices.into_iter().map(PriceResponseV2::from).collect();
+            (StatusCode::OK, ApiResponse::success(response)).into_response()
+        })
+        .unwrap_or_else(|e| (e.status_code(), ApiResponse::<()>::error(e.to_string())).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_response_v2_formats_fixed_precision() {
+        let price = FeedPrice { symbol: "BTC/USD".to_string(), feed_id: "abc".to_string(), value: 89846.94, timestamp: None };
+        let json = serde_json::to_value(PriceResponseV2::from(price)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "symbol": "BTC/USD", "feed_id": "abc", "price": "89846.940000", "precision": 6 })
+        );
+    }
+
+    #[test]
+    fn test_price_response_v2_rounds_small_values() {
+        let price = FeedPrice { symbol: "SHIB/USD".to_string(), feed_id: String::new(), value: 0.0000123456789, timestamp: None };
+        assert_eq!(PriceResponseV2::from(price).price, "0.000012");
+    }
+}
@@ -0,0 +1,186 @@
+//! Cross-source bid/ask spread (`multi-source` feature)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use futures_util::future::{join_all, BoxFuture};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::routes::{ApiResponse, AppState};
+use crate::error::Result;
+use crate::SurgeClient;
+
+/// A price source able to quote a best bid and ask for a symbol
+pub trait ExchangeAdapter: Send + Sync {
+    /// Short lowercase name used in `?exchanges=`
+    fn name(&self) -> &str;
+
+    /// Fetch `(bid, ask)` for a symbol
+    fn get_bid_ask<'a>(&'a self, symbol: &'a str) -> BoxFuture<'a, Result<(f64, f64)>>;
+}
+
+/// Surge oracle feed: a single midpoint price, so bid == ask
+pub struct SurgeAdapter {
+    client: Arc<SurgeClient>,
+}
+
+impl SurgeAdapter {
+    pub fn new(client: Arc<SurgeClient>) -> Self {
+        Self { client }
+    }
+}
+
+impl ExchangeAdapter for SurgeAdapter {
+    fn name(&self) -> &str {
+        "surge"
+    }
+
+    fn get_bid_ask<'a>(&'a self, symbol: &'a str) -> BoxFuture<'a, Result<(f64, f64)>> {
+        Box::pin(async move {
+            let price = self.client.get_price(symbol).await?;
+            Ok((price.value, price.value))
+        })
+    }
+}
+
+/// Adapters available to the spread endpoint
+fn adapters(state: &AppState) -> Vec<Box<dyn ExchangeAdapter>> {
+    vec![Box::new(SurgeAdapter::new(state.client.clone()))]
+}
+
+/// Query parameters for the spread endpoint
+#[derive(Deserialize, Default)]
+pub struct SpreadQuery {
+    /// Comma-separated source names; all sources when omitted
+    pub exchanges: Option<String>,
+}
+
+/// Bid/ask quoted by one source
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceQuote {
+    pub name: String,
+    pub bid: f64,
+    pub ask: f64,
+}
+
+/// Best bid/ask across sources
+#[derive(Debug, Serialize)]
+pub struct SpreadResponse {
+    pub symbol: String,
+    pub best_bid: f64,
+    pub best_ask: f64,
+    pub spread_abs: f64,
+    pub spread_pct: f64,
+    pub sources: Vec<SourceQuote>,
+}
+
+/// Combine per-source quotes into the best bid (highest) and best ask (lowest)
+pub fn compute_spread(symbol: &str, sources: Vec<SourceQuote>) -> Option<SpreadResponse> {
+    if sources.is_empty() {
+        return None;
+    }
+    let best_bid = sources.iter().map(|s| s.bid).fold(f64::NEG_INFINITY, f64::max);
+    let best_ask = sources.iter().map(|s| s.ask).fold(f64::INFINITY, f64::min);
+    let spread_abs = best_ask - best_bid;
+    let mid = (best_ask + best_bid) / 2.0;
+    let spread_pct = if mid != 0.0 { spread_abs / mid * 100.0 } else { 0.0 };
+
+    Some(SpreadResponse {
+        symbol: symbol.to_string(),
+        best_bid,
+        best_ask,
+        spread_abs,
+        spread_pct,
+        sources,
+    })
+}
+
+/// Best bid/ask spread across data sources
+/// GET /v1/prices/:symbol/spread?exchanges=surge
+pub async fn get_spread(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<SpreadQuery>,
+) -> impl IntoResponse {
+    let symbol = crate::normalize_symbol(&symbol);
+    let mut selected = adapters(&state);
+
+    if let Some(ref exchanges) = query.exchanges {
+        let wanted: Vec<String> = exchanges.split(',').map(|e| e.trim().to_lowercase()).collect();
+        let unknown: Vec<&String> = wanted.iter().filter(|w| !selected.iter().any(|a| a.name() == w.as_str())).collect();
+        if !unknown.is_empty() {
+            let message = format!("Unknown exchanges: {}", unknown.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", "));
+            return (StatusCode::BAD_REQUEST, ApiResponse::<()>::error(message)).into_response();
+        }
+        selected.retain(|a| wanted.iter().any(|w| w == a.name()));
+    }
+
+    let results = join_all(selected.iter().map(|a| a.get_bid_ask(&symbol))).await;
+    let mut last_error = None;
+    let quotes: Vec<SourceQuote> = selected
+        .iter()
+        .zip(results)
+        .filter_map(|(adapter, result)| match result {
+            Ok((bid, ask)) => Some(SourceQuote { name: adapter.name().to_string(), bid, ask }),
+            Err(e) => {
+                tracing::warn!("Spread source {} failed: {}", adapter.name(), e);
+                last_error = Some(e);
+                None
+            }
+        })
+        .collect();
+
+    match compute_spread(&symbol, quotes) {
+        Some(spread) => (StatusCode::OK, ApiResponse::success(spread)).into_response(),
+        None => match last_error {
+            Some(e) => (e.status_code(), ApiResponse::<()>::error(e.to_string())).into_response(),
+            None => (StatusCode::BAD_REQUEST, ApiResponse::<()>::error("No exchanges selected")).into_response(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(name: &str, bid: f64, ask: f64) -> SourceQuote {
+        SourceQuote { name: name.to_string(), bid, ask }
+    }
+
+    #[test]
+    fn test_compute_spread_single_midpoint_source() {
+        let spread = compute_spread("BTC/USD", vec![quote("surge", 50000.0, 50000.0)]).unwrap();
+        assert_eq!(spread.best_bid, 50000.0);
+        assert_eq!(spread.best_ask, 50000.0);
+        assert_eq!(spread.spread_abs, 0.0);
+        assert_eq!(spread.spread_pct, 0.0);
+    }
+
+    #[test]
+    fn test_compute_spread_picks_best_across_sources() {
+        let spread = compute_spread(
+            "BTC/USD",
+            vec![quote("a", 99.0, 101.0), quote("b", 99.5, 102.0), quote("c", 98.0, 100.5)],
+        )
+        .unwrap();
+        assert_eq!(spread.best_bid, 99.5);
+        assert_eq!(spread.best_ask, 100.5);
+        assert_eq!(spread.spread_abs, 1.0);
+        assert!((spread.spread_pct - 1.0).abs() < 1e-9);
+        assert_eq!(spread.sources.len(), 3);
+    }
+
+    #[test]
+    fn test_compute_spread_crossed_market_is_negative() {
+        let spread = compute_spread("ETH/USD", vec![quote("a", 101.0, 102.0), quote("b", 99.0, 100.0)]).unwrap();
+        assert_eq!(spread.spread_abs, -1.0);
+    }
+
+    #[test]
+    fn test_compute_spread_no_sources() {
+        assert!(compute_spread("BTC/USD", vec![]).is_none());
+    }
+}
@@ -0,0 +1,67 @@
+//! OpenTelemetry export of server traces over OTLP/gRPC
+
+use opentelemetry::{trace::TracerProvider as _, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    runtime,
+    trace::{Tracer, TracerProvider},
+    Resource,
+};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::{Result, SurgeError};
+
+/// Collector used when `OTEL_EXPORTER_OTLP_ENDPOINT` is unset
+pub const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4317";
+
+/// Collector endpoint from `OTEL_EXPORTER_OTLP_ENDPOINT`, else [`DEFAULT_OTLP_ENDPOINT`]
+pub fn otlp_endpoint() -> String {
+    std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_OTLP_ENDPOINT.to_string())
+}
+
+/// Tracing layer exporting spans in batches to `endpoint` as `service_name`.
+///
+/// Must be called inside a Tokio runtime. Keep the provider and call
+/// `shutdown` on exit so buffered spans are flushed.
+pub fn otel_layer<S>(service_name: &str, endpoint: &str) -> Result<(OpenTelemetryLayer<S, Tracer>, TracerProvider)>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(|e| SurgeError::InvalidConfig(format!("OTLP exporter for {}: {}", endpoint, e)))?;
+
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new([KeyValue::new("service.name", service_name.to_string())]))
+        .build();
+    let tracer = provider.tracer(service_name.to_string());
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    Ok((tracing_opentelemetry::layer().with_tracer(tracer), provider))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::Registry;
+
+    // Shutdown blocks on the batch exporter task, so it needs a second worker thread
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_otel_layer_builds_without_collector() {
+        let (_layer, provider) = otel_layer::<Registry>("test-service", DEFAULT_OTLP_ENDPOINT).unwrap();
+        provider.shutdown().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_otel_layer_rejects_invalid_endpoint() {
+        assert!(otel_layer::<Registry>("test-service", "not a uri").is_err());
+    }
+}
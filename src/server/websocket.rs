@@ -7,15 +7,17 @@ use axum::{
     },
     response::IntoResponse,
 };
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{stream::SplitSink, SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, watch, RwLock};
 
 use super::metrics::{ws_connection_closed, ws_connection_opened};
+use super::pool::{PoolLease, SharedSurgePool};
 use super::routes::AppState;
-use crate::{Surge, SurgeEvent};
+use crate::SurgeEvent;
 
 /// Client message for WebSocket subscription
 #[derive(Debug, Deserialize)]
@@ -23,6 +25,25 @@ use crate::{Surge, SurgeEvent};
 pub enum ClientMessage {
     Subscribe { symbols: Vec<String> },
     Unsubscribe { symbols: Vec<String> },
+    /// Coalesce price updates over `millis` (0 = unbatched, capped at [`MAX_BATCH_WINDOW_MS`])
+    #[serde(rename = "set_batch_window")]
+    SetBatchWindow { millis: u64 },
+}
+
+/// Default window over which price updates are coalesced into one frame
+pub const BATCH_WINDOW_MS: u64 = 5;
+
+/// Largest batch window a client may request
+pub const MAX_BATCH_WINDOW_MS: u64 = 50;
+
+/// One price in a [`ServerMessage::Batch`]; same fields as [`ServerMessage::Price`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PriceEntry {
+    pub symbol: String,
+    pub price: f64,
+    pub timestamp: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed_id: Option<String>,
 }
 
 /// Server message for WebSocket responses
@@ -36,37 +57,124 @@ pub enum ServerMessage {
         #[serde(skip_serializing_if = "Option::is_none")]
         feed_id: Option<String>,
     },
+    /// Price updates received within one batch window
+    Batch { updates: Vec<PriceEntry> },
     Subscribed { symbols: Vec<String> },
     Unsubscribed { symbols: Vec<String> },
+    /// Confirms the batch window now in effect
+    #[serde(rename = "batch_window")]
+    BatchWindow { millis: u64 },
     Error { message: String },
 }
 
+impl ServerMessage {
+    /// Frame for the buffered prices: a lone update stays a `Price`, more become a `Batch`
+    fn from_batch(mut updates: Vec<PriceEntry>) -> Option<Self> {
+        match updates.len() {
+            0 => None,
+            1 => updates.pop().map(|PriceEntry { symbol, price, timestamp, feed_id }| ServerMessage::Price {
+                symbol,
+                price,
+                timestamp,
+                feed_id,
+            }),
+            _ => Some(ServerMessage::Batch { updates }),
+        }
+    }
+}
+
+/// Wire format chosen by the client's first message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientEncoding {
+    /// Text frames carrying JSON
+    Json,
+    /// Binary frames carrying MessagePack
+    MessagePack,
+}
+
+impl ClientEncoding {
+    /// Encode a server message as a frame in this format
+    fn encode(self, msg: &ServerMessage) -> Option<Message> {
+        match self {
+            ClientEncoding::Json => serde_json::to_string(msg).ok().map(Message::Text),
+            ClientEncoding::MessagePack => rmp_serde::to_vec_named(msg).ok().map(Message::Binary),
+        }
+    }
+}
+
+/// Decode a binary frame as MessagePack, falling back to JSON bytes
+fn decode_binary(bytes: &[u8]) -> Result<ClientMessage, String> {
+    rmp_serde::from_slice(bytes)
+        .or_else(|_| serde_json::from_slice(bytes))
+        .map_err(|e| e.to_string())
+}
+
 /// WebSocket upgrade handler
 /// WS /v1/stream
-pub async fn ws_handler(ws: WebSocketUpgrade, State(_state): State<AppState>) -> impl IntoResponse {
-    ws.on_upgrade(handle_socket)
+pub async fn ws_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_socket(socket, state.surge_pool))
 }
 
 /// Handle an individual WebSocket connection
-async fn handle_socket(socket: WebSocket) {
+async fn handle_socket(socket: WebSocket, pool: SharedSurgePool) {
     ws_connection_opened();
     tracing::info!("WebSocket connection established");
 
     let (mut sender, mut receiver) = socket.split();
     let (tx, mut rx) = mpsc::channel::<ServerMessage>(100);
 
-    // Spawn task to send messages to the client
+    let encoding: Arc<OnceLock<ClientEncoding>> = Arc::new(OnceLock::new());
+
+    let (window_tx, mut window_rx) = watch::channel(BATCH_WINDOW_MS);
+
+    // Spawn task to send messages to the client, coalescing prices per batch window
+    let send_encoding = encoding.clone();
     let send_task = tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            if let Ok(json) = serde_json::to_string(&msg) {
-                if sender.send(Message::Text(json)).await.is_err() {
-                    break;
+        let mut window = BATCH_WINDOW_MS;
+        let mut flush = batch_interval(window);
+        let mut pending: Vec<PriceEntry> = Vec::new();
+        loop {
+            tokio::select! {
+                msg = rx.recv() => match msg {
+                    Some(ServerMessage::Price { symbol, price, timestamp, feed_id }) if window > 0 => {
+                        // The window opens with the first buffered update
+                        if pending.is_empty() {
+                            flush.reset();
+                        }
+                        pending.push(PriceEntry { symbol, price, timestamp, feed_id });
+                    }
+                    Some(msg) => {
+                        // Flush first so prices keep their place relative to other messages
+                        let batch = ServerMessage::from_batch(std::mem::take(&mut pending));
+                        for msg in batch.into_iter().chain([msg]) {
+                            if !send_message(&mut sender, &send_encoding, &msg).await {
+                                return;
+                            }
+                        }
+                    }
+                    None => return,
+                },
+                _ = flush.tick(), if !pending.is_empty() => {
+                    if let Some(batch) = ServerMessage::from_batch(std::mem::take(&mut pending)) {
+                        if !send_message(&mut sender, &send_encoding, &batch).await {
+                            return;
+                        }
+                    }
+                }
+                Ok(()) = window_rx.changed() => {
+                    window = *window_rx.borrow_and_update();
+                    flush = batch_interval(window);
+                    if let Some(batch) = ServerMessage::from_batch(std::mem::take(&mut pending)) {
+                        if !send_message(&mut sender, &send_encoding, &batch).await {
+                            return;
+                        }
+                    }
                 }
             }
         }
     });
 
-    let surge: Arc<RwLock<Option<Surge>>> = Arc::new(RwLock::new(None));
+    let surge: Arc<RwLock<Option<PoolLease>>> = Arc::new(RwLock::new(None));
     let subscribed_symbols: Arc<RwLock<HashSet<String>>> = Arc::new(RwLock::new(HashSet::new()));
 
     // Task to relay upstream price updates
@@ -81,20 +189,26 @@ async fn handle_socket(socket: WebSocket) {
 
             if let Some(mut rx) = event_rx {
                 match rx.recv().await {
-                    Ok(SurgeEvent::PriceUpdate(update)) => {
-                        let msg = ServerMessage::Price {
-                            symbol: update.data.symbol,
-                            price: update.data.price,
-                            timestamp: update.data.source_timestamp_ms,
-                            feed_id: update.data.feed_id,
-                        };
-                        if tx_relay.send(msg).await.is_err() {
-                            break;
+                    Ok(event @ (SurgeEvent::PriceUpdate(_) | SurgeEvent::PriceBatch(_) | SurgeEvent::Snapshot { .. })) => {
+                        for update in event.into_updates() {
+                            let msg = ServerMessage::Price {
+                                symbol: update.data.symbol,
+                                price: update.data.price,
+                                timestamp: update.data.source_timestamp_ms,
+                                feed_id: update.data.feed_id,
+                            };
+                            if tx_relay.send(msg).await.is_err() {
+                                return;
+                            }
                         }
                     }
                     Ok(SurgeEvent::Error(e)) => {
                         let _ = tx_relay.send(ServerMessage::Error { message: e }).await;
                     }
+                    Ok(SurgeEvent::ReconnectFailed { reason, .. }) => {
+                        let message = format!("Upstream connection lost: {}", reason);
+                        let _ = tx_relay.send(ServerMessage::Error { message }).await;
+                    }
                     Ok(_) => {}
                     Err(_) => tokio::time::sleep(tokio::time::Duration::from_millis(100)).await,
                 }
@@ -106,32 +220,15 @@ async fn handle_socket(socket: WebSocket) {
 
     // Handle incoming client messages
     while let Some(msg) = receiver.next().await {
-        match msg {
-            Ok(Message::Text(text)) => match serde_json::from_str::<ClientMessage>(&text) {
-                Ok(ClientMessage::Subscribe { symbols }) => {
-                    tracing::info!("Client subscribing to: {:?}", symbols);
-                    {
-                        let mut subs = subscribed_symbols.write().await;
-                        subs.extend(symbols.clone());
-                    }
-                    reconnect_surge(&surge, &subscribed_symbols, &tx).await;
-                    let _ = tx.send(ServerMessage::Subscribed { symbols }).await;
-                }
-                Ok(ClientMessage::Unsubscribe { symbols }) => {
-                    tracing::info!("Client unsubscribing from: {:?}", symbols);
-                    {
-                        let mut subs = subscribed_symbols.write().await;
-                        for sym in &symbols {
-                            subs.remove(sym);
-                        }
-                    }
-                    reconnect_surge(&surge, &subscribed_symbols, &tx).await;
-                    let _ = tx.send(ServerMessage::Unsubscribed { symbols }).await;
-                }
-                Err(e) => {
-                    let _ = tx.send(ServerMessage::Error { message: format!("Invalid message: {}", e) }).await;
-                }
-            },
+        let parsed = match msg {
+            Ok(Message::Text(text)) => {
+                encoding.get_or_init(|| ClientEncoding::Json);
+                serde_json::from_str::<ClientMessage>(&text).map_err(|e| e.to_string())
+            }
+            Ok(Message::Binary(bytes)) => {
+                encoding.get_or_init(|| ClientEncoding::MessagePack);
+                decode_binary(&bytes)
+            }
             Ok(Message::Close(_)) => {
                 tracing::info!("Client sent close frame");
                 break;
@@ -140,43 +237,88 @@ async fn handle_socket(socket: WebSocket) {
                 tracing::warn!("WebSocket error: {}", e);
                 break;
             }
-            _ => {}
+            _ => continue,
+        };
+
+        match parsed {
+            Ok(ClientMessage::Subscribe { symbols }) => {
+                tracing::info!("Client subscribing to: {:?}", symbols);
+                {
+                    let mut subs = subscribed_symbols.write().await;
+                    subs.extend(symbols.clone());
+                }
+                reconnect_surge(&surge, &subscribed_symbols, &tx, &pool).await;
+                let _ = tx.send(ServerMessage::Subscribed { symbols }).await;
+            }
+            Ok(ClientMessage::Unsubscribe { symbols }) => {
+                tracing::info!("Client unsubscribing from: {:?}", symbols);
+                {
+                    let mut subs = subscribed_symbols.write().await;
+                    for sym in &symbols {
+                        subs.remove(sym);
+                    }
+                }
+                reconnect_surge(&surge, &subscribed_symbols, &tx, &pool).await;
+                let _ = tx.send(ServerMessage::Unsubscribed { symbols }).await;
+            }
+            Ok(ClientMessage::SetBatchWindow { millis }) => {
+                let millis = millis.min(MAX_BATCH_WINDOW_MS);
+                tracing::debug!("Client batch window set to {}ms", millis);
+                window_tx.send_replace(millis);
+                let _ = tx.send(ServerMessage::BatchWindow { millis }).await;
+            }
+            Err(e) => {
+                let _ = tx.send(ServerMessage::Error { message: format!("Invalid message: {}", e) }).await;
+            }
         }
     }
 
     // Cleanup
     relay_task.abort();
     send_task.abort();
-    if let Some(s) = surge.write().await.take() {
-        let _ = s.disconnect().await;
-    }
+    // Dropping the lease releases the pooled connection
+    surge.write().await.take();
 
     ws_connection_closed();
     tracing::info!("WebSocket connection closed");
 }
 
+/// Timer that flushes buffered prices `window_ms` after it is reset
+fn batch_interval(window_ms: u64) -> tokio::time::Interval {
+    let period = Duration::from_millis(window_ms.max(1));
+    tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+}
+
+/// Encode and send one message, returning `false` once the client is gone
+async fn send_message(
+    sender: &mut SplitSink<WebSocket, Message>,
+    encoding: &OnceLock<ClientEncoding>,
+    msg: &ServerMessage,
+) -> bool {
+    let encoding = encoding.get().copied().unwrap_or(ClientEncoding::Json);
+    match encoding.encode(msg) {
+        Some(frame) => sender.send(frame).await.is_ok(),
+        None => true,
+    }
+}
+
+/// Move this client to the pooled connection for its current subscriptions
 async fn reconnect_surge(
-    surge: &Arc<RwLock<Option<Surge>>>,
+    surge: &Arc<RwLock<Option<PoolLease>>>,
     subscribed_symbols: &Arc<RwLock<HashSet<String>>>,
     tx: &mpsc::Sender<ServerMessage>,
+    pool: &SharedSurgePool,
 ) {
-    // Disconnect existing connection
-    if let Some(old_surge) = surge.write().await.take() {
-        let _ = old_surge.disconnect().await;
-    }
+    // Leave the previous connection
+    surge.write().await.take();
 
-    let symbols: Vec<String> = {
-        let current_subs = subscribed_symbols.read().await;
-        if current_subs.is_empty() {
-            return;
-        }
-        current_subs.iter().cloned().collect()
-    };
+    let symbols = subscribed_symbols.read().await.clone();
+    if symbols.is_empty() {
+        return;
+    }
 
-    let symbol_refs: Vec<&str> = symbols.iter().map(String::as_str).collect();
-    let mut new_surge = Surge::new("");
-    match new_surge.connect_and_subscribe(symbol_refs).await {
-        Ok(()) => *surge.write().await = Some(new_surge),
+    match pool.lease(&symbols).await {
+        Ok(lease) => *surge.write().await = Some(lease),
         Err(e) => {
             let _ = tx.send(ServerMessage::Error { message: e.to_string() }).await;
         }
@@ -238,6 +380,13 @@ mod tests {
         assert!(result.is_err(), "Should fail for invalid action");
     }
 
+    #[test]
+    fn test_client_message_set_batch_window() {
+        let json = r#"{"action": "set_batch_window", "millis": 20}"#;
+        let msg: ClientMessage = serde_json::from_str(json).unwrap();
+        assert!(matches!(msg, ClientMessage::SetBatchWindow { millis: 20 }));
+    }
+
     #[test]
     fn test_client_message_missing_symbols() {
         let json = r#"{"action": "subscribe"}"#;
@@ -312,6 +461,47 @@ mod tests {
         assert!(json.contains(r#""message":"Connection failed""#));
     }
 
+    #[test]
+    fn test_server_message_batch_serialization() {
+        let msg = ServerMessage::Batch {
+            updates: vec![
+                PriceEntry { symbol: "BTC/USD".to_string(), price: 89846.94, timestamp: 1705936800000, feed_id: None },
+                PriceEntry {
+                    symbol: "ETH/USD".to_string(),
+                    price: 3245.5,
+                    timestamp: 1705936800001,
+                    feed_id: Some("abc123".to_string()),
+                },
+            ],
+        };
+        let json = serde_json::to_value(&msg).unwrap();
+
+        assert_eq!(json["type"], "batch");
+        assert_eq!(json["updates"][0], serde_json::json!({ "symbol": "BTC/USD", "price": 89846.94, "timestamp": 1705936800000_i64 }));
+        assert_eq!(json["updates"][1]["feed_id"], "abc123");
+    }
+
+    #[test]
+    fn test_server_message_batch_window_serialization() {
+        let json = serde_json::to_string(&ServerMessage::BatchWindow { millis: 50 }).unwrap();
+        assert_eq!(json, r#"{"type":"batch_window","millis":50}"#);
+    }
+
+    #[test]
+    fn test_from_batch_keeps_single_update_as_price() {
+        let entry = |symbol: &str| PriceEntry { symbol: symbol.to_string(), price: 1.0, timestamp: 0, feed_id: None };
+
+        assert!(ServerMessage::from_batch(Vec::new()).is_none());
+        assert!(matches!(
+            ServerMessage::from_batch(vec![entry("BTC/USD")]),
+            Some(ServerMessage::Price { symbol, .. }) if symbol == "BTC/USD"
+        ));
+        assert!(matches!(
+            ServerMessage::from_batch(vec![entry("BTC/USD"), entry("ETH/USD")]),
+            Some(ServerMessage::Batch { updates }) if updates.len() == 2
+        ));
+    }
+
     // === Message roundtrip tests ===
 
     #[test]
@@ -333,6 +523,64 @@ mod tests {
         assert_eq!(parsed["feed_id"], "xyz789");
     }
 
+    // === Binary (MessagePack) tests ===
+
+    #[test]
+    fn test_binary_subscribe_roundtrip() {
+        let bytes = rmp_serde::to_vec_named(&serde_json::json!({
+            "action": "subscribe",
+            "symbols": ["BTC/USD", "ETH/USD"]
+        }))
+        .unwrap();
+
+        match decode_binary(&bytes).unwrap() {
+            ClientMessage::Subscribe { symbols } => assert_eq!(symbols, vec!["BTC/USD", "ETH/USD"]),
+            _ => panic!("Expected Subscribe variant"),
+        }
+    }
+
+    #[test]
+    fn test_binary_unsubscribe_roundtrip() {
+        let bytes = rmp_serde::to_vec_named(&serde_json::json!({
+            "action": "unsubscribe",
+            "symbols": ["SOL/USD"]
+        }))
+        .unwrap();
+
+        match decode_binary(&bytes).unwrap() {
+            ClientMessage::Unsubscribe { symbols } => assert_eq!(symbols, vec!["SOL/USD"]),
+            _ => panic!("Expected Unsubscribe variant"),
+        }
+    }
+
+    #[test]
+    fn test_binary_falls_back_to_json() {
+        let bytes = br#"{"action": "subscribe", "symbols": ["BTC/USD"]}"#;
+        assert!(matches!(decode_binary(bytes), Ok(ClientMessage::Subscribe { .. })));
+    }
+
+    #[test]
+    fn test_binary_garbage_is_error() {
+        assert!(decode_binary(&[0xc1, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_encode_server_message_as_msgpack() {
+        let msg = ServerMessage::Subscribed { symbols: vec!["BTC/USD".to_string()] };
+        let Some(Message::Binary(bytes)) = ClientEncoding::MessagePack.encode(&msg) else {
+            panic!("Expected binary frame");
+        };
+        let decoded: serde_json::Value = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded["type"], "subscribed");
+        assert_eq!(decoded["symbols"][0], "BTC/USD");
+    }
+
+    #[test]
+    fn test_encode_server_message_as_json() {
+        let msg = ServerMessage::Error { message: "oops".to_string() };
+        assert!(matches!(ClientEncoding::Json.encode(&msg), Some(Message::Text(t)) if t.contains("oops")));
+    }
+
     // === Subscription management tests ===
 
     #[test]
@@ -1,13 +1,18 @@
 use futures_util::{SinkExt, StreamExt};
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::{broadcast, mpsc, RwLock};
-use tokio::time::sleep;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicI64, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc, oneshot, RwLock};
+use tokio::time::{sleep, timeout};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use url::Url;
 
+use crate::client::{build_http_client, SurgeClient};
 use crate::error::{Result, SurgeError};
-use crate::types::{SurgeConfig, SurgeEvent, SurgeFeedInfo, SurgeUpdate, SubscriptionRequest, SymbolRequest};
+use crate::pipe::PipeFormat;
+use crate::tracing_ext::{self, SurgeTracer};
+use crate::types::{now_ms, BackoffConfig, SurgeConfig, SurgeEvent, SurgeFeedInfo, SurgeUpdate, SubscriptionRequest, SymbolRequest};
 
 /// Surge streaming client for real-time price updates
 pub struct Surge {
@@ -15,13 +20,491 @@ pub struct Surge {
     event_tx: broadcast::Sender<SurgeEvent>,
     control_tx: Option<mpsc::Sender<ControlMessage>>,
     is_connected: Arc<RwLock<bool>>,
-    subscriptions: Arc<RwLock<Vec<String>>>,
+    /// Number of callers subscribed to each symbol
+    ref_count: Arc<RwLock<HashMap<String, usize>>>,
+    hooks: LifecycleHooks,
+    event_count: EventCounter,
+    /// Most recent update received per symbol
+    last_prices: Arc<RwLock<HashMap<String, CachedPrice>>>,
+    stats: ConnectionStats,
+    /// REST client for connect snapshots; `None` uses [`default_snapshot_client`]
+    snapshot_client: Option<Arc<SurgeClient>>,
+}
+
+/// Connection health snapshot returned by [`Surge::statistics`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SurgeStatistics {
+    /// Reconnection attempts since the connection loop started
+    pub reconnect_count: u32,
+    /// Text frames received from the upstream WebSocket
+    pub messages_received: u64,
+    pub last_message_at: Option<Instant>,
+    /// Seconds the current connection has been open; 0 while disconnected
+    pub uptime_secs: u64,
+    /// Symbols with at least one subscriber
+    pub active_subscriptions: usize,
+    /// See [`Surge::event_lag_ms`]
+    pub avg_lag_ms: Option<f64>,
+}
+
+/// Smoothing factor giving the EWMA roughly the weight of the last 30 updates
+const LAG_EWMA_ALPHA: f64 = 2.0 / 31.0;
+
+/// EWMA of update lag in ms, stored as `f64` bits; NaN until the first sample
+#[derive(Clone)]
+struct LagEwma(Arc<AtomicU64>);
+
+impl Default for LagEwma {
+    fn default() -> Self {
+        Self(Arc::new(AtomicU64::new(f64::NAN.to_bits())))
+    }
+}
+
+impl LagEwma {
+    fn record(&self, lag_ms: f64) {
+        let _ = self.0.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+            let avg = f64::from_bits(bits);
+            let next = if avg.is_nan() { lag_ms } else { avg + LAG_EWMA_ALPHA * (lag_ms - avg) };
+            Some(next.to_bits())
+        });
+    }
+
+    fn get(&self) -> Option<f64> {
+        Some(f64::from_bits(self.0.load(Ordering::Relaxed))).filter(|avg| !avg.is_nan())
+    }
+}
+
+/// Counters updated by the connection loop
+#[derive(Clone, Default)]
+struct ConnectionStats {
+    reconnects: Arc<AtomicU32>,
+    messages: Arc<AtomicU64>,
+    /// Unix ms of the last message, 0 before the first
+    last_message_ms: Arc<AtomicI64>,
+    /// Unix ms the current connection opened, 0 while disconnected
+    connected_since_ms: Arc<AtomicI64>,
+    lag: LagEwma,
+}
+
+impl ConnectionStats {
+    fn message_received(&self) {
+        self.messages.fetch_add(1, Ordering::Relaxed);
+        self.last_message_ms.store(now_ms(), Ordering::Relaxed);
+    }
+
+    fn set_connected(&self, connected: bool) {
+        let since = if connected { now_ms() } else { 0 };
+        self.connected_since_ms.store(since, Ordering::Relaxed);
+    }
+}
+
+/// Local `Instant` for a past unix-ms timestamp; `None` for 0
+fn instant_at(unix_ms: i64) -> Option<Instant> {
+    if unix_ms <= 0 {
+        return None;
+    }
+    Instant::now().checked_sub(Duration::from_millis((now_ms() - unix_ms).max(0) as u64))
+}
+
+/// Last update seen for a symbol, stamped with its local receive time
+#[derive(Debug, Clone)]
+pub struct CachedPrice {
+    pub update: SurgeUpdate,
+    received_at: Instant,
+}
+
+impl CachedPrice {
+    /// Milliseconds since this update was received
+    pub fn age_ms(&self) -> u64 {
+        self.received_at.elapsed().as_millis() as u64
+    }
+}
+
+/// How often the events-per-second window is restarted
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Shared count of events emitted by one or more [`Surge`] clients
+#[derive(Clone)]
+pub struct EventCounter {
+    total: Arc<AtomicU64>,
+    window: Arc<Mutex<(Instant, u64)>>,
+}
+
+impl Default for EventCounter {
+    fn default() -> Self {
+        Self {
+            total: Arc::new(AtomicU64::new(0)),
+            window: Arc::new(Mutex::new((Instant::now(), 0))),
+        }
+    }
+}
+
+impl EventCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total events emitted so far
+    pub fn get(&self) -> u64 {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    fn increment(&self) {
+        self.total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Average rate since the current window began; the window restarts every 10 seconds
+    pub fn events_per_second(&self) -> f64 {
+        let total = self.get();
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        let (started, count_at_start) = *window;
+        let elapsed = started.elapsed();
+        let rate = if elapsed.is_zero() {
+            0.0
+        } else {
+            total.saturating_sub(count_at_start) as f64 / elapsed.as_secs_f64()
+        };
+        if elapsed >= THROUGHPUT_WINDOW {
+            *window = (Instant::now(), total);
+        }
+        rate
+    }
+}
+
+fn diff_symbols(current: &HashMap<String, usize>, desired: &[&str]) -> (Vec<String>, Vec<String>) {
+    let desired: std::collections::BTreeSet<&str> = desired.iter().copied().collect();
+    let to_add = desired.iter().filter(|s| !current.contains_key(**s)).map(|s| s.to_string()).collect();
+    let mut to_remove: Vec<String> = current.keys().filter(|s| !desired.contains(s.as_str())).cloned().collect();
+    to_remove.sort();
+    (to_add, to_remove)
+}
+
+/// Watched symbols still subscribed with no update within `threshold`.
+/// Symbols never updated count as stale once `watched_for` exceeds `threshold`.
+fn stale_symbols(
+    watched: &[String],
+    subscriptions: &HashMap<String, usize>,
+    last_prices: &HashMap<String, CachedPrice>,
+    threshold: Duration,
+    watched_for: Duration,
+) -> Vec<String> {
+    let threshold_ms = threshold.as_millis() as u64;
+    watched
+        .iter()
+        .filter(|s| subscriptions.contains_key(*s))
+        .filter(|s| match last_prices.get(*s) {
+            Some(cached) => cached.age_ms() > threshold_ms,
+            None => watched_for > threshold,
+        })
+        .cloned()
+        .collect()
+}
+
+/// `StalePrice` events for subscribed symbols whose cached update is older than
+/// `threshold_ms`, skipping those already in `reported`. Symbols that are fresh
+/// again (or unsubscribed) leave `reported`; never-updated symbols are ignored.
+fn stale_price_events(
+    subscriptions: &HashMap<String, usize>,
+    last_prices: &HashMap<String, CachedPrice>,
+    threshold_ms: u64,
+    reported: &mut HashSet<String>,
+) -> Vec<SurgeEvent> {
+    reported.retain(|symbol| {
+        subscriptions.contains_key(symbol) && last_prices.get(symbol).is_some_and(|c| c.age_ms() > threshold_ms)
+    });
+    let mut events = Vec::new();
+    for symbol in subscriptions.keys() {
+        let Some(cached) = last_prices.get(symbol) else {
+            continue;
+        };
+        let age_ms = cached.age_ms();
+        if age_ms > threshold_ms && reported.insert(symbol.clone()) {
+            events.push(SurgeEvent::StalePrice {
+                symbol: symbol.clone(),
+                last_update_ms: cached.update.data.source_timestamp_ms,
+                age_ms,
+            });
+        }
+    }
+    events
+}
+
+/// Buffer of each per-symbol channel from [`Surge::subscribe_price_updates`]
+const PRICE_RELAY_CAPACITY: usize = 100;
+
+/// Broadcast an event, counting it only if a receiver got it
+fn emit(event_tx: &broadcast::Sender<SurgeEvent>, event_count: &EventCounter, event: SurgeEvent) {
+    if event_tx.send(event).is_ok() {
+        event_count.increment();
+    }
+}
+
+/// Random delay in `0..=max_ms`. The randomness comes entirely from the
+/// SipHash keys of a fresh `RandomState`: they are seeded randomly per thread
+/// and advanced on every `RandomState::new`, so each empty hash differs.
+fn jitter(max_ms: u64) -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+    if max_ms == 0 {
+        return 0;
+    }
+    std::collections::hash_map::RandomState::new().build_hasher().finish() % (max_ms + 1)
+}
+
+/// REST client shared by every [`Surge`] without its own snapshot client,
+/// built on first use; `None` if it could not be built
+fn default_snapshot_client() -> Option<Arc<SurgeClient>> {
+    static CLIENT: once_cell::sync::OnceCell<Option<Arc<SurgeClient>>> = once_cell::sync::OnceCell::new();
+    CLIENT
+        .get_or_init(|| {
+            SurgeClient::new()
+                .inspect_err(|e| tracing::warn!("Snapshots disabled, REST client unavailable: {}", e))
+                .ok()
+                .map(Arc::new)
+        })
+        .clone()
+}
+
+/// Fetch a snapshot of `symbols` in the background and emit it as [`SurgeEvent::Snapshot`],
+/// so the socket keeps being read while the REST calls are in flight
+fn spawn_snapshot(
+    client: Option<Arc<SurgeClient>>,
+    symbols: Vec<String>,
+    limit: Duration,
+    event_tx: broadcast::Sender<SurgeEvent>,
+    event_count: EventCounter,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let prices = fetch_snapshot(client.as_deref(), &symbols, limit).await;
+        emit(&event_tx, &event_count, SurgeEvent::Snapshot { prices });
+    })
+}
+
+/// Current REST prices for `symbols`, bounded by `limit`.
+/// Failures are logged and left out rather than reported as errors.
+async fn fetch_snapshot(client: Option<&SurgeClient>, symbols: &[String], limit: Duration) -> Vec<SurgeUpdate> {
+    let Some(client) = client else {
+        return Vec::new();
+    };
+    let fetches = symbols.iter().map(|symbol| client.get_price(symbol));
+    match timeout(limit, futures_util::future::join_all(fetches)).await {
+        Ok(results) => results
+            .into_iter()
+            .filter_map(|result| {
+                result
+                    .inspect_err(|e| tracing::warn!("Snapshot fetch failed: {}", e))
+                    .ok()
+            })
+            .map(SurgeUpdate::from)
+            .collect(),
+        Err(_) => {
+            tracing::warn!("Snapshot fetch timed out after {}ms", limit.as_millis());
+            Vec::new()
+        }
+    }
+}
+
+/// Remember the latest update per symbol carried by `event`
+async fn cache_updates(last_prices: &RwLock<HashMap<String, CachedPrice>>, event: &SurgeEvent) {
+    let updates = event.updates();
+    if updates.is_empty() {
+        return;
+    }
+    let received_at = Instant::now();
+    let mut cache = last_prices.write().await;
+    for update in updates {
+        let cached = CachedPrice { update: update.clone(), received_at };
+        cache.insert(update.data.symbol.clone(), cached);
+    }
+}
+
+/// Parse a text frame as a batch (`[...]`) or a single price update
+fn parse_price_message(text: &str) -> Option<SurgeEvent> {
+    if text.trim_start().starts_with('[') {
+        if let Ok(updates) = serde_json::from_str::<Vec<SurgeUpdate>>(text) {
+            return Some(SurgeEvent::PriceBatch(updates));
+        }
+    }
+    serde_json::from_str::<SurgeUpdate>(text).ok().map(SurgeEvent::PriceUpdate)
+}
+
+/// Subscription acknowledgment frame, e.g.
+/// `{"type":"subscribed","symbols":["BTC/USD"]}` or
+/// `{"type":"subscription_failed","symbols":[{"symbol":"X/USD"}],"reason":"unknown symbol"}`
+#[derive(serde::Deserialize)]
+struct SubscriptionAck {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    symbols: Vec<AckSymbol>,
+    #[serde(default, alias = "message", alias = "error")]
+    reason: Option<String>,
+}
+
+/// Symbols in an ack may be bare names or echo the request's `{"symbol": ..}` objects
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum AckSymbol {
+    Name(String),
+    Request(SymbolRequest),
+}
+
+impl SubscriptionAck {
+    /// The request action this ack answers and whether it succeeded
+    fn outcome(&self) -> Option<(&'static str, bool)> {
+        match self.kind.as_str() {
+            "subscribed" => Some(("subscribe", true)),
+            "unsubscribed" => Some(("unsubscribe", true)),
+            "subscription_failed" | "subscribe_failed" => Some(("subscribe", false)),
+            _ => None,
+        }
+    }
+
+    fn symbol_names(&self) -> Vec<String> {
+        self.symbols
+            .iter()
+            .map(|s| match s {
+                AckSymbol::Name(symbol) | AckSymbol::Request(SymbolRequest { symbol }) => symbol.clone(),
+            })
+            .collect()
+    }
+
+    fn reason(&self) -> String {
+        self.reason.clone().unwrap_or_else(|| "Subscription rejected".to_string())
+    }
+
+    /// Event for subscribe acks; unsubscribe acks only resolve pending requests
+    fn event(&self) -> Option<SurgeEvent> {
+        let symbols = self.symbol_names();
+        match self.outcome()? {
+            ("subscribe", true) => Some(SurgeEvent::SubscriptionConfirmed { symbols }),
+            ("subscribe", false) => Some(SurgeEvent::SubscriptionFailed { symbols, reason: self.reason() }),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a text frame as a subscribe/unsubscribe acknowledgment
+fn parse_subscription_ack(text: &str) -> Option<SubscriptionAck> {
+    serde_json::from_str::<SubscriptionAck>(text).ok().filter(|ack| ack.outcome().is_some())
+}
+
+/// Replies to a subscribe/unsubscribe request once the server acknowledges it
+type AckSender = oneshot::Sender<Result<Vec<String>>>;
+
+/// A request still waiting for some of its symbols to be acknowledged
+struct PendingAck {
+    remaining: HashSet<String>,
+    confirmed: Vec<String>,
+    reply: AckSender,
+}
+
+/// Requests awaiting acknowledgment, keyed by action, oldest first
+#[derive(Default)]
+struct PendingAcks(HashMap<&'static str, Vec<PendingAck>>);
+
+impl PendingAcks {
+    fn push(&mut self, action: &'static str, symbols: Vec<String>, reply: AckSender) {
+        let waiting = self.0.entry(action).or_default();
+        // Callers that gave up have dropped their receiver
+        waiting.retain(|pending| !pending.reply.is_closed());
+        waiting.push(PendingAck { remaining: symbols.into_iter().collect(), confirmed: Vec::new(), reply });
+    }
+
+    /// Credit the ack's symbols to waiting requests, replying to each once all
+    /// of its symbols are confirmed or any of them is rejected
+    fn resolve(&mut self, ack: &SubscriptionAck) {
+        let Some((action, ok)) = ack.outcome() else {
+            return;
+        };
+        let Some(waiting) = self.0.get_mut(action) else {
+            return;
+        };
+        let mut symbols = ack.symbol_names();
+        if symbols.is_empty() {
+            // An ack that doesn't list symbols answers the oldest request
+            match waiting.first() {
+                Some(pending) => symbols = pending.remaining.iter().cloned().collect(),
+                None => return,
+            }
+        }
+
+        for mut pending in std::mem::take(waiting) {
+            let matched: Vec<String> = symbols.iter().filter(|&s| pending.remaining.remove(s)).cloned().collect();
+            if !ok && !matched.is_empty() {
+                let message = format!("Subscription to {} failed: {}", matched.join(", "), ack.reason());
+                let _ = pending.reply.send(Err(SurgeError::ApiError(message)));
+                continue;
+            }
+            pending.confirmed.extend(matched);
+            if pending.remaining.is_empty() {
+                let _ = pending.reply.send(Ok(pending.confirmed));
+            } else {
+                waiting.push(pending);
+            }
+        }
+    }
+}
+
+/// Build a subscribe/unsubscribe frame, `None` when there is nothing to send
+fn subscription_message(action: &str, symbols: &[String]) -> Option<Message> {
+    if symbols.is_empty() {
+        return None;
+    }
+    let request = SubscriptionRequest {
+        action: action.to_string(),
+        symbols: symbols.iter().map(|s| SymbolRequest { symbol: s.clone() }).collect(),
+    };
+    serde_json::to_string(&request).ok().map(Message::Text)
 }
 
 enum ControlMessage {
+    Subscribe(Vec<String>, Option<AckSender>),
+    Unsubscribe(Vec<String>, Option<AckSender>),
     Disconnect,
 }
 
+type Hook = Arc<dyn Fn() + Send + Sync>;
+type ErrorHook = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Callbacks run by the connection loop on lifecycle transitions
+#[derive(Clone, Default)]
+struct LifecycleHooks {
+    on_connected: Vec<Hook>,
+    on_disconnected: Vec<Hook>,
+    on_error: Vec<ErrorHook>,
+    observers: Vec<SurgeTracer>,
+}
+
+impl LifecycleHooks {
+    fn connected(&self) {
+        self.on_connected.iter().for_each(|hook| hook());
+    }
+
+    fn disconnected(&self) {
+        self.on_disconnected.iter().for_each(|hook| hook());
+    }
+
+    fn error(&self, message: &str) {
+        self.on_error.iter().for_each(|hook| hook(message));
+        self.observers.iter().for_each(|observer| observer.on_error(message));
+    }
+
+    fn reconnect(&self, attempt: u32, delay_ms: u64) {
+        self.observers.iter().for_each(|observer| observer.on_reconnect(attempt, delay_ms));
+    }
+
+    fn price_updates(&self, event: &SurgeEvent) {
+        if self.observers.is_empty() {
+            return;
+        }
+        let now = now_ms();
+        for update in event.updates() {
+            let latency = tracing_ext::latency_ms(update, now);
+            self.observers.iter().for_each(|observer| observer.on_price_update(update, latency));
+        }
+    }
+}
+
 impl Surge {
     pub fn new(api_key: impl Into<String>) -> Self {
         let (event_tx, _) = broadcast::channel(1000);
@@ -34,17 +517,147 @@ impl Surge {
             event_tx,
             control_tx: None,
             is_connected: Arc::new(RwLock::new(false)),
-            subscriptions: Arc::new(RwLock::new(Vec::new())),
+            ref_count: Arc::new(RwLock::new(HashMap::new())),
+            hooks: LifecycleHooks::default(),
+            event_count: EventCounter::default(),
+            last_prices: Arc::new(RwLock::new(HashMap::new())),
+            stats: ConnectionStats::default(),
+            snapshot_client: None,
         }
     }
 
+    /// Create a client with the API key from `SURGE_API_KEY`
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("SURGE_API_KEY") {
+            Ok(key) if !key.trim().is_empty() => Ok(Self::new(key)),
+            _ => Err(SurgeError::InvalidApiKey("SURGE_API_KEY not set".into())),
+        }
+    }
+
+    /// Count emitted events into a shared counter, e.g. one aggregated across clients
+    pub fn with_event_counter(mut self, counter: EventCounter) -> Self {
+        self.event_count = counter;
+        self
+    }
+
+    /// Fetch connect snapshots with `client`, e.g. one shared by many connections,
+    /// instead of the process-wide default client
+    pub fn with_snapshot_client(mut self, client: Arc<SurgeClient>) -> Self {
+        self.snapshot_client = Some(client);
+        self
+    }
+
+    /// Total events emitted by the connection loop
+    pub fn event_count(&self) -> u64 {
+        self.event_count.get()
+    }
+
+    /// Events emitted per second over the current 10 second window
+    pub fn events_per_second(&self) -> f64 {
+        self.event_count.events_per_second()
+    }
+
+    /// Text frames received from the upstream WebSocket
+    pub fn messages_received(&self) -> u64 {
+        self.stats.messages.load(Ordering::Relaxed)
+    }
+
+    /// Reconnection attempts since the connection loop started
+    pub fn reconnect_count(&self) -> u32 {
+        self.stats.reconnects.load(Ordering::Relaxed)
+    }
+
+    /// Snapshot of connection health: reconnects, message counts and uptime
+    pub async fn statistics(&self) -> SurgeStatistics {
+        let connected_since = self.stats.connected_since_ms.load(Ordering::Relaxed);
+        SurgeStatistics {
+            reconnect_count: self.reconnect_count(),
+            messages_received: self.messages_received(),
+            last_message_at: instant_at(self.stats.last_message_ms.load(Ordering::Relaxed)),
+            uptime_secs: if connected_since > 0 { ((now_ms() - connected_since).max(0) / 1000) as u64 } else { 0 },
+            active_subscriptions: self.ref_count.read().await.len(),
+            avg_lag_ms: self.event_lag_ms(),
+        }
+    }
+
+    /// Moving average (EWMA over ~30 updates) of the time from an update's
+    /// source timestamp to its arrival here, in ms; `None` before the first update
+    pub fn event_lag_ms(&self) -> Option<f64> {
+        self.stats.lag.get()
+    }
+
+    /// Set the WebSocket handshake timeout
+    pub fn connect_timeout(mut self, ms: u64) -> Self {
+        self.config.connect_timeout_ms = ms;
+        self
+    }
+
+    /// Set how long an open connection may go without a data frame before reconnecting
+    pub fn read_timeout(mut self, ms: u64) -> Self {
+        self.config.read_timeout_ms = ms;
+        self
+    }
+
+    /// Set how often a WebSocket ping is sent
+    pub fn ping_interval(mut self, ms: u64) -> Self {
+        self.config.ping_interval_ms = ms;
+        self
+    }
+
+    /// Set how long to wait for a pong before reconnecting
+    pub fn pong_timeout(mut self, ms: u64) -> Self {
+        self.config.pong_timeout_ms = ms;
+        self
+    }
+
+    /// Set how old a symbol's last update may get before `StalePrice` is emitted (0 disables)
+    pub fn stale_threshold(mut self, ms: u64) -> Self {
+        self.config.stale_threshold_ms = ms;
+        self
+    }
+
+    /// Set the delay schedule between reconnection attempts
+    pub fn with_backoff_config(mut self, config: BackoffConfig) -> Self {
+        self.config.backoff = config;
+        self
+    }
+
+    /// Exponential backoff without jitter: `initial_ms * multiplier^n`, capped at `cap_ms`
+    pub fn with_exponential_backoff_config(self, initial_ms: u64, multiplier: f64, cap_ms: u64) -> Self {
+        self.with_backoff_config(BackoffConfig { initial_ms, multiplier, cap_ms, jitter_ms: 0, jitter_factor: 0.0 })
+    }
+
+    /// Run `hook` every time the WebSocket connection is established
+    pub fn on_connected<F: Fn() + Send + Sync + 'static>(mut self, hook: F) -> Self {
+        self.hooks.on_connected.push(Arc::new(hook));
+        self
+    }
+
+    /// Run `hook` every time the WebSocket connection drops
+    pub fn on_disconnected<F: Fn() + Send + Sync + 'static>(mut self, hook: F) -> Self {
+        self.hooks.on_disconnected.push(Arc::new(hook));
+        self
+    }
+
+    /// Run `hook` with the error message whenever the client reports an error
+    pub fn on_error<F: Fn(&str) + Send + Sync + 'static>(mut self, hook: F) -> Self {
+        self.hooks.on_error.push(Arc::new(hook));
+        self
+    }
+
+    /// Forward price updates, reconnects and errors to `observer`
+    pub fn with_observer(mut self, observer: SurgeTracer) -> Self {
+        self.hooks.observers.push(observer);
+        self
+    }
+
     pub fn subscribe_events(&self) -> broadcast::Receiver<SurgeEvent> {
         self.event_tx.subscribe()
     }
 
     pub async fn connect_and_subscribe(&mut self, symbols: Vec<&str>) -> Result<()> {
-        let symbols: Vec<String> = symbols.iter().map(|&s| s.to_owned()).collect();
-        *self.subscriptions.write().await = symbols;
+        self.config.validate()?;
+        *self.ref_count.write().await = symbols.iter().map(|&s| (s.to_owned(), 1)).collect();
 
         let (control_tx, control_rx) = mpsc::channel(100);
         self.control_tx = Some(control_tx);
@@ -52,16 +665,344 @@ impl Surge {
         let config = self.config.clone();
         let event_tx = self.event_tx.clone();
         let is_connected = self.is_connected.clone();
-        let subscriptions = self.subscriptions.clone();
+        let subscriptions = self.ref_count.clone();
+        let hooks = self.hooks.clone();
+        let event_count = self.event_count.clone();
+        let last_prices = self.last_prices.clone();
+        let stats = self.stats.clone();
+        let snapshot_client = self.snapshot_client.clone().or_else(default_snapshot_client);
+
+        tokio::spawn(async move {
+            connection_loop(
+                config,
+                event_tx,
+                control_rx,
+                is_connected,
+                subscriptions,
+                hooks,
+                event_count,
+                last_prices,
+                stats,
+                snapshot_client,
+            )
+            .await;
+        });
+
+        Ok(())
+    }
+
+    /// Wait until the connection started by [`Surge::connect_and_subscribe`] is open.
+    ///
+    /// Returns as soon as `Connected` is seen (or already connected), fails on the
+    /// next `Error` event, and gives [`SurgeError::Timeout`] once `wait` elapses.
+    pub async fn await_connected(&self, wait: Duration) -> Result<()> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let mut events = self.subscribe_events();
+        if *self.is_connected.read().await {
+            return Ok(());
+        }
+        let connected = async {
+            loop {
+                match events.recv().await {
+                    Ok(SurgeEvent::Connected) => return Ok(()),
+                    Ok(SurgeEvent::Error(message)) => return Err(SurgeError::ConnectionError(message)),
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => {
+                        return Err(SurgeError::ConnectionError("Connection loop stopped".to_string()))
+                    }
+                }
+            }
+        };
+        timeout(wait, connected)
+            .await
+            .unwrap_or_else(|_| Err(SurgeError::Timeout(format!("Not connected after {}ms", wait.as_millis()))))
+    }
+
+    /// Wait for the server to acknowledge a subscription and return the confirmed symbols.
+    ///
+    /// Only acknowledgments emitted after this call are seen, so call it right after
+    /// [`Surge::connect_and_subscribe`] or [`Surge::subscribe`]. Fails on
+    /// `SubscriptionFailed` and gives [`SurgeError::Timeout`] once `wait` elapses.
+    pub async fn await_subscribed(&self, wait: Duration) -> Result<Vec<String>> {
+        use tokio::sync::broadcast::error::RecvError;
 
+        let mut events = self.subscribe_events();
+        let confirmed = async {
+            loop {
+                match events.recv().await {
+                    Ok(SurgeEvent::SubscriptionConfirmed { symbols }) => return Ok(symbols),
+                    Ok(SurgeEvent::SubscriptionFailed { symbols, reason }) => {
+                        return Err(SurgeError::ApiError(format!("Subscription to {} failed: {}", symbols.join(", "), reason)))
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => {
+                        return Err(SurgeError::ConnectionError("Connection loop stopped".to_string()))
+                    }
+                }
+            }
+        };
+        timeout(wait, confirmed)
+            .await
+            .unwrap_or_else(|_| Err(SurgeError::Timeout(format!("No subscription acknowledgment after {}ms", wait.as_millis()))))
+    }
+
+    /// Add a reference to each symbol, subscribing upstream to symbols not yet subscribed.
+    ///
+    /// Resolves with the symbols the server acknowledged, or [`SurgeError::Timeout`]
+    /// after `connect_timeout_ms`. Resolves at once with an empty list when nothing
+    /// needs sending: every symbol was already subscribed, or no connection is running
+    /// (the symbols are then subscribed on connect).
+    pub async fn subscribe(&self, symbols: Vec<&str>) -> Result<Vec<String>> {
+        let added: Vec<String> = {
+            let mut counts = self.ref_count.write().await;
+            symbols
+                .into_iter()
+                .filter_map(|symbol| {
+                    let count = counts.entry(symbol.to_owned()).or_insert(0);
+                    *count += 1;
+                    (*count == 1).then(|| symbol.to_owned())
+                })
+                .collect()
+        };
+        self.send_and_confirm(added, ControlMessage::Subscribe).await
+    }
+
+    /// Drop a reference to each symbol, unsubscribing upstream once no caller needs it.
+    ///
+    /// Resolves like [`Surge::subscribe`] with the symbols the server acknowledged
+    /// unsubscribing.
+    pub async fn unsubscribe(&self, symbols: Vec<&str>) -> Result<Vec<String>> {
+        let removed: Vec<String> = {
+            let mut counts = self.ref_count.write().await;
+            symbols
+                .into_iter()
+                .filter(|&symbol| match counts.get_mut(symbol) {
+                    Some(count) if *count > 1 => {
+                        *count -= 1;
+                        false
+                    }
+                    Some(_) => counts.remove(symbol).is_some(),
+                    None => false,
+                })
+                .map(str::to_owned)
+                .collect()
+        };
+        self.send_and_confirm(removed, ControlMessage::Unsubscribe).await
+    }
+
+    /// Unsubscribe regardless of how many callers hold a reference
+    pub async fn force_unsubscribe(&self, symbols: Vec<&str>) -> Result<()> {
+        let removed: Vec<String> = {
+            let mut counts = self.ref_count.write().await;
+            symbols
+                .into_iter()
+                .filter(|&symbol| counts.remove(symbol).is_some())
+                .map(str::to_owned)
+                .collect()
+        };
+        self.send_control(ControlMessage::Unsubscribe(removed, None)).await;
+        Ok(())
+    }
+
+    /// Subscribe like [`Surge::subscribe`], then watch for symbols that stop updating.
+    ///
+    /// A watchdog checks every `stale_threshold`; any of `symbols` still
+    /// subscribed whose last update is older than that (or that never updated)
+    /// gets its subscribe message re-sent and a `Reconnecting` event is emitted.
+    /// The watchdog stops when the connection loop exits or none of `symbols`
+    /// remain subscribed. Requires a running connection.
+    pub async fn subscribe_adaptive(&self, symbols: Vec<&str>, stale_threshold: Duration) -> Result<()> {
+        let Some(control_tx) = self.control_tx.clone() else {
+            return Err(SurgeError::ApiError("Not connected: call connect_and_subscribe first".to_string()));
+        };
+        let watched: Vec<String> = symbols.iter().map(|&s| s.to_owned()).collect();
+        self.subscribe(symbols).await?;
+
+        let subscriptions = self.ref_count.clone();
+        let last_prices = self.last_prices.clone();
+        let event_tx = self.event_tx.clone();
+        let event_count = self.event_count.clone();
         tokio::spawn(async move {
-            connection_loop(config, event_tx, control_rx, is_connected, subscriptions).await;
+            let started = Instant::now();
+            let mut attempt = 0;
+            let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + stale_threshold, stale_threshold);
+            loop {
+                ticker.tick().await;
+                let stale = {
+                    let subs = subscriptions.read().await;
+                    let prices = last_prices.read().await;
+                    if !watched.iter().any(|s| subs.contains_key(s)) {
+                        return;
+                    }
+                    stale_symbols(&watched, &subs, &prices, stale_threshold, started.elapsed())
+                };
+                for symbol in stale {
+                    attempt += 1;
+                    let delay_ms = stale_threshold.as_millis() as u64;
+                    emit(&event_tx, &event_count, SurgeEvent::Reconnecting { attempt, delay_ms });
+                    if control_tx.send(ControlMessage::Subscribe(vec![symbol], None)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Write every price update to `writer` in `format`, flushing after each one.
+    ///
+    /// Runs until the connection loop exits; a write error stops piping and is returned.
+    pub async fn pipe_to<W: tokio::io::AsyncWrite + Unpin>(&mut self, mut writer: W, format: PipeFormat) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+        use tokio::sync::broadcast::error::RecvError;
+
+        let Some(control_tx) = self.control_tx.clone() else {
+            return Err(SurgeError::ApiError("Not connected: call connect_and_subscribe first".to_string()));
+        };
+        let mut events = self.subscribe_events();
+        if let Some(header) = format.header() {
+            writer.write_all(&header).await?;
+            writer.flush().await?;
+        }
+
+        loop {
+            tokio::select! {
+                biased;
+                event = events.recv() => match event {
+                    Ok(event) => {
+                        for update in event.updates() {
+                            writer.write_all(&format.encode(update)?).await?;
+                            writer.flush().await?;
+                        }
+                    }
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return Ok(()),
+                },
+                _ = control_tx.closed() => return Ok(()),
+            }
+        }
+    }
+
+    /// [`Surge::pipe_to`] a newly created (or truncated) file
+    pub async fn pipe_to_file(&mut self, path: impl AsRef<std::path::Path>, format: PipeFormat) -> Result<()> {
+        let file = tokio::fs::File::create(path).await?;
+        self.pipe_to(tokio::io::BufWriter::new(file), format).await
+    }
+
+    /// Price updates for `symbol` only (shortcuts like "btc" work).
+    ///
+    /// A relay task filters the main event channel; it stops once every
+    /// returned receiver has been dropped and the next update arrives.
+    pub fn subscribe_price_updates(&self, symbol: &str) -> broadcast::Receiver<SurgeUpdate> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let symbol = crate::normalize_symbol(symbol);
+        let mut events = self.subscribe_events();
+        let (tx, rx) = broadcast::channel(PRICE_RELAY_CAPACITY);
+
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        for update in event.into_updates() {
+                            if crate::normalize_symbol(&update.data.symbol) == symbol && tx.send(update).is_err() {
+                                return;
+                            }
+                        }
+                        if tx.receiver_count() == 0 {
+                            return;
+                        }
+                    }
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return,
+                }
+            }
         });
+        rx
+    }
+
+    /// Most recent update received for `symbol` on the current connection
+    pub async fn last_price(&self, symbol: &str) -> Option<SurgeUpdate> {
+        self.last_prices.read().await.get(symbol).map(|cached| cached.update.clone())
+    }
+
+    /// Snapshot of the most recent update per symbol on the current connection.
+    /// Emptied on `Disconnected` and refilled as updates arrive after reconnecting.
+    pub async fn last_prices(&self) -> HashMap<String, SurgeUpdate> {
+        let prices = self.last_prices.read().await;
+        prices.iter().map(|(symbol, cached)| (symbol.clone(), cached.update.clone())).collect()
+    }
+
+    /// `(to_add, to_remove)` turning the current subscriptions into `desired`, each sorted
+    pub async fn subscription_diff(&self, desired: &[&str]) -> (Vec<String>, Vec<String>) {
+        diff_symbols(&*self.ref_count.read().await, desired)
+    }
 
-        sleep(Duration::from_millis(100)).await;
+    /// Replace the subscription set with `desired`.
+    ///
+    /// The diff is applied under one lock; removed symbols are dropped
+    /// regardless of their reference count, as with [`Surge::force_unsubscribe`].
+    pub async fn update_subscriptions(&self, desired: Vec<&str>) -> Result<()> {
+        let (to_add, to_remove) = {
+            let mut counts = self.ref_count.write().await;
+            let (to_add, to_remove) = diff_symbols(&counts, &desired);
+            for symbol in &to_remove {
+                counts.remove(symbol);
+            }
+            for symbol in &to_add {
+                counts.insert(symbol.clone(), 1);
+            }
+            (to_add, to_remove)
+        };
+        self.send_control(ControlMessage::Unsubscribe(to_remove, None)).await;
+        self.send_control(ControlMessage::Subscribe(to_add, None)).await;
         Ok(())
     }
 
+    /// Symbols with at least one subscriber, sorted
+    pub async fn get_subscriptions(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self.ref_count.read().await.keys().cloned().collect();
+        symbols.sort();
+        symbols
+    }
+
+    /// Forward a subscription change to the connection loop, if one is running
+    async fn send_control(&self, message: ControlMessage) {
+        if let Some(tx) = &self.control_tx {
+            let _ = tx.send(message).await;
+        }
+    }
+
+    /// Send a subscription change and wait up to `connect_timeout_ms` for the server to acknowledge it
+    async fn send_and_confirm(
+        &self,
+        symbols: Vec<String>,
+        message: fn(Vec<String>, Option<AckSender>) -> ControlMessage,
+    ) -> Result<Vec<String>> {
+        let Some(tx) = &self.control_tx else {
+            return Ok(Vec::new());
+        };
+        if symbols.is_empty() {
+            return Ok(Vec::new());
+        }
+        let stopped = || SurgeError::ConnectionError("Connection loop stopped".to_string());
+        let (reply_tx, reply_rx) = oneshot::channel();
+        tx.send(message(symbols, Some(reply_tx))).await.map_err(|_| stopped())?;
+
+        let wait = Duration::from_millis(self.config.connect_timeout_ms);
+        match timeout(wait, reply_rx).await {
+            Ok(reply) => reply.unwrap_or_else(|_| Err(stopped())),
+            Err(_) => Err(SurgeError::Timeout(format!("No subscription acknowledgment after {}ms", wait.as_millis()))),
+        }
+    }
+
+    /// Whether the connection loop is still running, i.e. connected or
+    /// reconnecting; `false` before connecting and once it has given up
+    pub fn is_running(&self) -> bool {
+        self.control_tx.as_ref().is_some_and(|tx| !tx.is_closed())
+    }
+
     pub async fn disconnect(&self) -> Result<()> {
         if let Some(tx) = &self.control_tx {
             let _ = tx.send(ControlMessage::Disconnect).await;
@@ -71,7 +1012,7 @@ impl Surge {
     }
 
     pub async fn get_surge_feeds(&self) -> Result<Vec<SurgeFeedInfo>> {
-        let client = reqwest::Client::new();
+        let client = build_http_client(None, self.config.no_proxy.as_deref())?;
         let url = format!("{}/feeds", self.config.api_url);
         let response = client
             .get(&url)
@@ -79,6 +1020,9 @@ impl Surge {
             .send()
             .await?;
 
+        if let Some(err) = SurgeError::from_rate_limit(&response) {
+            return Err(err);
+        }
         if !response.status().is_success() {
             return Err(SurgeError::ApiError(format!("Failed to fetch feeds: {}", response.status())));
         }
@@ -86,93 +1030,1005 @@ impl Surge {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn connection_loop(
     config: SurgeConfig,
     event_tx: broadcast::Sender<SurgeEvent>,
     mut control_rx: mpsc::Receiver<ControlMessage>,
     is_connected: Arc<RwLock<bool>>,
-    subscriptions: Arc<RwLock<Vec<String>>>,
+    subscriptions: Arc<RwLock<HashMap<String, usize>>>,
+    hooks: LifecycleHooks,
+    event_count: EventCounter,
+    last_prices: Arc<RwLock<HashMap<String, CachedPrice>>>,
+    stats: ConnectionStats,
+    snapshot_client: Option<Arc<SurgeClient>>,
 ) {
     let mut reconnect_attempts = 0;
-    let mut delay = config.initial_reconnect_delay_ms;
+    // Why the last connection attempt failed or ended, reported with `ReconnectFailed`
+    let mut last_error = String::new();
+    let backoff = config.reconnect_backoff();
+    let mut delay = backoff.initial_ms;
+    let connect_timeout = Duration::from_millis(config.connect_timeout_ms);
+    let read_timeout = Duration::from_millis(config.read_timeout_ms);
+    let ping_interval = Duration::from_millis(config.ping_interval_ms);
+    let pong_timeout = Duration::from_millis(config.pong_timeout_ms);
+    // Checking at half the threshold reports staleness within 1.5x of it
+    let stale_check = Duration::from_millis((config.stale_threshold_ms / 2).max(1));
+    // Kept across reconnects: the resubscribe on connect is acknowledged too
+    let mut pending_acks = PendingAcks::default();
 
     loop {
         let ws_url = format!("{}?apiKey={}", config.ws_url, config.api_key);
         let url = match Url::parse(&ws_url) {
             Ok(u) => u,
             Err(e) => {
-                let _ = event_tx.send(SurgeEvent::Error(format!("Invalid URL: {}", e)));
+                let message = format!("Invalid URL: {}", e);
+                hooks.error(&message);
+                emit(&event_tx, &event_count, SurgeEvent::Error(message));
                 return;
             }
         };
 
-        match connect_async(url).await {
-            Ok((ws_stream, _)) => {
+        match timeout(connect_timeout, connect_async(url)).await {
+            Ok(Ok((ws_stream, _))) => {
                 reconnect_attempts = 0;
-                delay = config.initial_reconnect_delay_ms;
+                delay = backoff.initial_ms;
                 *is_connected.write().await = true;
-                let _ = event_tx.send(SurgeEvent::Connected);
+                stats.set_connected(true);
+                emit(&event_tx, &event_count, SurgeEvent::Connected);
+                hooks.connected();
 
                 let (mut write, mut read) = ws_stream.split();
 
                 // Subscribe to symbols
-                let subscription_msg = {
-                    let current_subs = subscriptions.read().await;
-                    if current_subs.is_empty() {
-                        None
-                    } else {
-                        Some(SubscriptionRequest {
-                            action: "subscribe".to_string(),
-                            symbols: current_subs.iter().map(|s| SymbolRequest { symbol: s.clone() }).collect(),
-                        })
-                    }
+                let current_subs: Vec<String> = {
+                    let mut symbols: Vec<String> = subscriptions.read().await.keys().cloned().collect();
+                    symbols.sort();
+                    symbols
                 };
-                if let Some(msg) = subscription_msg {
-                    if let Ok(json) = serde_json::to_string(&msg) {
-                        let _ = write.send(Message::Text(json)).await;
-                    }
+                if let Some(msg) = subscription_message("subscribe", &current_subs) {
+                    let _ = write.send(msg).await;
                 }
 
+                let snapshot = spawn_snapshot(
+                    snapshot_client.clone(),
+                    current_subs,
+                    connect_timeout,
+                    event_tx.clone(),
+                    event_count.clone(),
+                );
+
+                let mut ping_timer = tokio::time::interval_at(tokio::time::Instant::now() + ping_interval, ping_interval);
+                let mut pong_deadline: Option<tokio::time::Instant> = None;
+                let mut stale_timer = tokio::time::interval_at(tokio::time::Instant::now() + stale_check, stale_check);
+                let mut reported_stale = HashSet::new();
+                // Pushed back only by data frames, so answered pings don't hide a silent feed
+                let mut read_deadline = tokio::time::Instant::now() + read_timeout;
+
                 loop {
                     tokio::select! {
+                        _ = stale_timer.tick(), if config.stale_threshold_ms > 0 => {
+                            let stale = {
+                                let subs = subscriptions.read().await;
+                                let prices = last_prices.read().await;
+                                stale_price_events(&subs, &prices, config.stale_threshold_ms, &mut reported_stale)
+                            };
+                            for event in stale {
+                                emit(&event_tx, &event_count, event);
+                            }
+                        }
+                        _ = ping_timer.tick() => {
+                            if write.send(Message::Ping(Vec::new())).await.is_ok() {
+                                pong_deadline.get_or_insert_with(|| tokio::time::Instant::now() + pong_timeout);
+                            }
+                        }
+                        _ = async {
+                            match pong_deadline {
+                                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                                None => std::future::pending().await,
+                            }
+                        } => {
+                            let message = format!("No pong received within {}ms", config.pong_timeout_ms);
+                            hooks.error(&message);
+                            last_error.clone_from(&message);
+                            emit(&event_tx, &event_count, SurgeEvent::Error(message));
+                            emit(&event_tx, &event_count, SurgeEvent::Disconnected);
+                            *is_connected.write().await = false;
+                            hooks.disconnected();
+                            break;
+                        }
+                        _ = tokio::time::sleep_until(read_deadline) => {
+                            let message = format!("No message received for {}ms", config.read_timeout_ms);
+                            hooks.error(&message);
+                            last_error.clone_from(&message);
+                            emit(&event_tx, &event_count, SurgeEvent::Error(message));
+                            emit(&event_tx, &event_count, SurgeEvent::Disconnected);
+                            *is_connected.write().await = false;
+                            hooks.disconnected();
+                            break;
+                        }
                         msg = read.next() => {
+                            if matches!(msg, Some(Ok(Message::Text(_) | Message::Binary(_)))) {
+                                read_deadline = tokio::time::Instant::now() + read_timeout;
+                            }
                             match msg {
                                 Some(Ok(Message::Text(text))) => {
-                                    if let Ok(update) = serde_json::from_str::<SurgeUpdate>(&text) {
-                                        let _ = event_tx.send(SurgeEvent::PriceUpdate(update));
+                                    stats.message_received();
+                                    if let Some(event) = parse_price_message(&text) {
+                                        hooks.price_updates(&event);
+                                        let received_ms = now_ms();
+                                        for update in event.updates() {
+                                            stats.lag.record(tracing_ext::latency_ms(update, received_ms) as f64);
+                                            metrics::counter!("surge_ws_price_updates_total", "symbol" => update.data.symbol.clone())
+                                                .increment(1);
+                                        }
+                                        cache_updates(&last_prices, &event).await;
+                                        emit(&event_tx, &event_count, event);
+                                    } else if let Some(ack) = parse_subscription_ack(&text) {
+                                        pending_acks.resolve(&ack);
+                                        if let Some(event) = ack.event() {
+                                            emit(&event_tx, &event_count, event);
+                                        }
                                     }
                                 }
+                                Some(Ok(Message::Pong(_))) => pong_deadline = None,
                                 Some(Ok(Message::Close(_))) | Some(Err(_)) | None => {
-                                    let _ = event_tx.send(SurgeEvent::Disconnected);
+                                    last_error = "Connection closed".to_string();
+                                    emit(&event_tx, &event_count, SurgeEvent::Disconnected);
                                     *is_connected.write().await = false;
+                                    hooks.disconnected();
                                     break;
                                 }
                                 _ => {}
                             }
                         }
-                        ctrl = control_rx.recv() => {
-                            if matches!(ctrl, Some(ControlMessage::Disconnect) | None) {
+                        ctrl = control_rx.recv() => match ctrl {
+                            Some(ControlMessage::Subscribe(symbols, reply)) => {
+                                if let Some(msg) = subscription_message("subscribe", &symbols) {
+                                    let _ = write.send(msg).await;
+                                }
+                                if let Some(reply) = reply {
+                                    pending_acks.push("subscribe", symbols, reply);
+                                }
+                            }
+                            Some(ControlMessage::Unsubscribe(symbols, reply)) => {
+                                if let Some(msg) = subscription_message("unsubscribe", &symbols) {
+                                    let _ = write.send(msg).await;
+                                }
+                                if let Some(reply) = reply {
+                                    pending_acks.push("unsubscribe", symbols, reply);
+                                }
+                            }
+                            Some(ControlMessage::Disconnect) | None => {
+                                snapshot.abort();
                                 let _ = write.send(Message::Close(None)).await;
                                 *is_connected.write().await = false;
+                                stats.set_connected(false);
+                                hooks.disconnected();
                                 return;
                             }
                         }
                     }
                 }
+
+                // Prices from the dropped connection are no longer current
+                snapshot.abort();
+                last_prices.write().await.clear();
+                stats.set_connected(false);
             }
-            Err(e) => {
-                let _ = event_tx.send(SurgeEvent::Error(format!("Connection failed: {}", e)));
+            Ok(Err(e)) => {
+                let message = format!("Connection failed: {}", e);
+                hooks.error(&message);
+                last_error.clone_from(&message);
+                emit(&event_tx, &event_count, SurgeEvent::Error(message));
+            }
+            Err(_) => {
+                let message = format!("Connection timed out after {}ms", config.connect_timeout_ms);
+                hooks.error(&message);
+                last_error.clone_from(&message);
+                emit(&event_tx, &event_count, SurgeEvent::Error(message));
             }
         }
 
         if !config.auto_reconnect || reconnect_attempts >= config.max_reconnect_attempts {
-            let _ = event_tx.send(SurgeEvent::Error("Max reconnection attempts reached".to_string()));
+            hooks.error("Max reconnection attempts reached");
+            emit(&event_tx, &event_count, SurgeEvent::ReconnectFailed { attempts: reconnect_attempts, reason: last_error });
             return;
         }
 
-        let _ = event_tx.send(SurgeEvent::Reconnecting { attempt: reconnect_attempts + 1, delay_ms: delay });
-        sleep(Duration::from_millis(delay)).await;
+        let wait = delay + jitter(backoff.max_jitter_ms(delay));
+        hooks.reconnect(reconnect_attempts + 1, wait);
+        stats.reconnects.fetch_add(1, Ordering::Relaxed);
+        emit(&event_tx, &event_count, SurgeEvent::Reconnecting { attempt: reconnect_attempts + 1, delay_ms: wait });
+        sleep(Duration::from_millis(wait)).await;
         reconnect_attempts += 1;
-        delay = (delay * 2).min(30000);
+        delay = backoff.next_delay(delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    type ServerWs = tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>;
+
+    /// Accept one WebSocket connection on a local port and hand it to `handler`,
+    /// returning the `ws://` URL to connect to
+    async fn spawn_ws_server<F, Fut>(handler: F) -> String
+    where
+        F: FnOnce(ServerWs) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            handler(tokio_tungstenite::accept_async(socket).await.unwrap()).await;
+        });
+        format!("ws://{}", addr)
+    }
+
+    /// Complete the handshake, then keep the connection open without reading
+    async fn hold_open(_ws: ServerWs) {
+        sleep(Duration::from_secs(5)).await;
+    }
+
+    #[test]
+    fn test_lifecycle_hooks_are_invoked() {
+        let connected = Arc::new(AtomicUsize::new(0));
+        let disconnected = Arc::new(AtomicUsize::new(0));
+        let errors = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let (c, d, e) = (connected.clone(), disconnected.clone(), errors.clone());
+        let surge = Surge::new("")
+            .on_connected(move || {
+                c.fetch_add(1, Ordering::SeqCst);
+            })
+            .on_disconnected(move || {
+                d.fetch_add(1, Ordering::SeqCst);
+            })
+            .on_error(move |msg| e.lock().unwrap().push(msg.to_string()));
+
+        surge.hooks.connected();
+        surge.hooks.connected();
+        surge.hooks.disconnected();
+        surge.hooks.error("boom");
+
+        assert_eq!(connected.load(Ordering::SeqCst), 2);
+        assert_eq!(disconnected.load(Ordering::SeqCst), 1);
+        assert_eq!(*errors.lock().unwrap(), vec!["boom".to_string()]);
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl crate::StreamObserver for RecordingObserver {
+        fn on_price_update(&self, update: &SurgeUpdate, _latency_ms: u64) {
+            self.events.lock().unwrap().push(format!("price {}", update.data.symbol));
+        }
+
+        fn on_reconnect(&self, attempt: u32, delay_ms: u64) {
+            self.events.lock().unwrap().push(format!("reconnect {} {}", attempt, delay_ms));
+        }
+
+        fn on_error(&self, message: &str) {
+            self.events.lock().unwrap().push(format!("error {}", message));
+        }
+    }
+
+    #[test]
+    fn test_observer_receives_stream_events() {
+        let observer = Arc::new(RecordingObserver::default());
+        let surge = Surge::new("").with_observer(observer.clone());
+
+        let batch = parse_price_message(&format!("[{},{}]", UPDATE_JSON, UPDATE_JSON)).unwrap();
+        surge.hooks.price_updates(&batch);
+        surge.hooks.price_updates(&SurgeEvent::Connected);
+        surge.hooks.reconnect(1, 1000);
+        surge.hooks.error("boom");
+
+        assert_eq!(
+            *observer.events.lock().unwrap(),
+            ["price BTC/USD", "price BTC/USD", "reconnect 1 1000", "error boom"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cache_updates_keeps_latest_per_symbol() {
+        let surge = Surge::new("");
+        let batch = parse_price_message(&format!("[{},{}]", UPDATE_JSON, UPDATE_JSON.replace("50000.0", "51000.0"))).unwrap();
+        cache_updates(&surge.last_prices, &batch).await;
+
+        let update = surge.last_price("BTC/USD").await.unwrap();
+        assert_eq!(update.data.price, 51000.0);
+        assert!(surge.last_prices.read().await["BTC/USD"].age_ms() < 1000);
+        assert!(surge.last_price("ETH/USD").await.is_none());
+
+        let snapshot = surge.last_prices().await;
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot["BTC/USD"].data.price, 51000.0);
+    }
+
+    #[test]
+    fn test_stale_symbols() {
+        let update: SurgeUpdate = serde_json::from_str(UPDATE_JSON).unwrap();
+        let watched = vec!["BTC/USD".to_string(), "ETH/USD".to_string(), "SOL/USD".to_string()];
+        let subscriptions: HashMap<String, usize> = [("BTC/USD".to_string(), 1), ("ETH/USD".to_string(), 1)].into();
+        let old = Instant::now() - Duration::from_secs(10);
+        let last_prices: HashMap<String, CachedPrice> =
+            [("BTC/USD".to_string(), CachedPrice { update, received_at: old })].into();
+        let threshold = Duration::from_secs(5);
+
+        // ETH has never updated but the watchdog only just started
+        assert_eq!(stale_symbols(&watched, &subscriptions, &last_prices, threshold, Duration::ZERO), ["BTC/USD"]);
+        // SOL is no longer subscribed, so it is never re-sent
+        assert_eq!(
+            stale_symbols(&watched, &subscriptions, &last_prices, threshold, Duration::from_secs(6)),
+            ["BTC/USD", "ETH/USD"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscription_diff() {
+        let surge = Surge::new("");
+        surge.subscribe(vec!["BTC/USD", "ETH/USD", "SOL/USD"]).await.unwrap();
+
+        let (to_add, to_remove) = surge.subscription_diff(&["SOL/USD", "DOGE/USD", "ADA/USD", "ADA/USD"]).await;
+        assert_eq!(to_add, ["ADA/USD", "DOGE/USD"]);
+        assert_eq!(to_remove, ["BTC/USD", "ETH/USD"]);
+    }
+
+    #[tokio::test]
+    async fn test_update_subscriptions_replaces_set() {
+        let surge = Surge::new("");
+        surge.subscribe(vec!["BTC/USD", "ETH/USD", "ETH/USD"]).await.unwrap();
+
+        surge.update_subscriptions(vec!["ETH/USD", "SOL/USD"]).await.unwrap();
+        assert_eq!(surge.get_subscriptions().await, ["ETH/USD", "SOL/USD"]);
+        // Kept symbols keep their reference count
+        assert_eq!(surge.ref_count.read().await["ETH/USD"], 2);
+
+        surge.update_subscriptions(vec![]).await.unwrap();
+        assert!(surge.get_subscriptions().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pipe_to_writes_updates_until_connection_ends() {
+        let mut surge = Surge::new("");
+        let (control_tx, control_rx) = mpsc::channel(1);
+        surge.control_tx = Some(control_tx);
+        let event_tx = surge.event_tx.clone();
+
+        let mut out = Vec::new();
+        let feed = async {
+            sleep(Duration::from_millis(20)).await;
+            event_tx.send(parse_price_message(&format!("[{},{}]", UPDATE_JSON, UPDATE_JSON)).unwrap()).unwrap();
+            event_tx.send(SurgeEvent::Connected).unwrap();
+            sleep(Duration::from_millis(20)).await;
+            drop(control_rx);
+        };
+        let (result, _) = tokio::join!(surge.pipe_to(&mut out, PipeFormat::JsonLines), feed);
+
+        result.unwrap();
+        let lines: Vec<SurgeUpdate> = out
+            .split(|&b| b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].data.symbol, "BTC/USD");
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_price_updates_filters_by_symbol() {
+        let surge = Surge::new("");
+        let mut btc = surge.subscribe_price_updates("btc");
+        let mut eth = surge.subscribe_price_updates("ETH/USD");
+
+        let btc_update = parse_price_message(UPDATE_JSON).unwrap().into_updates().remove(0);
+        let mut eth_update = btc_update.clone();
+        eth_update.data.symbol = "ETH/USD".to_string();
+        surge.event_tx.send(SurgeEvent::PriceBatch(vec![btc_update, eth_update.clone()])).unwrap();
+        surge.event_tx.send(SurgeEvent::Connected).unwrap();
+        surge.event_tx.send(SurgeEvent::PriceUpdate(eth_update)).unwrap();
+
+        let (btc_seen, eth_seen) = tokio::join!(
+            async {
+                let first = btc.recv().await.unwrap();
+                assert!(timeout(Duration::from_millis(50), btc.recv()).await.is_err());
+                vec![first]
+            },
+            async { vec![eth.recv().await.unwrap(), eth.recv().await.unwrap()] },
+        );
+        assert!(btc_seen.iter().all(|u| u.data.symbol == "BTC/USD"));
+        assert_eq!(eth_seen.len(), 2);
+        assert!(eth_seen.iter().all(|u| u.data.symbol == "ETH/USD"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_adaptive_requires_connection() {
+        let surge = Surge::new("");
+        assert!(surge.subscribe_adaptive(vec!["BTC/USD"], Duration::from_secs(1)).await.is_err());
+    }
+
+    #[test]
+    fn test_timeout_builders() {
+        let surge = Surge::new("").connect_timeout(1500).read_timeout(20000);
+        assert_eq!(surge.config.connect_timeout_ms, 1500);
+        assert_eq!(surge.config.read_timeout_ms, 20000);
+    }
+
+    #[test]
+    fn test_keepalive_builders() {
+        let surge = Surge::new("").ping_interval(30000).pong_timeout(5000);
+        assert_eq!(surge.config.ping_interval_ms, 30000);
+        assert_eq!(surge.config.pong_timeout_ms, 5000);
+    }
+
+    #[tokio::test]
+    async fn test_missing_pong_drops_connection() {
+        // Complete the handshake, then never read again so pings go unanswered
+        let url = spawn_ws_server(hold_open).await;
+
+        let mut surge = Surge::new("").ping_interval(100).pong_timeout(50);
+        surge.config.ws_url = url;
+        surge.config.auto_reconnect = false;
+        let mut events = surge.subscribe_events();
+        surge.connect_and_subscribe(vec!["BTC/USD"]).await.unwrap();
+
+        let error = timeout(Duration::from_secs(2), async {
+            loop {
+                if let Ok(SurgeEvent::Error(message)) = events.recv().await {
+                    return message;
+                }
+            }
+        })
+        .await
+        .expect("connection should be declared dead");
+        assert_eq!(error, "No pong received within 50ms");
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout_fires_while_pings_are_answered() {
+        let pings = Arc::new(AtomicUsize::new(0));
+        let seen = pings.clone();
+        // Reading lets tungstenite answer every ping, but no data frame is ever sent
+        let url = spawn_ws_server(|mut ws| async move {
+            while let Some(Ok(msg)) = ws.next().await {
+                if msg.is_ping() {
+                    seen.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        })
+        .await;
+
+        let mut surge = Surge::new("").ping_interval(50).pong_timeout(40).read_timeout(300);
+        surge.config.ws_url = url;
+        surge.config.auto_reconnect = false;
+        let mut events = surge.subscribe_events();
+        surge.connect_and_subscribe(vec!["BTC/USD"]).await.unwrap();
+
+        let error = timeout(Duration::from_secs(2), async {
+            loop {
+                if let Ok(SurgeEvent::Error(message)) = events.recv().await {
+                    return message;
+                }
+            }
+        })
+        .await
+        .expect("read timeout should fire");
+        assert_eq!(error, "No message received for 300ms");
+        assert!(pings.load(Ordering::SeqCst) >= 3, "pings should keep flowing");
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_follows_connected() {
+        let url = spawn_ws_server(hold_open).await;
+
+        let mut surge = Surge::new("");
+        surge.config.ws_url = url;
+        let mut events = surge.subscribe_events();
+        // Unknown to the feed registry, so the snapshot is empty without any network call
+        surge.connect_and_subscribe(vec!["NOTACOIN123/USD"]).await.unwrap();
+
+        let received = timeout(Duration::from_secs(2), async {
+            let mut received = Vec::new();
+            while received.len() < 2 {
+                received.push(events.recv().await.unwrap());
+            }
+            received
+        })
+        .await
+        .expect("should connect and snapshot");
+        assert!(matches!(received[0], SurgeEvent::Connected));
+        assert!(matches!(&received[1], SurgeEvent::Snapshot { prices } if prices.is_empty()));
+        surge.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_slow_snapshot_does_not_block_updates() {
+        // A proxy that accepts but never answers, so the snapshot fetch hangs
+        let proxy = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = SurgeClient::with_proxy(&format!("http://{}", proxy.local_addr().unwrap())).unwrap();
+        let url = spawn_ws_server(|mut ws| async move {
+            ws.send(Message::Text(UPDATE_JSON.to_string())).await.unwrap();
+            sleep(Duration::from_secs(5)).await;
+        })
+        .await;
+
+        let mut surge = Surge::new("").connect_timeout(3000).with_snapshot_client(Arc::new(client));
+        surge.config.ws_url = url;
+        let mut events = surge.subscribe_events();
+        surge.connect_and_subscribe(vec!["BTC/USD"]).await.unwrap();
+
+        let update = timeout(Duration::from_secs(1), async {
+            loop {
+                match events.recv().await.unwrap() {
+                    SurgeEvent::PriceUpdate(update) => return update,
+                    SurgeEvent::Snapshot { .. } => panic!("snapshot should still be pending"),
+                    _ => {}
+                }
+            }
+        })
+        .await
+        .expect("updates should flow while the snapshot is pending");
+        assert_eq!(update.data.symbol, "BTC/USD");
+        surge.disconnect().await.unwrap();
+        drop(proxy);
+    }
+
+    #[tokio::test]
+    async fn test_await_connected_resolves_on_connect() {
+        let url = spawn_ws_server(hold_open).await;
+
+        let mut surge = Surge::new("");
+        surge.config.ws_url = url;
+        surge.connect_and_subscribe(vec!["NOTACOIN123/USD"]).await.unwrap();
+
+        surge.await_connected(Duration::from_secs(2)).await.unwrap();
+        // Already connected: returns immediately
+        surge.await_connected(Duration::ZERO).await.unwrap();
+        surge.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_await_connected_times_out() {
+        // Accepts TCP but never completes the WebSocket handshake
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let mut surge = Surge::new("");
+        surge.config.ws_url = format!("ws://{}", addr);
+        surge.connect_and_subscribe(vec!["BTC/USD"]).await.unwrap();
+
+        let result = surge.await_connected(Duration::from_millis(100)).await;
+        assert!(matches!(result, Err(SurgeError::Timeout(_))), "got {:?}", result);
+        surge.disconnect().await.unwrap();
+        drop(listener);
+    }
+
+    #[tokio::test]
+    async fn test_await_connected_fails_on_error() {
+        // Nothing listens here, so every attempt is refused
+        let addr = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+
+        let mut surge = Surge::new("").with_backoff_config(BackoffConfig::constant(50));
+        surge.config.ws_url = format!("ws://{}", addr);
+        surge.connect_and_subscribe(vec!["BTC/USD"]).await.unwrap();
+
+        let result = surge.await_connected(Duration::from_secs(2)).await;
+        assert!(matches!(result, Err(SurgeError::ConnectionError(_))), "got {:?}", result);
+        surge.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_failed_after_max_attempts() {
+        let addr = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+
+        let mut surge = Surge::new("").with_backoff_config(BackoffConfig::constant(10));
+        surge.config.ws_url = format!("ws://{}", addr);
+        surge.config.max_reconnect_attempts = 2;
+        let mut events = surge.subscribe_events();
+        surge.connect_and_subscribe(vec!["BTC/USD"]).await.unwrap();
+
+        let (attempts, reason) = timeout(Duration::from_secs(2), async {
+            loop {
+                if let Ok(SurgeEvent::ReconnectFailed { attempts, reason }) = events.recv().await {
+                    return (attempts, reason);
+                }
+            }
+        })
+        .await
+        .expect("loop should give up");
+        assert_eq!(attempts, 2);
+        assert!(reason.starts_with("Connection failed"), "{reason}");
+        assert_eq!(surge.reconnect_count(), 2);
+    }
+
+    #[test]
+    fn test_parse_subscription_ack() {
+        let event = |text: &str| parse_subscription_ack(text).and_then(|ack| ack.event());
+        let confirmed = event(r#"{"type":"subscribed","symbols":["BTC/USD",{"symbol":"ETH/USD"}]}"#);
+        assert!(matches!(confirmed, Some(SurgeEvent::SubscriptionConfirmed { symbols }) if symbols == ["BTC/USD", "ETH/USD"]));
+
+        let failed = event(r#"{"type":"subscription_failed","symbols":["X/USD"],"message":"unknown symbol"}"#);
+        assert!(matches!(
+            failed,
+            Some(SurgeEvent::SubscriptionFailed { symbols, reason }) if symbols == ["X/USD"] && reason == "unknown symbol"
+        ));
+
+        let unsubscribed = parse_subscription_ack(r#"{"type":"unsubscribed","symbols":["BTC/USD"]}"#);
+        assert!(unsubscribed.is_some_and(|ack| ack.event().is_none()), "unsubscribe acks emit no event");
+        assert!(parse_subscription_ack(UPDATE_JSON).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_await_subscribed_sees_acknowledgment() {
+        let url = spawn_ws_server(|mut ws| async move {
+            // Echo the requested symbols back once the subscribe frame arrives
+            while let Some(Ok(Message::Text(text))) = ws.next().await {
+                let request: SubscriptionRequest = serde_json::from_str(&text).unwrap();
+                let ack = serde_json::json!({ "type": "subscribed", "symbols": request.symbols });
+                ws.send(Message::Text(ack.to_string())).await.unwrap();
+            }
+        })
+        .await;
+
+        let mut surge = Surge::new("");
+        surge.config.ws_url = url;
+        surge.connect_and_subscribe(vec!["NOTACOIN123/USD"]).await.unwrap();
+
+        let symbols = surge.await_subscribed(Duration::from_secs(2)).await.unwrap();
+        assert_eq!(symbols, ["NOTACOIN123/USD"]);
+        surge.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_and_unsubscribe_resolve_on_acknowledgment() {
+        let url = spawn_ws_server(|mut ws| async move {
+            while let Some(Ok(Message::Text(text))) = ws.next().await {
+                let request: SubscriptionRequest = serde_json::from_str(&text).unwrap();
+                let kind = if request.action == "subscribe" { "subscribed" } else { "unsubscribed" };
+                let ack = serde_json::json!({ "type": kind, "symbols": request.symbols });
+                ws.send(Message::Text(ack.to_string())).await.unwrap();
+            }
+        })
+        .await;
+
+        let mut surge = Surge::new("");
+        surge.config.ws_url = url;
+        surge.connect_and_subscribe(vec!["NOTACOIN123/USD"]).await.unwrap();
+
+        let added = surge.subscribe(vec!["NOTACOIN456/USD", "NOTACOIN789/USD"]).await.unwrap();
+        assert_eq!(added, ["NOTACOIN456/USD", "NOTACOIN789/USD"]);
+        let removed = surge.unsubscribe(vec!["NOTACOIN123/USD"]).await.unwrap();
+        assert_eq!(removed, ["NOTACOIN123/USD"]);
+        surge.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_times_out_without_acknowledgment() {
+        let url = spawn_ws_server(hold_open).await;
+
+        let mut surge = Surge::new("").connect_timeout(200);
+        surge.config.ws_url = url;
+        surge.connect_and_subscribe(vec![]).await.unwrap();
+
+        let result = surge.subscribe(vec!["NOTACOIN123/USD"]).await;
+        assert!(matches!(result, Err(SurgeError::Timeout(_))));
+        surge.disconnect().await.unwrap();
+    }
+
+    #[test]
+    fn test_pending_acks_partial_and_failed() {
+        let ack = |json: &str| serde_json::from_str::<SubscriptionAck>(json).unwrap();
+        let mut pending = PendingAcks::default();
+        let (both_tx, mut both_rx) = oneshot::channel();
+        let (bad_tx, mut bad_rx) = oneshot::channel();
+        pending.push("subscribe", vec!["BTC/USD".to_string(), "ETH/USD".to_string()], both_tx);
+        pending.push("subscribe", vec!["X/USD".to_string()], bad_tx);
+
+        pending.resolve(&ack(r#"{"type":"subscribed","symbols":["ETH/USD"]}"#));
+        assert!(both_rx.try_recv().is_err(), "BTC/USD is still unconfirmed");
+        pending.resolve(&ack(r#"{"type":"subscribed","symbols":["BTC/USD"]}"#));
+        assert_eq!(both_rx.try_recv().unwrap().unwrap(), ["ETH/USD", "BTC/USD"]);
+
+        pending.resolve(&ack(r#"{"type":"subscription_failed","symbols":["X/USD"],"reason":"unknown symbol"}"#));
+        assert!(matches!(bad_rx.try_recv().unwrap(), Err(SurgeError::ApiError(m)) if m.contains("unknown symbol")));
+    }
+
+    #[tokio::test]
+    async fn test_statistics_track_messages() {
+        let url = spawn_ws_server(|mut ws| async move {
+            ws.send(Message::Text(UPDATE_JSON.to_string())).await.unwrap();
+            sleep(Duration::from_secs(5)).await;
+        })
+        .await;
+
+        let mut surge = Surge::new("");
+        surge.config.ws_url = url;
+        assert_eq!(surge.statistics().await.last_message_at, None);
+        let mut events = surge.subscribe_events();
+        surge.connect_and_subscribe(vec!["NOTACOIN123/USD"]).await.unwrap();
+
+        timeout(Duration::from_secs(2), async {
+            while !matches!(events.recv().await, Ok(SurgeEvent::PriceUpdate(_))) {}
+        })
+        .await
+        .expect("should receive the update");
+
+        let stats = surge.statistics().await;
+        assert_eq!(stats.messages_received, 1);
+        assert!(stats.last_message_at.is_some());
+        assert_eq!(stats.reconnect_count, 0);
+        assert_eq!(stats.active_subscriptions, 1);
+        assert!(stats.avg_lag_ms.is_some_and(|lag| lag > 0.0), "UPDATE_JSON is timestamped in the past");
+        assert_eq!(surge.messages_received(), 1);
+        surge.disconnect().await.unwrap();
+    }
+
+    #[test]
+    fn test_lag_ewma() {
+        let lag = LagEwma::default();
+        assert_eq!(lag.get(), None);
+
+        lag.record(100.0);
+        assert_eq!(lag.get(), Some(100.0));
+        lag.record(400.0);
+        assert!((lag.get().unwrap() - (100.0 + 300.0 * 2.0 / 31.0)).abs() < 1e-9);
+
+        // Converges on a steady lag
+        (0..500).for_each(|_| lag.record(50.0));
+        assert!((lag.get().unwrap() - 50.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_connect_rejects_invalid_keepalive() {
+        let mut surge = Surge::new("").ping_interval(1000).pong_timeout(2000);
+        let result = surge.connect_and_subscribe(vec!["BTC/USD"]).await;
+        assert!(matches!(result, Err(SurgeError::InvalidConfig(_))));
+        assert!(surge.control_tx.is_none(), "no connection task should start");
+    }
+
+    fn cached_at(symbol: &str, age: Duration) -> CachedPrice {
+        let mut update = parse_price_message(UPDATE_JSON).unwrap().into_updates().remove(0);
+        update.data.symbol = symbol.to_string();
+        CachedPrice { update, received_at: Instant::now() - age }
+    }
+
+    #[test]
+    fn test_stale_price_events_reported_once() {
+        let subs: HashMap<String, usize> = [("BTC/USD", 1), ("ETH/USD", 1), ("SOL/USD", 1)]
+            .map(|(s, n)| (s.to_string(), n))
+            .into();
+        let mut prices: HashMap<String, CachedPrice> = HashMap::new();
+        prices.insert("BTC/USD".to_string(), cached_at("BTC/USD", Duration::from_secs(120)));
+        prices.insert("ETH/USD".to_string(), cached_at("ETH/USD", Duration::from_secs(1)));
+        let mut reported = HashSet::new();
+
+        let events = stale_price_events(&subs, &prices, 60_000, &mut reported);
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            SurgeEvent::StalePrice { symbol, last_update_ms, age_ms } => {
+                assert_eq!(symbol, "BTC/USD");
+                assert_eq!(*last_update_ms, prices["BTC/USD"].update.data.source_timestamp_ms);
+                assert!(*age_ms >= 120_000);
+            }
+            other => panic!("expected StalePrice, got {:?}", other),
+        }
+        assert!(stale_price_events(&subs, &prices, 60_000, &mut reported).is_empty());
+
+        // A fresh update re-arms the check
+        prices.insert("BTC/USD".to_string(), cached_at("BTC/USD", Duration::ZERO));
+        assert!(stale_price_events(&subs, &prices, 60_000, &mut reported).is_empty());
+        assert!(reported.is_empty());
+    }
+
+    #[test]
+    fn test_stale_price_events_skip_unsubscribed() {
+        let subs: HashMap<String, usize> = HashMap::new();
+        let mut prices = HashMap::new();
+        prices.insert("BTC/USD".to_string(), cached_at("BTC/USD", Duration::from_secs(120)));
+        assert!(stale_price_events(&subs, &prices, 60_000, &mut HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_stale_threshold_builder() {
+        assert_eq!(SurgeConfig::default().stale_threshold_ms, 60_000);
+        assert_eq!(Surge::new("").stale_threshold(0).config.stale_threshold_ms, 0);
+    }
+
+    // === Event counter tests ===
+
+    #[test]
+    fn test_event_count_increments_monotonically() {
+        let surge = Surge::new("");
+        let _rx = surge.subscribe_events();
+
+        let mut last = surge.event_count();
+        assert_eq!(last, 0);
+        for _ in 0..5 {
+            emit(&surge.event_tx, &surge.event_count, SurgeEvent::Connected);
+            let current = surge.event_count();
+            assert_eq!(current, last + 1);
+            last = current;
+        }
+    }
+
+    #[test]
+    fn test_event_count_skips_undelivered_events() {
+        let surge = Surge::new("");
+        emit(&surge.event_tx, &surge.event_count, SurgeEvent::Disconnected);
+        assert_eq!(surge.event_count(), 0);
+    }
+
+    #[test]
+    fn test_shared_event_counter() {
+        let counter = EventCounter::new();
+        let a = Surge::new("").with_event_counter(counter.clone());
+        let b = Surge::new("").with_event_counter(counter.clone());
+        let (_ra, _rb) = (a.subscribe_events(), b.subscribe_events());
+
+        emit(&a.event_tx, &a.event_count, SurgeEvent::Connected);
+        emit(&b.event_tx, &b.event_count, SurgeEvent::Connected);
+        assert_eq!(counter.get(), 2);
+    }
+
+    #[test]
+    fn test_events_per_second() {
+        let counter = EventCounter::new();
+        *counter.window.lock().unwrap() = (Instant::now() - Duration::from_secs(2), 0);
+        for _ in 0..10 {
+            counter.increment();
+        }
+        let rate = counter.events_per_second();
+        assert!((4.0..=5.0).contains(&rate), "rate was {}", rate);
+    }
+
+    #[test]
+    fn test_events_per_second_window_resets() {
+        let counter = EventCounter::new();
+        *counter.window.lock().unwrap() = (Instant::now() - THROUGHPUT_WINDOW, 0);
+        counter.increment();
+        assert!(counter.events_per_second() > 0.0);
+        assert_eq!(counter.window.lock().unwrap().1, 1);
+    }
+
+    // === Backoff tests ===
+
+    #[test]
+    fn test_with_exponential_backoff_config() {
+        let surge = Surge::new("key").with_exponential_backoff_config(500, 3.0, 10000);
+        assert_eq!(surge.config.backoff, BackoffConfig { initial_ms: 500, multiplier: 3.0, cap_ms: 10000, jitter_ms: 0, jitter_factor: 0.0 });
+    }
+
+    #[test]
+    fn test_jitter_stays_in_range() {
+        assert_eq!(jitter(0), 0);
+        assert!((0..100).all(|_| jitter(50) <= 50));
+    }
+
+    // === Message parsing tests ===
+
+    const UPDATE_JSON: &str = r#"{"type":"price","data":{"symbol":"BTC/USD","price":50000.0,"source_ts_ms":1}}"#;
+
+    #[test]
+    fn test_parse_single_update() {
+        let event = parse_price_message(UPDATE_JSON).unwrap();
+        assert!(matches!(event, SurgeEvent::PriceUpdate(ref u) if u.data.symbol == "BTC/USD"));
+    }
+
+    #[test]
+    fn test_parse_batch() {
+        let text = format!("[{}, {}]", UPDATE_JSON, UPDATE_JSON.replace("BTC", "ETH"));
+        let symbols: Vec<String> = parse_price_message(&text)
+            .unwrap()
+            .into_updates()
+            .into_iter()
+            .map(|u| u.data.symbol)
+            .collect();
+        assert_eq!(symbols, vec!["BTC/USD", "ETH/USD"]);
+    }
+
+    #[test]
+    fn test_parse_unrecognised_message() {
+        assert!(parse_price_message(r#"{"status":"ok"}"#).is_none());
+        assert!(parse_price_message("[1, 2]").is_none());
+    }
+
+    // === Subscription reference counting tests ===
+
+    #[tokio::test]
+    async fn test_shared_subscription_survives_one_unsubscribe() {
+        let surge = Surge::new("");
+        surge.subscribe(vec!["BTC/USD"]).await.unwrap();
+        surge.subscribe(vec!["BTC/USD", "ETH/USD"]).await.unwrap();
+
+        surge.unsubscribe(vec!["BTC/USD"]).await.unwrap();
+        assert_eq!(surge.get_subscriptions().await, vec!["BTC/USD", "ETH/USD"]);
+
+        surge.unsubscribe(vec!["BTC/USD"]).await.unwrap();
+        assert_eq!(surge.get_subscriptions().await, vec!["ETH/USD"]);
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_unknown_symbol_is_noop() {
+        let surge = Surge::new("");
+        surge.subscribe(vec!["SOL/USD"]).await.unwrap();
+        surge.unsubscribe(vec!["DOGE/USD"]).await.unwrap();
+        assert_eq!(surge.get_subscriptions().await, vec!["SOL/USD"]);
+    }
+
+    #[tokio::test]
+    async fn test_force_unsubscribe_ignores_ref_count() {
+        let surge = Surge::new("");
+        surge.subscribe(vec!["BTC/USD"]).await.unwrap();
+        surge.subscribe(vec!["BTC/USD"]).await.unwrap();
+
+        surge.force_unsubscribe(vec!["BTC/USD"]).await.unwrap();
+        assert!(surge.get_subscriptions().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_only_first_subscribe_and_last_unsubscribe_reach_upstream() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let mut surge = Surge::new("");
+        surge.control_tx = Some(tx);
+
+        // Stand in for the connection loop, acknowledging every request
+        let upstream = tokio::spawn(async move {
+            let mut sent = Vec::new();
+            while let Some(msg) = rx.recv().await {
+                let (action, symbols, reply) = match msg {
+                    ControlMessage::Subscribe(s, reply) => ("subscribe", s, reply),
+                    ControlMessage::Unsubscribe(s, reply) => ("unsubscribe", s, reply),
+                    ControlMessage::Disconnect => continue,
+                };
+                if let Some(reply) = reply {
+                    let _ = reply.send(Ok(symbols.clone()));
+                }
+                sent.push((action, symbols));
+            }
+            sent
+        });
+
+        let btc = vec!["BTC/USD".to_string()];
+        assert_eq!(surge.subscribe(vec!["BTC/USD"]).await.unwrap(), btc);
+        assert!(surge.subscribe(vec!["BTC/USD"]).await.unwrap().is_empty());
+        assert!(surge.unsubscribe(vec!["BTC/USD"]).await.unwrap().is_empty());
+        assert_eq!(surge.unsubscribe(vec!["BTC/USD"]).await.unwrap(), btc);
+        drop(surge);
+
+        let sent = upstream.await.unwrap();
+        assert_eq!(sent, vec![("subscribe", btc.clone()), ("unsubscribe", btc)]);
+    }
+
+    #[test]
+    fn test_subscription_message() {
+        assert!(subscription_message("subscribe", &[]).is_none());
+        let Some(Message::Text(json)) = subscription_message("unsubscribe", &["BTC/USD".to_string()]) else {
+            panic!("expected text frame");
+        };
+        assert_eq!(json, r#"{"action":"unsubscribe","symbols":[{"symbol":"BTC/USD"}]}"#);
+    }
+
+    #[test]
+    fn test_multiple_hooks_per_event() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let (a, b) = (count.clone(), count.clone());
+        let surge = Surge::new("")
+            .on_connected(move || {
+                a.fetch_add(1, Ordering::SeqCst);
+            })
+            .on_connected(move || {
+                b.fetch_add(10, Ordering::SeqCst);
+            });
+
+        surge.hooks.connected();
+        assert_eq!(count.load(Ordering::SeqCst), 11);
     }
 }
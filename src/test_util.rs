@@ -0,0 +1,39 @@
+//! Helpers shared by unit tests
+
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Serve one raw HTTP response on a local port, returning its `http://host:port` URL
+pub(crate) async fn serve_once(response: impl Into<String>) -> String {
+    let response = response.into();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        respond(socket, &response, Duration::ZERO).await;
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    });
+    url
+}
+
+/// Like [`serve_once`], but answers every connection, each after `delay`
+pub(crate) async fn serve_delayed(response: impl Into<String>, delay: Duration) -> String {
+    let response: std::sync::Arc<str> = response.into().into();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            let response = response.clone();
+            tokio::spawn(async move { respond(socket, &response, delay).await });
+        }
+    });
+    url
+}
+
+async fn respond(mut socket: TcpStream, response: &str, delay: Duration) {
+    let mut buf = [0u8; 1024];
+    let _ = socket.read(&mut buf).await;
+    tokio::time::sleep(delay).await;
+    let _ = socket.write_all(response.as_bytes()).await;
+}
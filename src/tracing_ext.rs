@@ -0,0 +1,77 @@
+//! Structured hooks into the streaming connection for observability pipelines
+
+use crate::types::SurgeUpdate;
+
+/// Receives streaming events as they happen in the connection loop.
+///
+/// Every method defaults to a no-op, so implementors only override what they forward.
+pub trait StreamObserver {
+    /// A price update arrived; `latency_ms` is local receive time minus the source timestamp
+    fn on_price_update(&self, _update: &SurgeUpdate, _latency_ms: u64) {}
+
+    /// The client is about to wait `delay_ms` before reconnection attempt `attempt`
+    fn on_reconnect(&self, _attempt: u32, _delay_ms: u64) {}
+
+    /// The client reported an error
+    fn on_error(&self, _message: &str) {}
+}
+
+/// Shared observer handle accepted by [`crate::Surge::with_observer`]
+pub type SurgeTracer = std::sync::Arc<dyn StreamObserver + Send + Sync>;
+
+/// Example observer that forwards events to `tracing`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoggingObserver;
+
+impl StreamObserver for LoggingObserver {
+    fn on_price_update(&self, update: &SurgeUpdate, latency_ms: u64) {
+        tracing::debug!(symbol = %update.data.symbol, price = update.data.price, latency_ms, "price update");
+    }
+
+    fn on_reconnect(&self, attempt: u32, delay_ms: u64) {
+        tracing::info!(attempt, delay_ms, "reconnecting");
+    }
+
+    fn on_error(&self, message: &str) {
+        tracing::warn!(error = message, "stream error");
+    }
+}
+
+/// Milliseconds between the source timestamp and `now_ms`, zero if the source is ahead
+pub(crate) fn latency_ms(update: &SurgeUpdate, now_ms: i64) -> u64 {
+    now_ms.saturating_sub(update.data.source_timestamp_ms).max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SurgeUpdateData;
+
+    fn update(source_timestamp_ms: i64) -> SurgeUpdate {
+        SurgeUpdate {
+            event_type: None,
+            data: SurgeUpdateData {
+                symbol: "BTC/USD".to_string(),
+                price: 50000.0,
+                source_timestamp_ms,
+                feed_id: None,
+                signature: None,
+                conf: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_latency_ms() {
+        assert_eq!(latency_ms(&update(1_000), 1_250), 250);
+        assert_eq!(latency_ms(&update(2_000), 1_000), 0);
+    }
+
+    #[test]
+    fn test_logging_observer_accepts_all_events() {
+        let observer: SurgeTracer = std::sync::Arc::new(LoggingObserver);
+        observer.on_price_update(&update(0), 5);
+        observer.on_reconnect(1, 1000);
+        observer.on_error("boom");
+    }
+}
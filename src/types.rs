@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Price data from a feed
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -6,6 +7,64 @@ pub struct FeedPrice {
     pub symbol: String,
     pub feed_id: String,
     pub value: f64,
+    /// Unix ms when the source observed the price, if it reported one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+}
+
+/// Exclusive upper bound accepted by [`FeedPrice::default_sanity_validator`]
+const MAX_SANE_PRICE: f64 = 1e12;
+
+impl FeedPrice {
+    /// Validator for [`crate::SurgeClient::with_response_validator`] accepting
+    /// `0 < value < 1e12`
+    pub fn default_sanity_validator() -> impl Fn(&FeedPrice) -> crate::Result<()> + Send + Sync + 'static {
+        |price: &FeedPrice| {
+            if price.value > 0.0 && price.value < MAX_SANE_PRICE {
+                Ok(())
+            } else {
+                Err(crate::SurgeError::ApiError(format!(
+                    "{} price {} outside (0, {:e})",
+                    price.symbol, price.value, MAX_SANE_PRICE
+                )))
+            }
+        }
+    }
+
+    /// Time since `timestamp`, zero if it lies in the future; `None` without one
+    pub fn age(&self) -> Option<Duration> {
+        self.age_at(now_ms())
+    }
+
+    fn age_at(&self, now_ms: i64) -> Option<Duration> {
+        self.timestamp.map(|ts| Duration::from_millis(now_ms.saturating_sub(ts).max(0) as u64))
+    }
+
+    /// Whether the price is older than `max_age`; prices without a timestamp count as fresh
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.age().is_some_and(|age| age > max_age)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl FeedPrice {
+    /// Build a price from a `Decimal`; `value` holds its nearest `f64`
+    pub fn new_decimal(symbol: impl Into<String>, feed_id: impl Into<String>, value: rust_decimal::Decimal) -> Self {
+        use rust_decimal::prelude::ToPrimitive;
+        Self {
+            symbol: symbol.into(),
+            feed_id: feed_id.into(),
+            value: value.to_f64().unwrap_or_default(),
+            timestamp: None,
+        }
+    }
+
+    /// `value` as a `Decimal` (rounded to 15 significant digits, so prices
+    /// built with [`FeedPrice::new_decimal`] round-trip); zero if not finite
+    pub fn value_decimal(&self) -> rust_decimal::Decimal {
+        use rust_decimal::prelude::FromPrimitive;
+        rust_decimal::Decimal::from_f64(self.value).unwrap_or_default()
+    }
 }
 
 impl std::fmt::Display for FeedPrice {
@@ -14,18 +73,106 @@ impl std::fmt::Display for FeedPrice {
     }
 }
 
-/// Configuration for Surge streaming client
-#[derive(Debug, Clone)]
+/// A [`FeedPrice`] with the spread of the oracle samples behind it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedPriceWithConfidence {
+    pub price: FeedPrice,
+    /// Population standard deviation of the samples; 0 for a single sample
+    pub confidence: f64,
+    /// `confidence` as a percentage of the price; 0 when the price is 0
+    pub confidence_pct: f64,
+}
+
+impl FeedPriceWithConfidence {
+    /// Derive the confidence from the spread of `samples`
+    pub fn from_samples(price: FeedPrice, samples: &[f64]) -> Self {
+        let confidence = std_deviation(samples);
+        let confidence_pct = if price.value == 0.0 { 0.0 } else { confidence / price.value.abs() * 100.0 };
+        Self { price, confidence, confidence_pct }
+    }
+}
+
+/// Population standard deviation; 0 for fewer than two values
+fn std_deviation(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt()
+}
+
+/// Base assets listed first, roughly in market-cap order
+pub const PRIORITY_BASES: &[&str] = &[
+    "BTC", "ETH", "USDT", "SOL", "BNB", "XRP", "USDC", "DOGE", "ADA", "TRX", "AVAX", "LINK", "TON", "DOT", "MATIC", "LTC",
+];
+
+/// Result of [`crate::SurgeClient::health_check`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthStatus {
+    /// The feed registry has at least one symbol
+    pub feed_loader_ok: bool,
+    /// The price gateway answered within 2 seconds
+    pub gateway_reachable: bool,
+    /// Round trip for a BTC/USD price fetch, if it succeeded
+    pub latency_ms: Option<u64>,
+    pub symbol_count: usize,
+    /// First failure encountered, if any
+    pub error: Option<String>,
+}
+
+/// Hit/miss counters for [`crate::SurgeClient::cached_get_price`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Symbols currently held, fresh or expired
+    pub entries: usize,
+}
+
+/// Configuration for Surge streaming client.
+///
+/// Serializes (and displays) with `api_key` redacted; see [`SurgeConfig::full_json`].
+#[derive(Debug, Clone, Serialize)]
 pub struct SurgeConfig {
+    #[serde(serialize_with = "redact_api_key")]
     pub api_key: String,
     pub ws_url: String,
     pub api_url: String,
     pub auto_reconnect: bool,
     pub max_reconnect_attempts: u32,
+    /// First reconnect delay. When changed from its default of 1000 it
+    /// overrides `backoff.initial_ms`.
+    #[deprecated(note = "use `backoff.initial_ms`")]
+    #[serde(skip)]
     pub initial_reconnect_delay_ms: u64,
+    /// Delay schedule between reconnection attempts
+    pub backoff: BackoffConfig,
+    /// Hosts that bypass the HTTP proxy (defaults to `NO_PROXY` when unset)
+    pub no_proxy: Option<Vec<String>>,
+    /// Max time for the WebSocket handshake before the attempt counts as failed
+    pub connect_timeout_ms: u64,
+    /// Max time an open connection may go without a data (text or binary) frame
+    /// before it is treated as stale and reconnected. Pings and pongs don't
+    /// count, so a socket that only answers keepalives still times out.
+    pub read_timeout_ms: u64,
+    /// How often to send a WebSocket ping
+    pub ping_interval_ms: u64,
+    /// How long to wait for the pong before declaring the connection dead.
+    /// Must be shorter than `ping_interval_ms`.
+    pub pong_timeout_ms: u64,
+    /// Age after which a subscribed symbol's last update is reported as
+    /// [`SurgeEvent::StalePrice`]; 0 disables the check
+    pub stale_threshold_ms: u64,
+    /// Name reported to tracing backends (the OTLP `service.name` resource)
+    pub service_name: String,
 }
 
+/// Default first reconnect delay
+const DEFAULT_RECONNECT_DELAY_MS: u64 = 1000;
+
 impl Default for SurgeConfig {
+    #[allow(deprecated)]
     fn default() -> Self {
         Self {
             api_key: String::new(),
@@ -33,7 +180,215 @@ impl Default for SurgeConfig {
             api_url: "https://surge.switchboard.xyz".to_string(),
             auto_reconnect: true,
             max_reconnect_attempts: 10,
-            initial_reconnect_delay_ms: 1000,
+            initial_reconnect_delay_ms: DEFAULT_RECONNECT_DELAY_MS,
+            backoff: BackoffConfig::default(),
+            no_proxy: None,
+            connect_timeout_ms: 5000,
+            read_timeout_ms: 60000,
+            ping_interval_ms: 15000,
+            pong_timeout_ms: 10000,
+            stale_threshold_ms: 60000,
+            service_name: "i-am-surging".to_string(),
+        }
+    }
+}
+
+const REDACTED: &str = "***REDACTED***";
+
+fn redact_api_key<S: serde::Serializer>(_key: &str, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(REDACTED)
+}
+
+impl std::fmt::Display for SurgeConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| std::fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+impl SurgeConfig {
+    /// Start from the defaults; `api_key` must be set before [`SurgeConfigBuilder::build`]
+    pub fn builder() -> SurgeConfigBuilder {
+        SurgeConfigBuilder::default()
+    }
+
+    /// JSON including the real `api_key`; never log this
+    pub fn full_json(&self) -> serde_json::Value {
+        let mut json = serde_json::to_value(self).unwrap_or_default();
+        json["api_key"] = serde_json::Value::String(self.api_key.clone());
+        json
+    }
+
+    /// `backoff` with the deprecated `initial_reconnect_delay_ms` applied
+    #[allow(deprecated)]
+    pub fn reconnect_backoff(&self) -> BackoffConfig {
+        let mut backoff = self.backoff;
+        if self.initial_reconnect_delay_ms != DEFAULT_RECONNECT_DELAY_MS {
+            backoff.initial_ms = self.initial_reconnect_delay_ms;
+        }
+        backoff
+    }
+
+    /// Check settings that would otherwise cause spurious disconnects
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.pong_timeout_ms >= self.ping_interval_ms {
+            return Err(crate::SurgeError::InvalidConfig(format!(
+                "pong_timeout_ms ({}) must be less than ping_interval_ms ({})",
+                self.pong_timeout_ms, self.ping_interval_ms
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.backoff.jitter_factor) {
+            return Err(crate::SurgeError::InvalidConfig(format!(
+                "backoff.jitter_factor ({}) must be between 0.0 and 1.0",
+                self.backoff.jitter_factor
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Shortest `initial_reconnect_delay_ms` [`SurgeConfigBuilder::build`] accepts
+const MIN_RECONNECT_DELAY_MS: u64 = 100;
+
+/// Validated construction of a [`SurgeConfig`]; start from [`SurgeConfig::builder`]
+#[derive(Debug, Clone, Default)]
+pub struct SurgeConfigBuilder {
+    config: SurgeConfig,
+}
+
+impl SurgeConfigBuilder {
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.config.api_key = api_key.into();
+        self
+    }
+
+    pub fn ws_url(mut self, ws_url: impl Into<String>) -> Self {
+        self.config.ws_url = ws_url.into();
+        self
+    }
+
+    pub fn api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.config.api_url = api_url.into();
+        self
+    }
+
+    pub fn auto_reconnect(mut self, auto_reconnect: bool) -> Self {
+        self.config.auto_reconnect = auto_reconnect;
+        self
+    }
+
+    pub fn max_reconnect_attempts(mut self, attempts: u32) -> Self {
+        self.config.max_reconnect_attempts = attempts;
+        self
+    }
+
+    /// First reconnect delay; sets `backoff.initial_ms`
+    pub fn initial_reconnect_delay_ms(mut self, delay_ms: u64) -> Self {
+        self.config.backoff.initial_ms = delay_ms;
+        self
+    }
+
+    pub fn service_name(mut self, service_name: impl Into<String>) -> Self {
+        self.config.service_name = service_name.into();
+        self
+    }
+
+    /// Check the settings and return the config
+    pub fn build(self) -> crate::Result<SurgeConfig> {
+        let config = self.config;
+        let invalid = |msg: String| Err(crate::SurgeError::InvalidConfig(msg));
+
+        if config.api_key.trim().is_empty() {
+            return invalid("api_key must not be empty".to_string());
+        }
+        for (name, value) in [("ws_url", &config.ws_url), ("api_url", &config.api_url)] {
+            if let Err(e) = url::Url::parse(value) {
+                return invalid(format!("{} {:?} is not a valid URL: {}", name, value, e));
+            }
+        }
+        if config.max_reconnect_attempts == 0 {
+            return invalid("max_reconnect_attempts must be greater than 0".to_string());
+        }
+        if config.backoff.initial_ms < MIN_RECONNECT_DELAY_MS {
+            return invalid(format!(
+                "initial_reconnect_delay_ms ({}) must be at least {}",
+                config.backoff.initial_ms, MIN_RECONNECT_DELAY_MS
+            ));
+        }
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// Reconnection delay schedule: each delay is the previous one times
+/// `multiplier`, capped at `cap_ms`, plus up to `jitter_ms` and up to
+/// `jitter_factor` of the delay of random slack
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BackoffConfig {
+    pub initial_ms: u64,
+    /// Growth factor per attempt; below 1.0 the delay shrinks towards zero
+    pub multiplier: f64,
+    pub cap_ms: u64,
+    pub jitter_ms: u64,
+    /// Extra random delay as a fraction of the computed one (0.0–1.0), so
+    /// clients dropped together don't all reconnect at the same moment
+    pub jitter_factor: f64,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        Self {
+            initial_ms: DEFAULT_RECONNECT_DELAY_MS,
+            multiplier: 2.0,
+            cap_ms: 30000,
+            jitter_ms: 0,
+            jitter_factor: 0.2,
+        }
+    }
+}
+
+impl BackoffConfig {
+    /// Retry after the same fixed delay every time
+    pub fn constant(delay_ms: u64) -> Self {
+        Self {
+            initial_ms: delay_ms,
+            multiplier: 1.0,
+            cap_ms: delay_ms,
+            jitter_ms: 0,
+            jitter_factor: 0.0,
+        }
+    }
+
+    /// Delay to use after `current_ms`, before jitter
+    pub fn next_delay(&self, current_ms: u64) -> u64 {
+        ((current_ms as f64 * self.multiplier) as u64).min(self.cap_ms)
+    }
+
+    /// Most random slack that may be added to `delay_ms`
+    pub fn max_jitter_ms(&self, delay_ms: u64) -> u64 {
+        self.jitter_ms + (delay_ms as f64 * self.jitter_factor) as u64
+    }
+}
+
+/// When [`crate::SurgeClient::get_price_with_retries`] tries again: up to
+/// `max_attempts` calls in total, waiting `initial_delay_ms` and then
+/// `backoff_factor` times longer after each failure that `retryable` accepts
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay_ms: u64,
+    pub backoff_factor: f64,
+    pub retryable: fn(&crate::SurgeError) -> bool,
+}
+
+impl Default for RetryPolicy {
+    /// 3 attempts, 500ms then 1s apart, retrying [`crate::SurgeError::is_retryable`] errors
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay_ms: 500,
+            backoff_factor: 2.0,
+            retryable: crate::SurgeError::is_retryable,
         }
     }
 }
@@ -46,6 +401,27 @@ pub struct SurgeUpdate {
     pub data: SurgeUpdateData,
 }
 
+impl From<FeedPrice> for SurgeUpdate {
+    /// Wrap a REST price as an update stamped with the current time
+    fn from(price: FeedPrice) -> Self {
+        let now_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or_default();
+        Self {
+            event_type: Some("snapshot".to_string()),
+            data: SurgeUpdateData {
+                symbol: price.symbol,
+                price: price.value,
+                source_timestamp_ms: now_ms,
+                feed_id: Some(price.feed_id),
+                signature: None,
+                conf: None,
+            },
+        }
+    }
+}
+
 /// Price update data payload
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SurgeUpdateData {
@@ -57,6 +433,132 @@ pub struct SurgeUpdateData {
     pub feed_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signature: Option<String>,
+    /// Confidence interval around `price`, when the feed reports one
+    /// (as `conf` or `confidence`)
+    #[serde(default, alias = "confidence", skip_serializing_if = "Option::is_none")]
+    pub conf: Option<f64>,
+}
+
+#[cfg(feature = "decimal")]
+impl SurgeUpdateData {
+    /// `price` as a `Decimal`; zero if not finite
+    pub fn price_decimal(&self) -> rust_decimal::Decimal {
+        use rust_decimal::prelude::FromPrimitive;
+        rust_decimal::Decimal::from_f64(self.price).unwrap_or_default()
+    }
+}
+
+const SATS_PER_BTC: f64 = 100_000_000.0;
+const MICRO_LAMPORTS_PER_SOL: f64 = 1_000_000_000_000_000.0;
+const WEI_PER_ETH: f64 = 1_000_000_000_000_000_000.0;
+
+impl SurgeUpdateData {
+    /// Price expressed in satoshis, treating `price` as a BTC amount.
+    /// Rounds to the nearest satoshi; negative prices clamp to 0.
+    pub fn price_in_sats(&self) -> u64 {
+        (self.price * SATS_PER_BTC).round() as u64
+    }
+
+    /// USD price converted into micro-lamports at the given SOL/USD rate
+    pub fn price_in_micro_lamports(&self, sol_price_usd: f64) -> u64 {
+        if sol_price_usd <= 0.0 {
+            return 0;
+        }
+        (self.price / sol_price_usd * MICRO_LAMPORTS_PER_SOL).round() as u64
+    }
+
+    /// USD price converted into wei at the given ETH/USD rate
+    pub fn price_in_wei(&self, eth_price_usd: f64) -> u128 {
+        if eth_price_usd <= 0.0 {
+            return 0;
+        }
+        (self.price / eth_price_usd * WEI_PER_ETH).round() as u128
+    }
+}
+
+/// Pyth fixed-point exponent used for converted prices
+#[cfg(feature = "pyth-compat")]
+const PYTH_EXPO: i32 = -8;
+
+/// Confidence used when the feed reports none: 0.1% of the price
+#[cfg(feature = "pyth-compat")]
+const DEFAULT_CONF_RATIO: f64 = 0.001;
+
+/// Price in the Pyth Network attestation layout
+#[cfg(feature = "pyth-compat")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PythPriceAttestation {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    /// Unix seconds
+    pub publish_time: i64,
+}
+
+#[cfg(feature = "pyth-compat")]
+impl PythPriceAttestation {
+    /// `price` scaled back by `expo`
+    pub fn price_f64(&self) -> f64 {
+        self.price as f64 * 10f64.powi(self.expo)
+    }
+
+    /// `conf` scaled back by `expo`
+    pub fn conf_f64(&self) -> f64 {
+        self.conf as f64 * 10f64.powi(self.expo)
+    }
+}
+
+#[cfg(feature = "pyth-compat")]
+impl SurgeUpdate {
+    /// Convert to a Pyth attestation with exponent -8
+    pub fn to_pyth_price_attestation(&self) -> PythPriceAttestation {
+        let scale = 10f64.powi(-PYTH_EXPO);
+        let conf = self.data.conf.unwrap_or(self.data.price.abs() * DEFAULT_CONF_RATIO);
+        PythPriceAttestation {
+            price: (self.data.price * scale).round() as i64,
+            conf: (conf * scale).round() as u64,
+            expo: PYTH_EXPO,
+            publish_time: self.data.source_timestamp_ms / 1000,
+        }
+    }
+}
+
+#[cfg(feature = "solana")]
+impl SurgeUpdate {
+    /// Message the oracle signs: `sha256(symbol || price (f64 LE) || source_ts_ms (i64 LE))`
+    fn signed_message(&self) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
+        hasher.update(self.data.symbol.as_bytes());
+        hasher.update(self.data.price.to_le_bytes());
+        hasher.update(self.data.source_timestamp_ms.to_le_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Check `data.signature` (hex ed25519) against a base58 oracle public key.
+    ///
+    /// `Ok(false)` when unsigned or the signature does not match; malformed
+    /// keys or signatures are errors.
+    pub fn verify_signature(&self, oracle_pubkey: &str) -> crate::Result<bool> {
+        use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+        let invalid = |msg: String| crate::SurgeError::ApiError(msg);
+
+        let Some(signature) = &self.data.signature else {
+            return Ok(false);
+        };
+        let key_bytes: [u8; 32] = bs58::decode(oracle_pubkey)
+            .into_vec()
+            .map_err(|e| invalid(format!("invalid oracle pubkey: {}", e)))?
+            .try_into()
+            .map_err(|_| invalid("oracle pubkey must be 32 bytes".to_string()))?;
+        let key = VerifyingKey::from_bytes(&key_bytes).map_err(|e| invalid(format!("invalid oracle pubkey: {}", e)))?;
+        let sig_bytes: [u8; 64] = hex::decode(signature)
+            .map_err(|e| invalid(format!("invalid signature: {}", e)))?
+            .try_into()
+            .map_err(|_| invalid("signature must be 64 bytes".to_string()))?;
+
+        Ok(key.verify(&self.signed_message(), &Signature::from_bytes(&sig_bytes)).is_ok())
+    }
 }
 
 /// Events emitted by the Surge streaming client
@@ -65,8 +567,49 @@ pub enum SurgeEvent {
     Connected,
     Disconnected,
     PriceUpdate(SurgeUpdate),
+    /// Several updates delivered in one WebSocket message
+    PriceBatch(Vec<SurgeUpdate>),
     Error(String),
     Reconnecting { attempt: u32, delay_ms: u64 },
+    /// The connection loop gave up: reconnection is disabled or
+    /// `max_reconnect_attempts` ran out. No further events follow.
+    ReconnectFailed { attempts: u32, reason: String },
+    /// Current prices of all subscriptions, emitted right after `Connected`;
+    /// empty if they could not be fetched
+    Snapshot { prices: Vec<SurgeUpdate> },
+    /// A subscribed symbol has had no update for `stale_threshold_ms`.
+    /// Emitted once until the symbol updates again.
+    StalePrice {
+        symbol: String,
+        /// Source timestamp of the last update
+        last_update_ms: i64,
+        /// Time since the last update was received
+        age_ms: u64,
+    },
+    /// The server acknowledged a subscribe request for `symbols`
+    SubscriptionConfirmed { symbols: Vec<String> },
+    /// The server rejected a subscribe request for `symbols`
+    SubscriptionFailed { symbols: Vec<String>, reason: String },
+}
+
+impl SurgeEvent {
+    /// Borrowing form of [`SurgeEvent::into_updates`]
+    pub fn updates(&self) -> &[SurgeUpdate] {
+        match self {
+            SurgeEvent::PriceUpdate(update) => std::slice::from_ref(update),
+            SurgeEvent::PriceBatch(updates) | SurgeEvent::Snapshot { prices: updates } => updates,
+            _ => &[],
+        }
+    }
+
+    /// Price updates carried by this event; empty for non-price events
+    pub fn into_updates(self) -> Vec<SurgeUpdate> {
+        match self {
+            SurgeEvent::PriceUpdate(update) => vec![update],
+            SurgeEvent::PriceBatch(updates) | SurgeEvent::Snapshot { prices: updates } => updates,
+            _ => Vec::new(),
+        }
+    }
 }
 
 /// Request to subscribe/unsubscribe to symbols
@@ -90,6 +633,14 @@ pub struct SurgeFeedInfo {
     pub feed_id: Option<String>,
 }
 
+/// Current unix time in milliseconds
+pub(crate) fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,6 +653,7 @@ mod tests {
             symbol: "BTC/USD".to_string(),
             feed_id: "abc123".to_string(),
             value: 50000.123456,
+            timestamp: None,
         };
         assert_eq!(format!("{}", price), "BTC/USD: $50000.123456");
     }
@@ -112,6 +664,7 @@ mod tests {
             symbol: "SHIB/USD".to_string(),
             feed_id: "abc123".to_string(),
             value: 0.000012,
+            timestamp: None,
         };
         assert_eq!(format!("{}", price), "SHIB/USD: $0.000012");
     }
@@ -122,6 +675,7 @@ mod tests {
             symbol: "ETH/USD".to_string(),
             feed_id: "def456".to_string(),
             value: 3000.50,
+            timestamp: None,
         };
         let json = serde_json::to_string(&price).unwrap();
         assert!(json.contains("\"symbol\":\"ETH/USD\""));
@@ -144,15 +698,65 @@ mod tests {
             symbol: "BTC/USD".to_string(),
             feed_id: "abc".to_string(),
             value: 50000.0,
+            timestamp: None,
         };
         let cloned = price.clone();
         assert_eq!(price.symbol, cloned.symbol);
         assert_eq!(price.value, cloned.value);
     }
 
+    #[test]
+    fn test_feed_price_timestamp_round_trip() {
+        let json = r#"{"symbol":"SOL/USD","feed_id":"xyz789","value":150.25,"timestamp":1705936800000}"#;
+        let price: FeedPrice = serde_json::from_str(json).unwrap();
+        assert_eq!(price.timestamp, Some(1705936800000));
+        assert_eq!(serde_json::to_string(&price).unwrap(), json);
+    }
+
+    #[test]
+    fn test_feed_price_age_and_staleness() {
+        let at = |timestamp| FeedPrice { symbol: "BTC/USD".to_string(), feed_id: String::new(), value: 1.0, timestamp };
+
+        assert_eq!(at(Some(1_000)).age_at(6_000), Some(Duration::from_secs(5)));
+        assert_eq!(at(Some(9_000)).age_at(6_000), Some(Duration::ZERO));
+        assert_eq!(at(None).age(), None);
+
+        assert!(at(Some(0)).is_stale(Duration::from_secs(60)));
+        assert!(!at(None).is_stale(Duration::ZERO), "unknown freshness counts as fresh");
+        let now_ms = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() as i64;
+        assert!(!at(Some(now_ms)).is_stale(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_default_sanity_validator_bounds() {
+        let validate = FeedPrice::default_sanity_validator();
+        let price = |value| FeedPrice { symbol: "BTC/USD".to_string(), feed_id: String::new(), value, timestamp: None };
+
+        assert!(validate(&price(0.0001)).is_ok());
+        assert!(validate(&price(999_999_999_999.0)).is_ok());
+        assert!(validate(&price(0.0)).is_err());
+        assert!(validate(&price(-1.0)).is_err());
+        assert!(validate(&price(1e12)).is_err());
+        assert!(validate(&price(f64::NAN)).is_err());
+    }
+
+    #[test]
+    fn test_price_with_confidence_from_samples() {
+        let price = |value| FeedPrice { symbol: "BTC/USD".to_string(), feed_id: String::new(), value, timestamp: None };
+
+        let single = FeedPriceWithConfidence::from_samples(price(100.0), &[100.0]);
+        assert_eq!((single.confidence, single.confidence_pct), (0.0, 0.0));
+
+        let spread = FeedPriceWithConfidence::from_samples(price(10.0), &[10.0, 12.0, 8.0, 10.0]);
+        assert!((spread.confidence - 2f64.sqrt()).abs() < 1e-12);
+        assert!((spread.confidence_pct - 2f64.sqrt() * 10.0).abs() < 1e-9);
+        assert_eq!(FeedPriceWithConfidence::from_samples(price(0.0), &[0.0, 1.0]).confidence_pct, 0.0);
+    }
+
     // === SurgeConfig tests ===
 
     #[test]
+    #[allow(deprecated)]
     fn test_surge_config_default() {
         let config = SurgeConfig::default();
         assert_eq!(config.ws_url, "wss://surge.switchboard.xyz/ws");
@@ -160,7 +764,161 @@ mod tests {
         assert!(config.auto_reconnect);
         assert_eq!(config.max_reconnect_attempts, 10);
         assert_eq!(config.initial_reconnect_delay_ms, 1000);
+        assert_eq!(config.backoff, BackoffConfig::default());
         assert!(config.api_key.is_empty());
+        assert!(config.no_proxy.is_none());
+        assert_eq!(config.connect_timeout_ms, 5000);
+        assert_eq!(config.read_timeout_ms, 60000);
+        assert_eq!(config.service_name, "i-am-surging");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_initial_reconnect_delay_maps_to_backoff() {
+        assert_eq!(SurgeConfig::default().reconnect_backoff(), BackoffConfig::default());
+
+        let legacy = SurgeConfig { initial_reconnect_delay_ms: 250, ..SurgeConfig::default() };
+        assert_eq!(legacy.reconnect_backoff().initial_ms, 250);
+
+        let backoff = BackoffConfig { initial_ms: 500, ..BackoffConfig::default() };
+        let config = SurgeConfig { backoff, ..SurgeConfig::default() };
+        assert_eq!(config.reconnect_backoff(), backoff);
+    }
+
+    #[test]
+    fn test_surge_config_redacts_api_key() {
+        let config = SurgeConfig { api_key: "sk-secret".to_string(), ..SurgeConfig::default() };
+
+        let shown = config.to_string();
+        assert!(!shown.contains("sk-secret"));
+        assert!(shown.contains("***REDACTED***"));
+        assert_eq!(serde_json::to_value(&config).unwrap()["api_key"], "***REDACTED***");
+
+        let full = config.full_json();
+        assert_eq!(full["api_key"], "sk-secret");
+        assert_eq!(full["ws_url"], config.ws_url);
+    }
+
+    #[test]
+    fn test_surge_config_builder() {
+        let config = SurgeConfig::builder()
+            .api_key("sk-test")
+            .ws_url("wss://example.com/ws")
+            .api_url("https://example.com")
+            .auto_reconnect(false)
+            .max_reconnect_attempts(3)
+            .initial_reconnect_delay_ms(250)
+            .service_name("price-worker")
+            .build()
+            .unwrap();
+        assert_eq!(config.api_key, "sk-test");
+        assert_eq!(config.ws_url, "wss://example.com/ws");
+        assert_eq!(config.api_url, "https://example.com");
+        assert!(!config.auto_reconnect);
+        assert_eq!(config.max_reconnect_attempts, 3);
+        assert_eq!(config.backoff.initial_ms, 250);
+        assert_eq!(config.service_name, "price-worker");
+        assert_eq!(config.read_timeout_ms, SurgeConfig::default().read_timeout_ms);
+    }
+
+    #[test]
+    fn test_surge_config_builder_rejects_invalid() {
+        let valid = || SurgeConfig::builder().api_key("sk-test");
+        let cases = [
+            (SurgeConfig::builder(), "api_key"),
+            (valid().api_key("  "), "api_key"),
+            (valid().ws_url("not a url"), "ws_url"),
+            (valid().api_url(""), "api_url"),
+            (valid().max_reconnect_attempts(0), "max_reconnect_attempts"),
+            (valid().initial_reconnect_delay_ms(99), "initial_reconnect_delay_ms"),
+        ];
+        for (builder, field) in cases {
+            match builder.build() {
+                Err(crate::SurgeError::InvalidConfig(msg)) => assert!(msg.contains(field), "{msg}"),
+                other => panic!("expected InvalidConfig for {field}, got {other:?}"),
+            }
+        }
+        assert!(valid().build().is_ok());
+    }
+
+    #[test]
+    fn test_surge_config_default_is_valid() {
+        assert!(SurgeConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_surge_config_rejects_pong_timeout_not_below_ping_interval() {
+        let config = SurgeConfig {
+            ping_interval_ms: 5000,
+            pong_timeout_ms: 5000,
+            ..SurgeConfig::default()
+        };
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("pong_timeout_ms (5000) must be less than ping_interval_ms (5000)"));
+    }
+
+    // === BackoffConfig tests ===
+
+    fn delays(config: &BackoffConfig, steps: usize) -> Vec<u64> {
+        std::iter::successors(Some(config.initial_ms), |&d| Some(config.next_delay(d)))
+            .take(steps)
+            .collect()
+    }
+
+    #[test]
+    fn test_backoff_default_doubles_up_to_cap() {
+        let config = BackoffConfig::default();
+        assert_eq!(delays(&config, 7), [1000, 2000, 4000, 8000, 16000, 30000, 30000]);
+    }
+
+    #[test]
+    fn test_backoff_follows_multiplier() {
+        let config = BackoffConfig { initial_ms: 100, multiplier: 1.5, cap_ms: 1000, jitter_ms: 0, jitter_factor: 0.0 };
+        assert_eq!(delays(&config, 6), [100, 150, 225, 337, 505, 757]);
+        assert_eq!(config.next_delay(757), 1000);
+    }
+
+    #[test]
+    fn test_backoff_multiplier_below_one_shrinks() {
+        let config = BackoffConfig { initial_ms: 8000, multiplier: 0.5, cap_ms: 30000, jitter_ms: 0, jitter_factor: 0.0 };
+        assert_eq!(delays(&config, 5), [8000, 4000, 2000, 1000, 500]);
+    }
+
+    #[test]
+    fn test_backoff_max_jitter() {
+        assert_eq!(BackoffConfig::default().max_jitter_ms(10000), 2000);
+        assert_eq!(BackoffConfig::constant(2500).max_jitter_ms(2500), 0);
+        let config = BackoffConfig { jitter_ms: 50, jitter_factor: 0.5, ..BackoffConfig::default() };
+        assert_eq!(config.max_jitter_ms(1000), 550);
+    }
+
+    #[test]
+    fn test_surge_config_rejects_jitter_factor_out_of_range() {
+        for jitter_factor in [-0.1, 1.5, f64::NAN] {
+            let config = SurgeConfig {
+                backoff: BackoffConfig { jitter_factor, ..BackoffConfig::default() },
+                ..SurgeConfig::default()
+            };
+            assert!(config.validate().is_err(), "{jitter_factor}");
+        }
+    }
+
+    #[test]
+    fn test_backoff_constant() {
+        let config = BackoffConfig::constant(2500);
+        assert_eq!(delays(&config, 4), [2500, 2500, 2500, 2500]);
+    }
+
+    #[test]
+    fn test_snapshot_event_carries_updates() {
+        let price = FeedPrice { symbol: "BTC/USD".to_string(), feed_id: "abc".to_string(), value: 50000.0, timestamp: None };
+        let event = SurgeEvent::Snapshot { prices: vec![SurgeUpdate::from(price)] };
+
+        assert_eq!(event.updates().len(), 1);
+        let update = event.into_updates().remove(0);
+        assert_eq!(update.event_type.as_deref(), Some("snapshot"));
+        assert_eq!(update.data.feed_id.as_deref(), Some("abc"));
+        assert!(update.data.source_timestamp_ms > 0);
     }
 
     // === SurgeUpdate tests ===
@@ -182,6 +940,13 @@ mod tests {
         assert_eq!(update.data.source_timestamp_ms, 1234567890);
     }
 
+    #[test]
+    fn test_surge_update_confidence_alias() {
+        let json = r#"{"data":{"symbol":"BTC/USD","price":50000.0,"source_ts_ms":1,"confidence":12.5}}"#;
+        let update: SurgeUpdate = serde_json::from_str(json).unwrap();
+        assert_eq!(update.data.conf, Some(12.5));
+    }
+
     #[test]
     fn test_surge_update_with_optional_fields() {
         let json = r#"{
@@ -213,6 +978,57 @@ mod tests {
         assert_eq!(update.data.symbol, "SOL/USD");
     }
 
+    // === Unit conversion tests ===
+
+    fn update_data(price: f64) -> SurgeUpdateData {
+        SurgeUpdateData {
+            symbol: "BTC/USD".to_string(),
+            price,
+            source_timestamp_ms: 0,
+            feed_id: None,
+            signature: None,
+            conf: None,
+        }
+    }
+
+    #[test]
+    fn test_price_in_sats() {
+        assert_eq!(update_data(1.0).price_in_sats(), 100_000_000);
+        assert_eq!(update_data(0.00000001).price_in_sats(), 1);
+        assert_eq!(update_data(100_000.0).price_in_sats(), 10_000_000_000_000);
+    }
+
+    #[test]
+    fn test_price_in_sats_rounding() {
+        // 0.1 * 1e8 is 9999999.999... in f64; rounding must recover the exact value
+        assert_eq!(update_data(0.1).price_in_sats(), 10_000_000);
+        assert_eq!(update_data(0.000000014).price_in_sats(), 1);
+        assert_eq!(update_data(0.000000016).price_in_sats(), 2);
+    }
+
+    #[test]
+    fn test_price_in_sats_boundaries() {
+        assert_eq!(update_data(0.0).price_in_sats(), 0);
+        assert_eq!(update_data(-5.0).price_in_sats(), 0);
+        assert_eq!(update_data(f64::MAX).price_in_sats(), u64::MAX);
+    }
+
+    #[test]
+    fn test_price_in_micro_lamports() {
+        // $150 at SOL = $150 is exactly 1 SOL = 1e15 micro-lamports
+        assert_eq!(update_data(150.0).price_in_micro_lamports(150.0), 1_000_000_000_000_000);
+        assert_eq!(update_data(75.0).price_in_micro_lamports(150.0), 500_000_000_000_000);
+        assert_eq!(update_data(1.0).price_in_micro_lamports(0.0), 0);
+    }
+
+    #[test]
+    fn test_price_in_wei() {
+        // $3000 at ETH = $3000 is exactly 1 ETH = 1e18 wei
+        assert_eq!(update_data(3000.0).price_in_wei(3000.0), 1_000_000_000_000_000_000);
+        assert_eq!(update_data(1500.0).price_in_wei(3000.0), 500_000_000_000_000_000);
+        assert_eq!(update_data(1.0).price_in_wei(-1.0), 0);
+    }
+
     // === SubscriptionRequest tests ===
 
     #[test]
@@ -281,6 +1097,23 @@ mod tests {
         assert!(format!("{:?}", event).contains("test error"));
     }
 
+    #[test]
+    fn test_surge_event_into_updates() {
+        let update = SurgeUpdate { event_type: None, data: update_data(100.0) };
+
+        assert_eq!(SurgeEvent::PriceUpdate(update.clone()).into_updates().len(), 1);
+        assert_eq!(SurgeEvent::PriceBatch(vec![update.clone(), update]).into_updates().len(), 2);
+        assert!(SurgeEvent::Connected.into_updates().is_empty());
+    }
+
+    #[test]
+    fn test_surge_event_reconnect_failed() {
+        let event = SurgeEvent::ReconnectFailed { attempts: 10, reason: "Connection refused".to_string() };
+        let debug = format!("{:?}", event);
+        assert!(debug.contains("10"));
+        assert!(debug.contains("Connection refused"));
+    }
+
     #[test]
     fn test_surge_event_reconnecting() {
         let event = SurgeEvent::Reconnecting { attempt: 3, delay_ms: 5000 };
@@ -288,4 +1121,112 @@ mod tests {
         assert!(debug.contains("3"));
         assert!(debug.contains("5000"));
     }
+
+    // === Signature verification tests ===
+
+    #[cfg(feature = "solana")]
+    fn signed_update(price: f64) -> (SurgeUpdate, String) {
+        use ed25519_dalek::{Signer, SigningKey};
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let mut update = SurgeUpdate { event_type: None, data: update_data(price) };
+        update.data.source_timestamp_ms = 1705936800000;
+        update.data.signature = Some(hex::encode(key.sign(&update.signed_message()).to_bytes()));
+        (update, bs58::encode(key.verifying_key().to_bytes()).into_string())
+    }
+
+    #[cfg(feature = "solana")]
+    #[test]
+    fn test_verify_signature_valid() {
+        let (update, pubkey) = signed_update(89846.94);
+        assert!(update.verify_signature(&pubkey).unwrap());
+    }
+
+    #[cfg(feature = "solana")]
+    #[test]
+    fn test_verify_signature_rejects_tampered_price() {
+        let (mut update, pubkey) = signed_update(89846.94);
+        update.data.price = 1.0;
+        assert!(!update.verify_signature(&pubkey).unwrap());
+    }
+
+    #[cfg(feature = "solana")]
+    #[test]
+    fn test_verify_signature_unsigned_is_false() {
+        let (mut update, pubkey) = signed_update(1.0);
+        update.data.signature = None;
+        assert!(!update.verify_signature(&pubkey).unwrap());
+    }
+
+    #[cfg(feature = "solana")]
+    #[test]
+    fn test_verify_signature_malformed_inputs() {
+        let (mut update, pubkey) = signed_update(1.0);
+        assert!(update.verify_signature("not-base58-0OIl").is_err());
+        update.data.signature = Some("zz".to_string());
+        assert!(update.verify_signature(&pubkey).is_err());
+    }
+
+    // === Decimal tests ===
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_value_decimal_round_trips() {
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+
+        let value = Decimal::from_str("89846.94").unwrap();
+        let price = FeedPrice::new_decimal("BTC/USD", "feed", value);
+        assert_eq!(price.value, 89846.94);
+        assert_eq!(price.value_decimal(), value);
+        assert_eq!(update_data(0.1).price_decimal(), Decimal::from_str("0.1").unwrap());
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_value_decimal_non_finite_is_zero() {
+        let price = FeedPrice { symbol: "X".to_string(), feed_id: String::new(), value: f64::NAN, timestamp: None };
+        assert!(price.value_decimal().is_zero());
+    }
+
+    // === Pyth conversion tests ===
+
+    #[cfg(feature = "pyth-compat")]
+    #[test]
+    fn test_pyth_attestation_roundtrip() {
+        let mut data = update_data(89846.94);
+        data.source_timestamp_ms = 1705936800123;
+        let update = SurgeUpdate { event_type: None, data };
+
+        let attestation = update.to_pyth_price_attestation();
+        assert_eq!(attestation.price, 8_984_694_000_000);
+        assert_eq!(attestation.expo, -8);
+        assert_eq!(attestation.publish_time, 1705936800);
+        assert!((attestation.price_f64() - 89846.94).abs() < 1e-8);
+    }
+
+    #[cfg(feature = "pyth-compat")]
+    #[test]
+    fn test_pyth_attestation_default_conf() {
+        let update = SurgeUpdate { event_type: None, data: update_data(100.0) };
+        let attestation = update.to_pyth_price_attestation();
+        assert_eq!(attestation.conf, 10_000_000);
+        assert!((attestation.conf_f64() - 0.1).abs() < 1e-12);
+    }
+
+    #[cfg(feature = "pyth-compat")]
+    #[test]
+    fn test_pyth_attestation_uses_reported_conf() {
+        let mut data = update_data(100.0);
+        data.conf = Some(0.25);
+        let attestation = SurgeUpdate { event_type: None, data }.to_pyth_price_attestation();
+        assert_eq!(attestation.conf, 25_000_000);
+    }
+
+    #[cfg(feature = "pyth-compat")]
+    #[test]
+    fn test_pyth_attestation_serde_roundtrip() {
+        let attestation = SurgeUpdate { event_type: None, data: update_data(1.5) }.to_pyth_price_attestation();
+        let json = serde_json::to_string(&attestation).unwrap();
+        assert_eq!(serde_json::from_str::<PythPriceAttestation>(&json).unwrap(), attestation);
+    }
 }
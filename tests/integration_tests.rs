@@ -204,6 +204,23 @@ async fn test_client_get_multiple_prices() {
     assert_eq!(prices.len(), 2);
 }
 
+#[tokio::test]
+async fn test_client_get_prices_as_map() {
+    let client = SurgeClient::new().unwrap();
+    let prices = client.get_prices_as_map(&["BTC/USD", "ETH/USD"]).await.unwrap();
+    assert_eq!(prices.len(), 2);
+    assert_eq!(prices["BTC/USD"].symbol, "BTC/USD");
+    assert_eq!(prices["ETH/USD"].symbol, "ETH/USD");
+}
+
+#[tokio::test]
+async fn test_client_get_prices_as_map_concurrent() {
+    let client = SurgeClient::new().unwrap();
+    let prices = client.get_prices_as_map_concurrent(&["btc", "eth"], 2).await.unwrap();
+    assert!(prices.contains_key("BTC/USD"));
+    assert!(prices.contains_key("ETH/USD"));
+}
+
 // =============================================================================
 // FeedLoader Tests
 // =============================================================================
@@ -215,6 +232,14 @@ fn test_feed_loader_and_client_consistency() {
     assert_eq!(loader.get_all_symbols(), client.get_all_symbols());
 }
 
+#[test]
+fn test_feed_loader_from_json_str() {
+    let loader = FeedLoader::from_json_str(r#"{"BTC/USD": "abc123", "SOL/USD": "def456"}"#).unwrap();
+    assert_eq!(loader.len(), 2);
+    assert_eq!(loader.get_feed_id("btc").unwrap(), "abc123");
+    assert_eq!(FeedLoader::from_json_str(&loader.to_json_string().unwrap()).unwrap().len(), 2);
+}
+
 #[test]
 fn test_feed_loader_len() {
     let loader = FeedLoader::load_default().unwrap();
@@ -232,6 +257,7 @@ fn test_feed_price_display() {
         symbol: "BTC/USD".to_string(),
         feed_id: "abc".to_string(),
         value: 50000.123456,
+        timestamp: None,
     };
     let display = format!("{}", price);
     assert!(display.contains("BTC/USD"));
@@ -244,6 +270,7 @@ fn test_feed_price_json_roundtrip() {
         symbol: "ETH/USD".to_string(),
         feed_id: "def".to_string(),
         value: 3000.50,
+        timestamp: None,
     };
     let json = serde_json::to_string(&price).unwrap();
     let parsed: FeedPrice = serde_json::from_str(&json).unwrap();
@@ -259,7 +286,7 @@ fn test_feed_price_json_roundtrip() {
 #[test]
 fn test_surge_error_feed_not_found_status_code() {
     use axum::http::StatusCode;
-    let err = SurgeError::FeedNotFound("TEST/COIN".to_string());
+    let err = SurgeError::FeedNotFound("TEST/COIN".to_string(), Vec::new());
     assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
 }
 
@@ -270,14 +297,29 @@ fn test_surge_error_api_error_status_code() {
     assert_eq!(err.status_code(), StatusCode::BAD_GATEWAY);
 }
 
+#[test]
+fn test_surge_error_invalid_api_key_status_code() {
+    use axum::http::StatusCode;
+    let err = SurgeError::InvalidApiKey("SURGE_API_KEY not set".to_string());
+    assert_eq!(err.status_code(), StatusCode::UNAUTHORIZED);
+    assert!(!err.is_retryable());
+    assert_eq!(err.to_string(), "Invalid API key: SURGE_API_KEY not set");
+}
+
 #[test]
 fn test_surge_error_display() {
-    let err = SurgeError::FeedNotFound("FAKE/COIN".to_string());
+    let err = SurgeError::FeedNotFound("FAKE/COIN".to_string(), Vec::new());
     let display = format!("{}", err);
     assert!(display.contains("Feed not found"));
     assert!(display.contains("FAKE/COIN"));
 }
 
+#[test]
+fn test_surge_error_feed_not_found_suggestions() {
+    let err = SurgeError::FeedNotFound("BTCUSD".to_string(), vec!["BTC/USD".to_string(), "BTC/USDT".to_string()]);
+    assert_eq!(err.to_string(), "Feed not found for 'BTCUSD'. Did you mean: BTC/USD, BTC/USDT?");
+}
+
 #[test]
 fn test_surge_error_api_error_display() {
     let err = SurgeError::ApiError("Connection timeout".to_string());
@@ -2,32 +2,31 @@
 //!
 //! These tests verify the REST API endpoints work correctly.
 //!
-//! Note: These tests run with authentication DISABLED (SURGE_API_KEY unset).
-//! Auth middleware behavior is tested via unit tests in auth.rs.
+//! Note: These tests inject an `AppState` with authentication DISABLED unless
+//! a test explicitly configures an API key.
 
 use axum::{body::Body, http::{Request, StatusCode}, Router};
-use i_am_surging::server::{app::ServerConfig, create_app};
+use i_am_surging::server::{
+    app::ServerConfig,
+    create_app,
+    routes::{AppState, AppStateConfig},
+};
 use serde_json::Value;
-use std::sync::Once;
 use tower::ServiceExt;
 
-// Ensure env is clean before all tests
-static INIT: Once = Once::new();
-
-fn ensure_no_auth() {
-    INIT.call_once(|| {
-        std::env::remove_var("SURGE_API_KEY");
-    });
-}
-
 // =============================================================================
 // Test Helpers
 // =============================================================================
 
 /// Create a test app instance (no auth)
 fn create_test_app() -> Router {
-    ensure_no_auth();
-    create_app().expect("Failed to create app")
+    create_test_app_with_config(AppStateConfig::default())
+}
+
+/// Create a test app instance from an explicit state config
+fn create_test_app_with_config(config: AppStateConfig) -> Router {
+    let state = AppState::new_with_config(config).expect("Failed to create state");
+    create_app(Some(state)).expect("Failed to create app")
 }
 
 /// Parse JSON response body
@@ -97,8 +96,109 @@ async fn test_ready_endpoint_returns_ready_status() {
     assert_eq!(json["status"], "ready");
 }
 
-// Note: Authentication is tested via unit tests in src/server/auth.rs
-// Integration tests with env var changes don't work reliably in parallel
+// =============================================================================
+// Authentication Tests
+// =============================================================================
+
+fn create_auth_test_app() -> Router {
+    create_test_app_with_config(AppStateConfig {
+        api_key: Some("test-key".to_string()),
+        ..AppStateConfig::default()
+    })
+}
+
+#[tokio::test]
+async fn test_auth_missing_header_returns_401() {
+    let app = create_auth_test_app();
+
+    let response = app
+        .oneshot(Request::builder().uri("/v1/symbols").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_auth_wrong_key_returns_401() {
+    let app = create_auth_test_app();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/symbols")
+                .header("Authorization", "Bearer wrong-key")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_auth_valid_key_returns_200() {
+    let app = create_auth_test_app();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/symbols")
+                .header("Authorization", "Bearer test-key")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_auth_not_required_for_public_routes() {
+    let app = create_auth_test_app();
+
+    let response = app
+        .oneshot(Request::builder().uri("/health").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[cfg(feature = "solana")]
+#[tokio::test]
+async fn test_auth_accepts_allowed_wallet_signature() {
+    use ed25519_dalek::{Signer, SigningKey};
+    use i_am_surging::server::auth::{wallet_auth_message, SIGNATURE_HEADER, TIMESTAMP_HEADER, WALLET_ADDRESS_HEADER};
+
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+    let wallet = bs58::encode(key.verifying_key().to_bytes()).into_string();
+    let app = create_test_app_with_config(AppStateConfig {
+        api_key: Some("test-key".to_string()),
+        allowed_wallets: vec![wallet.clone()],
+        ..AppStateConfig::default()
+    });
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64;
+    let signed_request = |path: &str, signed_path: &str| {
+        let signature = key.sign(&wallet_auth_message("GET", signed_path, now));
+        Request::builder()
+            .uri(path)
+            .header(WALLET_ADDRESS_HEADER, &wallet)
+            .header(TIMESTAMP_HEADER, now.to_string())
+            .header(SIGNATURE_HEADER, bs58::encode(signature.to_bytes()).into_string())
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(signed_request("/v1/symbols", "/v1/symbols")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // A signature only covers the path it was made for
+    let response = app.oneshot(signed_request("/v1/symbols", "/v1/prices/btc")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
 
 // =============================================================================
 // Single Price Endpoint Tests
@@ -250,6 +350,22 @@ async fn test_get_price_invalid_symbol_returns_error_message() {
     assert!(json["error"].as_str().unwrap().contains("not found"));
 }
 
+#[tokio::test]
+async fn test_get_price_unknown_symbol_suggests_matches() {
+    let app = create_test_app();
+
+    let response = app
+        .oneshot(Request::builder().uri("/v1/prices/BTCUSD").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let json = parse_json_body(response).await;
+    let suggestions = json["did_you_mean"].as_array().unwrap();
+    assert!(suggestions.len() <= 3);
+    assert_eq!(suggestions[0], "BTC/USD");
+}
+
 // =============================================================================
 // Multiple Prices Endpoint Tests
 // =============================================================================
@@ -336,6 +452,71 @@ async fn test_get_prices_single_symbol() {
     assert_eq!(data[0]["symbol"], "BTC/USD");
 }
 
+#[tokio::test]
+async fn test_get_prices_rejects_too_many_symbols() {
+    let app = create_test_app();
+    let symbols: Vec<String> = (0..101).map(|i| format!("COIN{}", i)).collect();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/v1/prices?symbols={}", symbols.join(",")))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let json = parse_json_body(response).await;
+    assert!(json["error"].as_str().unwrap().contains("At most 100 symbols"));
+}
+
+fn post_prices_request(body: Value) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri("/v1/prices")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_post_prices_returns_all_symbols() {
+    let app = create_test_app();
+
+    let response = app
+        .oneshot(post_prices_request(serde_json::json!({ "symbols": ["BTC/USD", "eth"] })))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = parse_json_body(response).await;
+    let symbols: Vec<&str> = json["data"].as_array().unwrap().iter().map(|p| p["symbol"].as_str().unwrap()).collect();
+    assert_eq!(symbols, ["BTC/USD", "ETH/USD"]);
+}
+
+#[tokio::test]
+async fn test_post_prices_rejects_empty_symbols() {
+    let app = create_test_app();
+
+    let response = app.oneshot(post_prices_request(serde_json::json!({ "symbols": [] }))).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_post_prices_rejects_too_many_symbols() {
+    let app = create_test_app();
+    let symbols: Vec<String> = (0..101).map(|i| format!("COIN{}/USD", i)).collect();
+
+    let response = app.oneshot(post_prices_request(serde_json::json!({ "symbols": symbols }))).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let json = parse_json_body(response).await;
+    assert!(json["error"].as_str().unwrap().contains("At most 100 symbols"));
+}
+
 // =============================================================================
 // Symbols Listing Endpoint Tests
 // =============================================================================
@@ -401,6 +582,105 @@ async fn test_list_symbols_contains_major_symbols() {
     assert!(symbol_strs.contains(&"SOL/USD"));
 }
 
+#[tokio::test]
+async fn test_list_symbols_fuzzy() {
+    let app = create_test_app();
+
+    let response = app
+        .oneshot(Request::builder().uri("/v1/symbols?fuzzy=btcusd").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = parse_json_body(response).await;
+    let matches = json["data"]["matches"].as_array().unwrap();
+    assert_eq!(matches[0]["symbol"], "BTC/USD");
+    assert!(matches.iter().all(|m| m["score"].as_f64().unwrap() > 0.6));
+}
+
+const TEST_FEED_ID: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+/// Send GET /v1/symbols with an optional `If-None-Match` header
+async fn get_symbols(app: Router, if_none_match: Option<&str>) -> axum::response::Response {
+    let mut request = Request::builder().uri("/v1/symbols");
+    if let Some(etag) = if_none_match {
+        request = request.header("If-None-Match", etag);
+    }
+    app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+}
+
+fn etag_of(response: &axum::response::Response) -> String {
+    response.headers()["etag"].to_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_list_symbols_returns_304_for_matching_etag() {
+    let app = create_test_app();
+
+    let first = get_symbols(app.clone(), None).await;
+    assert_eq!(first.status(), StatusCode::OK);
+    let etag = etag_of(&first);
+
+    let second = get_symbols(app, Some(&etag)).await;
+    assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(etag_of(&second), etag);
+    let body = axum::body::to_bytes(second.into_body(), usize::MAX).await.unwrap();
+    assert!(body.is_empty());
+}
+
+#[tokio::test]
+async fn test_list_symbols_etag_changes_after_reload() {
+    let path = std::env::temp_dir().join(format!("surge_etag_{}.json", std::process::id()));
+    std::fs::write(&path, format!(r#"{{"BTC/USD": "{}"}}"#, TEST_FEED_ID)).unwrap();
+    let state = AppState::new_with_config(AppStateConfig {
+        feeds_path: Some(path.clone()),
+        ..AppStateConfig::default()
+    })
+    .unwrap();
+    let app = create_app(Some(state.clone())).unwrap();
+
+    let etag = etag_of(&get_symbols(app.clone(), None).await);
+
+    std::fs::write(&path, format!(r#"{{"BTC/USD": "{0}", "ETH/USD": "{0}"}}"#, TEST_FEED_ID)).unwrap();
+    state.reload_feeds().unwrap();
+    std::fs::remove_file(&path).ok();
+
+    let response = get_symbols(app, Some(&etag)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(etag_of(&response), etag);
+    let json = parse_json_body(response).await;
+    assert_eq!(json["data"]["count"], 2);
+}
+
+#[tokio::test]
+async fn test_get_price_sees_symbol_added_by_reload() {
+    let feeds = i_am_surging::FeedLoader::load_default().unwrap();
+    let (btc, eth) = (feeds.get_feed_id("BTC/USD").unwrap(), feeds.get_feed_id("ETH/USD").unwrap());
+    let path = std::env::temp_dir().join(format!("surge_reload_{}.json", std::process::id()));
+    std::fs::write(&path, format!(r#"{{"BTC/USD": "{}"}}"#, btc)).unwrap();
+    let state = AppState::new_with_config(AppStateConfig {
+        feeds_path: Some(path.clone()),
+        ..AppStateConfig::default()
+    })
+    .unwrap();
+    let app = create_app(Some(state.clone())).unwrap();
+    // Not in the default registry, so only the reloaded file can resolve it
+    let get_ether = || Request::builder().uri("/v1/prices/ether").body(Body::empty()).unwrap();
+
+    let response = app.clone().oneshot(get_ether()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    std::fs::write(&path, format!(r#"{{"BTC/USD": "{}", "ETHER/USD": "{}"}}"#, btc, eth)).unwrap();
+    state.reload_feeds().unwrap();
+    std::fs::remove_file(&path).ok();
+
+    let response = app.oneshot(get_ether()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = parse_json_body(response).await;
+    assert_eq!(json["data"]["symbol"], "ETHER/USD");
+    assert_eq!(json["data"]["feed_id"], eth);
+}
+
 #[tokio::test]
 async fn test_list_symbols_with_filter() {
     let app = create_test_app();
@@ -467,6 +747,347 @@ async fn test_list_symbols_filter_no_matches() {
     assert_eq!(json["data"]["count"], 0);
 }
 
+async fn get_symbols_page(uri: &str) -> (StatusCode, Value) {
+    let response = create_test_app()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, parse_json_body(response).await)
+}
+
+#[tokio::test]
+async fn test_list_symbols_pagination_boundaries() {
+    let (_, all) = get_symbols_page("/v1/symbols?filter=sol").await;
+    let all: Vec<Value> = all["data"]["symbols"].as_array().unwrap().clone();
+    assert!(all.len() > 2, "need at least 3 SOL symbols");
+
+    let (status, first) = get_symbols_page("/v1/symbols?filter=sol&page=1&page_size=2").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(first["data"]["symbols"].as_array().unwrap(), &all[..2]);
+    assert_eq!(first["data"]["count"], 2);
+    assert_eq!(first["data"]["total"], all.len());
+    assert_eq!(first["data"]["page"], 1);
+    assert_eq!(first["data"]["page_size"], 2);
+
+    let last_page = all.len().div_ceil(2);
+    let (_, last) = get_symbols_page(&format!("/v1/symbols?filter=sol&page={}&page_size=2", last_page)).await;
+    assert_eq!(last["data"]["symbols"].as_array().unwrap(), &all[(last_page - 1) * 2..]);
+}
+
+#[tokio::test]
+async fn test_list_symbols_page_past_end_is_empty() {
+    let (status, json) = get_symbols_page("/v1/symbols?page=10000").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(json["data"]["symbols"].as_array().unwrap().is_empty());
+    assert_eq!(json["data"]["count"], 0);
+    assert!(json["data"]["total"].as_u64().unwrap() > 2000);
+    assert_eq!(json["data"]["page_size"], 100);
+}
+
+#[tokio::test]
+async fn test_list_symbols_page_size_is_capped() {
+    let (_, json) = get_symbols_page("/v1/symbols?page_size=10000").await;
+    assert_eq!(json["data"]["page_size"], 500);
+    assert_eq!(json["data"]["count"], 500);
+}
+
+#[tokio::test]
+async fn test_list_symbols_rejects_zero_page() {
+    for uri in ["/v1/symbols?page=0", "/v1/symbols?page_size=0"] {
+        let (status, json) = get_symbols_page(uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        assert_eq!(json["success"], false);
+    }
+}
+
+// =============================================================================
+// Analytics Endpoint Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_zscore_insufficient_history_returns_422() {
+    let state = AppState::new_with_config(AppStateConfig::default()).unwrap();
+    for i in 0..10 {
+        state.history.record("BTC/USD", 100.0 + i as f64);
+    }
+    let app = create_app(Some(state)).unwrap();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/prices/btc/zscore?window_m=30")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let json = parse_json_body(response).await;
+    assert_eq!(json["success"], false);
+    assert!(json["error"].as_str().unwrap().contains("have 10"));
+}
+
+/// Send a GET to an app whose price history has been seeded
+async fn get_with_history(samples: &[(&str, f64, i64)], uri: &str) -> axum::response::Response {
+    let state = AppState::new_with_config(AppStateConfig::default()).unwrap();
+    for &(symbol, price, timestamp_ms) in samples {
+        state.history.record_at(symbol, price, timestamp_ms);
+    }
+    create_app(Some(state))
+        .unwrap()
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_window_overflow_returns_400() {
+    let max = u64::MAX;
+    for uri in [
+        format!("/v1/prices/btc/zscore?window_m={max}"),
+        format!("/v1/prices/btc/regime?window_m={max}"),
+        format!("/v1/prices/heatmap?window_m={max}"),
+    ] {
+        let response = get_with_history(&[], &uri).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn test_arbitrage_matrix_rejects_single_symbol() {
+    let response = get_with_history(&[], "/v1/prices/arbitrage-matrix?symbols=BTC").await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_arbitrage_matrix_rejects_too_many_symbols() {
+    let symbols = ["BTC"; 11].join(",");
+    let uri = format!("/v1/prices/arbitrage-matrix?symbols={}", symbols);
+    let response = get_with_history(&[], &uri).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_arbitrage_matrix_unknown_symbol_returns_404() {
+    let response = get_with_history(&[], "/v1/prices/arbitrage-matrix?symbols=NOTACOIN123,BTC").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_arbitrage_matrix_is_reciprocal() {
+    let response = get_with_history(&[], "/v1/prices/arbitrage-matrix?symbols=BTC,ETH,SOL").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = parse_json_body(response).await;
+    let matrix: Vec<Vec<f64>> = serde_json::from_value(json["data"]["matrix"].clone()).unwrap();
+    assert_eq!(matrix.len(), 3);
+    for (i, row) in matrix.iter().enumerate() {
+        assert_eq!(row[i], 1.0);
+        for (j, rate) in row.iter().enumerate() {
+            assert!((rate * matrix[j][i] - 1.0).abs() < 1e-9);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_candle_open_close_from_first_and_last_ticks() {
+    let samples = [
+        ("BTC/USD", 100.0, 1_000),
+        ("BTC/USD", 110.0, 2_000),
+        ("BTC/USD", 90.0, 3_000),
+        ("BTC/USD", 105.0, 4_000),
+    ];
+    let response = get_with_history(&samples, "/v1/prices/btc/candle?since_ms=0&until_ms=5000").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = parse_json_body(response).await;
+    assert_eq!(json["data"]["symbol"], "BTC/USD");
+    assert_eq!(json["data"]["open"], 100.0);
+    assert_eq!(json["data"]["high"], 110.0);
+    assert_eq!(json["data"]["low"], 90.0);
+    assert_eq!(json["data"]["close"], 105.0);
+    assert_eq!(json["data"]["tick_count"], 4);
+}
+
+#[tokio::test]
+async fn test_candle_respects_range_bounds() {
+    let samples = [("BTC/USD", 100.0, 1_000), ("BTC/USD", 200.0, 2_000), ("BTC/USD", 300.0, 3_000)];
+    let response = get_with_history(&samples, "/v1/prices/btc/candle?since_ms=1500&until_ms=2500").await;
+    let json = parse_json_body(response).await;
+
+    assert_eq!(json["data"]["open"], 200.0);
+    assert_eq!(json["data"]["close"], 200.0);
+    assert_eq!(json["data"]["tick_count"], 1);
+}
+
+#[tokio::test]
+async fn test_candle_invalid_range_returns_422() {
+    let response = get_with_history(&[], "/v1/prices/btc/candle?since_ms=2000&until_ms=1000").await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_candle_no_data_returns_422() {
+    let response = get_with_history(&[], "/v1/prices/btc/candle?since_ms=1000&until_ms=2000").await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_moving_average_sma_and_ema() {
+    let samples: Vec<(&str, f64, i64)> = (1..=5).map(|i| ("BTC/USD", i as f64, i * 1000)).collect();
+
+    let response = get_with_history(&samples, "/v1/prices/btc/moving-average?period=3").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = parse_json_body(response).await;
+    assert_eq!(json["data"]["ma_type"], "sma");
+    assert_eq!(json["data"]["value"], 4.0);
+    assert_eq!(json["data"]["current_price"], 5.0);
+    assert_eq!(json["data"]["current_is_above_ma"], true);
+
+    let response = get_with_history(&samples, "/v1/prices/btc/moving-average?period=3&type=ema").await;
+    let json = parse_json_body(response).await;
+    assert_eq!(json["data"]["ma_type"], "ema");
+    assert_eq!(json["data"]["value"], 4.0625);
+    assert_eq!(json["data"]["sample_count"], 5);
+}
+
+#[tokio::test]
+async fn test_moving_average_insufficient_history_returns_422() {
+    let samples = [("BTC/USD", 1.0, 1000), ("BTC/USD", 2.0, 2000)];
+    let response = get_with_history(&samples, "/v1/prices/btc/moving-average?period=3").await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_bollinger_bands_from_history() {
+    let prices = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+    let samples: Vec<(&str, f64, i64)> = prices.iter().zip(1..).map(|(&p, i)| ("BTC/USD", p, i * 1000)).collect();
+
+    let response = get_with_history(&samples, "/v1/prices/btc/bollinger?period=8&std_devs=2").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = parse_json_body(response).await;
+    assert_eq!(json["data"]["upper_band"], 9.0);
+    assert_eq!(json["data"]["middle_band"], 5.0);
+    assert_eq!(json["data"]["lower_band"], 1.0);
+    assert_eq!(json["data"]["current_price"], 9.0);
+    assert_eq!(json["data"]["percent_b"], 1.0);
+    assert_eq!(json["data"]["bandwidth_pct"], 160.0);
+}
+
+#[tokio::test]
+async fn test_bollinger_insufficient_history_returns_422() {
+    let response = get_with_history(&[("BTC/USD", 1.0, 1000)], "/v1/prices/btc/bollinger").await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_preload_cache_unknown_symbols_fails() {
+    let state = AppState::new_with_config(AppStateConfig::default()).unwrap();
+    assert!(state.preload_cache(&["NOTACOIN123"]).await.is_err());
+    assert!(state.history.all("NOTACOIN123/USD").is_empty());
+}
+
+#[tokio::test]
+async fn test_regime_trending_from_history() {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64;
+    let samples: Vec<(&str, f64, i64)> = (0..20).map(|i| ("BTC/USD", 100.0 + i as f64, now - (20 - i) * 1000)).collect();
+
+    let response = get_with_history(&samples, "/v1/prices/btc/regime?window_m=5").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = parse_json_body(response).await;
+    assert_eq!(json["data"]["regime"], "trending");
+    assert_eq!(json["data"]["direction"], "up");
+    assert_eq!(json["data"]["sample_count"], 20);
+}
+
+#[tokio::test]
+async fn test_regime_without_history_is_insufficient() {
+    let response = get_with_history(&[], "/v1/prices/btc/regime").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = parse_json_body(response).await;
+    assert_eq!(json["data"]["regime"], "insufficient");
+}
+
+#[tokio::test]
+async fn test_heatmap_rejects_too_many_symbols() {
+    let symbols: Vec<String> = (0..201).map(|i| format!("SYM{}", i)).collect();
+    let uri = format!("/v1/prices/heatmap?symbols={}", symbols.join(","));
+    let response = get_with_history(&[], &uri).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_heatmap_skips_symbols_without_prices() {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64;
+    // In history but unknown to the feed registry, so no current price
+    let samples = [("NOTACOIN123/USD", 1.0, now - 60_000), ("NOTACOIN123/USD", 2.0, now - 1_000)];
+
+    let response = get_with_history(&samples, "/v1/prices/heatmap").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = parse_json_body(response).await;
+    assert!(json["data"]["items"].as_array().unwrap().is_empty());
+    assert!(json["data"]["computed_at_ms"].as_i64().unwrap() >= now);
+}
+
+// =============================================================================
+// Debug Endpoint Tests
+// =============================================================================
+
+async fn get_debug_feed(debug_endpoints: bool) -> StatusCode {
+    let app = create_test_app_with_config(AppStateConfig {
+        debug_endpoints,
+        ..AppStateConfig::default()
+    });
+    app.oneshot(Request::builder().uri("/v1/debug/feed/not-a-feed-id").body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+async fn test_debug_feed_absent_by_default() {
+    assert_eq!(get_debug_feed(false).await, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_debug_feed_present_when_enabled() {
+    // Route is mounted; the malformed ID is rejected before any upstream call
+    assert_eq!(get_debug_feed(true).await, StatusCode::BAD_REQUEST);
+}
+
+// =============================================================================
+// Admin Endpoint Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_admin_throughput_returns_counters() {
+    let app = create_test_app();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/admin/throughput")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = parse_json_body(response).await;
+    assert_eq!(json["data"]["events_total"], 0);
+    assert!(json["data"]["events_per_second"].is_number());
+    assert!(json["data"]["uptime_secs"].is_u64());
+}
+
 // =============================================================================
 // Server Configuration Tests
 // =============================================================================
@@ -531,6 +1152,195 @@ async fn test_error_response_format() {
     assert!(json["error"].is_string());
 }
 
+// =============================================================================
+// Rate Limit Tests
+// =============================================================================
+
+fn symbols_request(api_key: &str) -> Request<Body> {
+    Request::builder()
+        .uri("/v1/symbols")
+        .header("Authorization", format!("Bearer {}", api_key))
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_rate_limit_returns_429_with_retry_after() {
+    let app = create_test_app_with_config(AppStateConfig { rate_limit_rpm: 2, ..AppStateConfig::default() });
+
+    for _ in 0..2 {
+        let response = app.clone().oneshot(symbols_request("any")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = app.clone().oneshot(symbols_request("any")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers()["retry-after"], "30");
+    let json = parse_json_body(response).await;
+    assert_eq!(json["success"], false);
+
+    // Public routes are not limited
+    let response = app
+        .oneshot(Request::builder().uri("/health").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_rate_limit_is_per_api_key() {
+    let app = create_test_app_with_config(AppStateConfig {
+        api_key: Some("test-key".to_string()),
+        rate_limit_rpm: 1,
+        ..AppStateConfig::default()
+    });
+
+    let response = app.clone().oneshot(symbols_request("test-key")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app.clone().oneshot(symbols_request("test-key")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Rejected by auth before it can use up a bucket
+    let response = app.oneshot(symbols_request("wrong-key")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+// =============================================================================
+// Server-Sent Events Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_sse_stream_returns_event_stream() {
+    let app = create_test_app();
+
+    let response = app
+        .oneshot(Request::builder().uri("/v1/stream/sse?symbols=btc,eth").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+}
+
+#[tokio::test]
+async fn test_sse_stream_without_symbols_returns_400() {
+    let app = create_test_app();
+
+    let response = app
+        .oneshot(Request::builder().uri("/v1/stream/sse?symbols=,").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_sse_stream_requires_auth() {
+    let app = create_auth_test_app();
+
+    let response = app
+        .oneshot(Request::builder().uri("/v1/stream/sse?symbols=btc").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+// =============================================================================
+// Request ID Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_request_id_is_echoed() {
+    let app = create_test_app();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/prices/NOTACOIN")
+                .header("x-request-id", "trace-123")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.headers()["x-request-id"], "trace-123");
+    let json = parse_json_body(response).await;
+    assert_eq!(json["request_id"], "trace-123");
+}
+
+#[tokio::test]
+async fn test_request_id_is_generated() {
+    let app = create_test_app();
+
+    let response = app
+        .oneshot(Request::builder().uri("/health").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    let id = response.headers()["x-request-id"].to_str().unwrap();
+    assert_eq!(id.len(), 36, "should be a UUID: {}", id);
+}
+
+// =============================================================================
+// V2 API Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_v2_price_is_decimal_string() {
+    let app = create_test_app();
+
+    let response = app
+        .oneshot(Request::builder().uri("/v2/prices/btc").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = parse_json_body(response).await;
+    assert_eq!(json["data"]["symbol"], "BTC/USD");
+    assert_eq!(json["data"]["precision"], 6);
+    let price = json["data"]["price"].as_str().expect("price should be a string");
+    assert_eq!(price.split_once('.').unwrap().1.len(), 6);
+}
+
+#[tokio::test]
+async fn test_v2_unknown_symbol_returns_404_with_suggestions() {
+    let app = create_test_app();
+
+    let response = app
+        .oneshot(Request::builder().uri("/v2/prices/BTCUSD").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let json = parse_json_body(response).await;
+    assert!(json["did_you_mean"].as_array().unwrap().iter().any(|s| s == "BTC/USD"));
+}
+
+#[tokio::test]
+async fn test_v2_prices_requires_symbols() {
+    let app = create_test_app();
+
+    let response = app
+        .oneshot(Request::builder().uri("/v2/prices?symbols=").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_v2_requires_auth() {
+    let app = create_auth_test_app();
+
+    let response = app
+        .oneshot(Request::builder().uri("/v2/prices/btc").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
 // =============================================================================
 // 404 Tests
 // =============================================================================